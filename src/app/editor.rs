@@ -0,0 +1,234 @@
+/// Multi-line text editor used to hand-merge a single conflict hunk
+#[derive(Debug, Clone, PartialEq)]
+pub struct HunkEditor {
+    /// Index of the conflict being edited
+    pub conflict_index: usize,
+    /// Buffer contents, one entry per line
+    pub lines: Vec<String>,
+    /// Cursor line
+    pub cursor_row: usize,
+    /// Cursor position within the line (in characters)
+    pub cursor_col: usize,
+}
+
+impl HunkEditor {
+    /// Create an editor for a conflict, seeded with the given text
+    pub fn new(conflict_index: usize, text: &str) -> Self {
+        let mut lines: Vec<String> = text
+            .lines()
+            .map(|line| line.strip_suffix('\r').unwrap_or(line).to_string())
+            .collect();
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+
+        Self {
+            conflict_index,
+            lines,
+            cursor_row: 0,
+            cursor_col: 0,
+        }
+    }
+
+    /// Get the edited text
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    /// Byte offset of the cursor in the current line
+    fn byte_offset(&self) -> usize {
+        let line = &self.lines[self.cursor_row];
+        line.char_indices()
+            .nth(self.cursor_col)
+            .map(|(i, _)| i)
+            .unwrap_or(line.len())
+    }
+
+    /// Number of characters in a line
+    fn line_len(&self, row: usize) -> usize {
+        self.lines[row].chars().count()
+    }
+
+    /// Insert a character at the cursor
+    pub fn insert_char(&mut self, c: char) {
+        if c == '\n' {
+            self.insert_newline();
+            return;
+        }
+        let offset = self.byte_offset();
+        self.lines[self.cursor_row].insert(offset, c);
+        self.cursor_col += 1;
+    }
+
+    /// Insert text at the cursor (e.g. from a paste), splitting lines as needed
+    pub fn insert_str(&mut self, text: &str) {
+        for c in text.chars() {
+            if c != '\r' {
+                self.insert_char(c);
+            }
+        }
+    }
+
+    /// Split the current line at the cursor
+    pub fn insert_newline(&mut self) {
+        let offset = self.byte_offset();
+        let rest = self.lines[self.cursor_row].split_off(offset);
+        self.cursor_row += 1;
+        self.cursor_col = 0;
+        self.lines.insert(self.cursor_row, rest);
+    }
+
+    /// Delete the character before the cursor, joining lines at the start of a line
+    pub fn backspace(&mut self) {
+        if self.cursor_col > 0 {
+            self.cursor_col -= 1;
+            let offset = self.byte_offset();
+            self.lines[self.cursor_row].remove(offset);
+        } else if self.cursor_row > 0 {
+            let line = self.lines.remove(self.cursor_row);
+            self.cursor_row -= 1;
+            self.cursor_col = self.line_len(self.cursor_row);
+            self.lines[self.cursor_row].push_str(&line);
+        }
+    }
+
+    /// Delete the character under the cursor, joining lines at the end of a line
+    pub fn delete(&mut self) {
+        if self.cursor_col < self.line_len(self.cursor_row) {
+            let offset = self.byte_offset();
+            self.lines[self.cursor_row].remove(offset);
+        } else if self.cursor_row + 1 < self.lines.len() {
+            let next = self.lines.remove(self.cursor_row + 1);
+            self.lines[self.cursor_row].push_str(&next);
+        }
+    }
+
+    /// Move the cursor left, wrapping to the end of the previous line
+    pub fn move_left(&mut self) {
+        if self.cursor_col > 0 {
+            self.cursor_col -= 1;
+        } else if self.cursor_row > 0 {
+            self.cursor_row -= 1;
+            self.cursor_col = self.line_len(self.cursor_row);
+        }
+    }
+
+    /// Move the cursor right, wrapping to the start of the next line
+    pub fn move_right(&mut self) {
+        if self.cursor_col < self.line_len(self.cursor_row) {
+            self.cursor_col += 1;
+        } else if self.cursor_row + 1 < self.lines.len() {
+            self.cursor_row += 1;
+            self.cursor_col = 0;
+        }
+    }
+
+    /// Move the cursor up one line
+    pub fn move_up(&mut self) {
+        if self.cursor_row > 0 {
+            self.cursor_row -= 1;
+            self.cursor_col = self.cursor_col.min(self.line_len(self.cursor_row));
+        }
+    }
+
+    /// Move the cursor down one line
+    pub fn move_down(&mut self) {
+        if self.cursor_row + 1 < self.lines.len() {
+            self.cursor_row += 1;
+            self.cursor_col = self.cursor_col.min(self.line_len(self.cursor_row));
+        }
+    }
+
+    /// Move the cursor to the start of the line
+    pub fn move_home(&mut self) {
+        self.cursor_col = 0;
+    }
+
+    /// Move the cursor to the end of the line
+    pub fn move_end(&mut self) {
+        self.cursor_col = self.line_len(self.cursor_row);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_seeds_lines() {
+        let editor = HunkEditor::new(2, "a\nb\n");
+        assert_eq!(editor.conflict_index, 2);
+        assert_eq!(editor.lines, vec!["a", "b"]);
+        assert_eq!(editor.text(), "a\nb");
+    }
+
+    #[test]
+    fn test_new_drops_carriage_returns() {
+        let editor = HunkEditor::new(0, "a\r\nb\r");
+        assert_eq!(editor.lines, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_new_empty_has_one_line() {
+        let editor = HunkEditor::new(0, "");
+        assert_eq!(editor.lines, vec![""]);
+    }
+
+    #[test]
+    fn test_insert_and_newline() {
+        let mut editor = HunkEditor::new(0, "ac");
+        editor.move_right();
+        editor.insert_char('b');
+        assert_eq!(editor.text(), "abc");
+
+        editor.insert_newline();
+        assert_eq!(editor.text(), "ab\nc");
+        assert_eq!((editor.cursor_row, editor.cursor_col), (1, 0));
+    }
+
+    #[test]
+    fn test_insert_str_handles_multiline_paste() {
+        let mut editor = HunkEditor::new(0, "");
+        editor.insert_str("one\r\ntwo");
+        assert_eq!(editor.text(), "one\ntwo");
+        assert_eq!((editor.cursor_row, editor.cursor_col), (1, 3));
+    }
+
+    #[test]
+    fn test_backspace_joins_lines() {
+        let mut editor = HunkEditor::new(0, "foo\nbar");
+        editor.move_down();
+        editor.backspace();
+        assert_eq!(editor.text(), "foobar");
+        assert_eq!((editor.cursor_row, editor.cursor_col), (0, 3));
+    }
+
+    #[test]
+    fn test_delete_joins_lines() {
+        let mut editor = HunkEditor::new(0, "foo\nbar");
+        editor.move_end();
+        editor.delete();
+        assert_eq!(editor.text(), "foobar");
+    }
+
+    #[test]
+    fn test_multibyte_characters() {
+        let mut editor = HunkEditor::new(0, "héllo");
+        editor.move_right();
+        editor.move_right();
+        editor.backspace();
+        assert_eq!(editor.text(), "hllo");
+        editor.insert_char('ë');
+        assert_eq!(editor.text(), "hëllo");
+    }
+
+    #[test]
+    fn test_vertical_movement_clamps_column() {
+        let mut editor = HunkEditor::new(0, "long line\nab");
+        editor.move_end();
+        editor.move_down();
+        assert_eq!((editor.cursor_row, editor.cursor_col), (1, 2));
+        editor.move_up();
+        assert_eq!((editor.cursor_row, editor.cursor_col), (0, 2));
+    }
+}
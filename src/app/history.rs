@@ -0,0 +1,181 @@
+use crate::domain::{FileResolution, Resolution};
+
+/// A single resolution change that can be undone and redone
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// Resolution of one conflict hunk changed
+    Hunk {
+        /// Index of the file in `AppState::files`
+        file: usize,
+        /// Index of the conflict in the file
+        conflict: usize,
+        before: Option<Resolution>,
+        after: Option<Resolution>,
+    },
+    /// Resolution of a whole-file conflict changed
+    File {
+        /// Index of the file in `AppState::files`
+        file: usize,
+        before: Option<FileResolution>,
+        after: Option<FileResolution>,
+    },
+}
+
+impl Change {
+    /// Index of the file this change applies to
+    pub fn file(&self) -> usize {
+        match self {
+            Change::Hunk { file, .. } | Change::File { file, .. } => *file,
+        }
+    }
+
+    /// The change that reverts this one
+    pub fn inverse(&self) -> Change {
+        match self.clone() {
+            Change::Hunk {
+                file,
+                conflict,
+                before,
+                after,
+            } => Change::Hunk {
+                file,
+                conflict,
+                before: after,
+                after: before,
+            },
+            Change::File {
+                file,
+                before,
+                after,
+            } => Change::File {
+                file,
+                before: after,
+                after: before,
+            },
+        }
+    }
+}
+
+/// Undo/redo stacks of resolution changes
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    undo: Vec<Change>,
+    redo: Vec<Change>,
+}
+
+impl History {
+    /// Create an empty history
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a new change (clears the redo stack)
+    pub fn record(&mut self, change: Change) {
+        self.undo.push(change);
+        self.redo.clear();
+    }
+
+    /// Take the change to revert, moving it to the redo stack
+    ///
+    /// Returns the inverse change, ready to be applied.
+    pub fn undo(&mut self) -> Option<Change> {
+        let change = self.undo.pop()?;
+        let inverse = change.inverse();
+        self.redo.push(change);
+        Some(inverse)
+    }
+
+    /// Take the change to re-apply, moving it back to the undo stack
+    pub fn redo(&mut self) -> Option<Change> {
+        let change = self.redo.pop()?;
+        self.undo.push(change.clone());
+        Some(change)
+    }
+
+    /// Check if there is anything to undo
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Check if there is anything to redo
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hunk_change(before: Option<Resolution>, after: Option<Resolution>) -> Change {
+        Change::Hunk {
+            file: 0,
+            conflict: 1,
+            before,
+            after,
+        }
+    }
+
+    #[test]
+    fn test_undo_returns_inverse() {
+        let mut history = History::new();
+        history.record(hunk_change(None, Some(Resolution::Current)));
+
+        assert_eq!(
+            history.undo(),
+            Some(hunk_change(Some(Resolution::Current), None))
+        );
+        assert!(!history.can_undo());
+        assert!(history.can_redo());
+    }
+
+    #[test]
+    fn test_redo_returns_original() {
+        let mut history = History::new();
+        history.record(hunk_change(None, Some(Resolution::Both)));
+        history.undo();
+
+        assert_eq!(
+            history.redo(),
+            Some(hunk_change(None, Some(Resolution::Both)))
+        );
+        assert!(history.can_undo());
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn test_record_clears_redo() {
+        let mut history = History::new();
+        history.record(hunk_change(None, Some(Resolution::Current)));
+        history.undo();
+        history.record(hunk_change(None, Some(Resolution::Incoming)));
+
+        assert!(!history.can_redo());
+        assert_eq!(history.redo(), None);
+    }
+
+    #[test]
+    fn test_empty_history() {
+        let mut history = History::new();
+        assert_eq!(history.undo(), None);
+        assert_eq!(history.redo(), None);
+    }
+
+    #[test]
+    fn test_file_change_inverse() {
+        let change = Change::File {
+            file: 2,
+            before: None,
+            after: Some(FileResolution::Delete),
+        };
+        assert_eq!(change.file(), 2);
+        assert_eq!(
+            change.inverse(),
+            Change::File {
+                file: 2,
+                before: Some(FileResolution::Delete),
+                after: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use crate::domain::{raw_lines, ConflictHunk, LineSelection, Resolution, Side};
+
+/// Picker for taking individual lines of each side of a conflict hunk
+///
+/// Rows list the current side's lines followed by the incoming side's; the
+/// picked lines are written in the order they were picked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinePicker {
+    /// Index of the conflict being picked from
+    pub conflict_index: usize,
+    /// Lines of the current side
+    pub current: Vec<String>,
+    /// Lines of the incoming side
+    pub incoming: Vec<String>,
+    /// Lines picked so far
+    pub selection: LineSelection,
+    /// Highlighted row
+    pub cursor: usize,
+}
+
+impl LinePicker {
+    /// Create a picker for a conflict, starting from its current resolution
+    ///
+    /// A side chosen as a whole starts with all its lines picked. Returns `None`
+    /// for a hand edit, which has no lines to pick and would be lost.
+    pub fn new(
+        conflict_index: usize,
+        hunk: &ConflictHunk,
+        resolution: Option<&Resolution>,
+    ) -> Option<Self> {
+        let lines = |text: &str| -> Vec<String> {
+            raw_lines(text)
+                .into_iter()
+                .map(|line| line.strip_suffix('\r').unwrap_or(line).to_string())
+                .collect()
+        };
+        let current = lines(&hunk.current);
+        let incoming = lines(&hunk.incoming);
+
+        let selection = match resolution {
+            Some(Resolution::Lines(selection)) => selection.clone(),
+            Some(Resolution::Current) => LineSelection::whole_side(Side::Current, current.len()),
+            Some(Resolution::Incoming) => LineSelection::whole_side(Side::Incoming, incoming.len()),
+            Some(Resolution::Both) => {
+                let mut selection = LineSelection::whole_side(Side::Current, current.len());
+                selection
+                    .lines
+                    .extend(LineSelection::whole_side(Side::Incoming, incoming.len()).lines);
+                selection
+            }
+            Some(Resolution::Custom(_)) => return None,
+            None => LineSelection::default(),
+        };
+
+        Some(Self {
+            conflict_index,
+            current,
+            incoming,
+            selection,
+            cursor: 0,
+        })
+    }
+
+    /// Number of rows (lines of both sides)
+    pub fn len(&self) -> usize {
+        self.current.len() + self.incoming.len()
+    }
+
+    /// Check if neither side has lines
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Side and line index of a row
+    pub fn row(&self, row: usize) -> (Side, usize) {
+        if row < self.current.len() {
+            (Side::Current, row)
+        } else {
+            (Side::Incoming, row - self.current.len())
+        }
+    }
+
+    /// Position of a row's line in the result, if it is picked
+    pub fn pick_position(&self, row: usize) -> Option<usize> {
+        let (side, index) = self.row(row);
+        self.selection.position(side, index)
+    }
+
+    /// Check if a row's line is picked
+    pub fn is_picked(&self, row: usize) -> bool {
+        self.pick_position(row).is_some()
+    }
+
+    /// Pick the line under the cursor after the others, or drop it
+    pub fn toggle(&mut self) {
+        if self.is_empty() {
+            return;
+        }
+        let (side, index) = self.row(self.cursor);
+        self.selection.toggle(side, index);
+    }
+
+    /// Move the cursor down one row
+    pub fn move_down(&mut self) {
+        if self.cursor + 1 < self.len() {
+            self.cursor += 1;
+        }
+    }
+
+    /// Move the cursor up one row
+    pub fn move_up(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    /// Resolution taking the picked lines
+    pub fn resolution(&self) -> Resolution {
+        Resolution::Lines(self.selection.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hunk() -> ConflictHunk {
+        ConflictHunk::new("a1\na2".to_string(), "b1\nb2\nb3".to_string(), 0, 8)
+    }
+
+    #[test]
+    fn test_rows_span_both_sides() {
+        let picker = LinePicker::new(0, &hunk(), None).unwrap();
+
+        assert_eq!(picker.len(), 5);
+        assert_eq!(picker.row(1), (Side::Current, 1));
+        assert_eq!(picker.row(2), (Side::Incoming, 0));
+        assert!(picker.selection.is_empty());
+    }
+
+    #[test]
+    fn test_seeded_from_whole_side() {
+        let picker = LinePicker::new(0, &hunk(), Some(&Resolution::Incoming)).unwrap();
+
+        assert!(!picker.is_picked(0));
+        assert!(picker.is_picked(2));
+        assert!(picker.is_picked(4));
+    }
+
+    #[test]
+    fn test_refuses_hand_edit() {
+        let custom = Resolution::Custom("hand\nmerged".to_string());
+        assert!(LinePicker::new(0, &hunk(), Some(&custom)).is_none());
+    }
+
+    #[test]
+    fn test_toggle_builds_resolution() {
+        let mut picker = LinePicker::new(0, &hunk(), None).unwrap();
+        picker.toggle();
+        picker.move_down();
+        picker.move_down();
+        picker.move_down();
+        picker.toggle();
+        picker.toggle();
+        picker.move_down();
+        picker.move_down();
+        picker.toggle();
+
+        assert_eq!(picker.cursor, 4);
+        assert_eq!(hunk().resolve(picker.resolution()), "a1\nb3");
+    }
+
+    #[test]
+    fn test_picks_interleave_sides() {
+        let mut picker = LinePicker::new(0, &hunk(), None).unwrap();
+        picker.move_down();
+        picker.move_down();
+        picker.toggle();
+        picker.cursor = 0;
+        picker.toggle();
+        picker.cursor = 3;
+        picker.toggle();
+
+        assert_eq!(picker.pick_position(2), Some(0));
+        assert_eq!(picker.pick_position(0), Some(1));
+        assert_eq!(hunk().resolve(picker.resolution()), "b1\na1\nb2");
+    }
+}
@@ -1,3 +1,11 @@
+pub mod editor;
+pub mod history;
+pub mod line_picker;
+pub mod notification;
 pub mod state;
 
-pub use state::{AppMode, AppState, PaneFocus, ViewMode};
+pub use editor::HunkEditor;
+pub use history::{Change, History};
+pub use line_picker::LinePicker;
+pub use notification::{GitAction, Notification};
+pub use state::{AppMode, AppState, CodeLayout, PaneFocus, ViewMode};
@@ -0,0 +1,101 @@
+use anyhow::Result;
+
+use crate::git::{
+    abort_rebase, continue_rebase, restore_file, skip_rebase, stage_file, unstage_file,
+};
+
+/// A git command run from the TUI, kept so a failure can be retried
+#[derive(Debug, Clone, PartialEq)]
+pub enum GitAction {
+    ContinueRebase,
+    AbortRebase,
+    SkipRebase,
+    /// Stage the file at this path
+    Stage(String),
+    /// Unstage the file at this path
+    Unstage(String),
+    /// Discard the changes of the file at this path
+    Restore(String),
+}
+
+impl GitAction {
+    /// What the command does, for notification titles
+    pub fn describe(&self) -> String {
+        match self {
+            GitAction::ContinueRebase => "Continue rebase".to_string(),
+            GitAction::AbortRebase => "Abort rebase".to_string(),
+            GitAction::SkipRebase => "Skip commit".to_string(),
+            GitAction::Stage(path) => format!("Stage {}", path),
+            GitAction::Unstage(path) => format!("Unstage {}", path),
+            GitAction::Restore(path) => format!("Restore {}", path),
+        }
+    }
+
+    /// Run the git command
+    pub fn run(&self) -> Result<()> {
+        match self {
+            GitAction::ContinueRebase => continue_rebase(),
+            GitAction::AbortRebase => abort_rebase(),
+            GitAction::SkipRebase => skip_rebase(),
+            GitAction::Stage(path) => stage_file(path),
+            GitAction::Unstage(path) => unstage_file(path),
+            GitAction::Restore(path) => restore_file(path),
+        }
+    }
+}
+
+/// An error shown over the TUI until it is dismissed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub title: String,
+    /// The error, including git's stderr for failed commands
+    pub message: String,
+    /// Command to run again when the user retries
+    pub retry: Option<GitAction>,
+}
+
+impl Notification {
+    /// Notification for an error, with its whole context chain
+    pub fn error(title: String, error: &anyhow::Error, retry: Option<GitAction>) -> Self {
+        Self {
+            title,
+            message: format!("{:#}", error).trim_end().to_string(),
+            retry,
+        }
+    }
+
+    /// Notification for a failed git command, offering to run it again
+    pub fn git_failure(action: GitAction, error: &anyhow::Error) -> Self {
+        Self::error(format!("{} failed", action.describe()), error, Some(action))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_git_failure_notification() {
+        let error = anyhow::anyhow!("git rebase --continue failed: hook rejected\n\n");
+        let notification = Notification::git_failure(GitAction::ContinueRebase, &error);
+
+        assert_eq!(notification.title, "Continue rebase failed");
+        assert_eq!(
+            notification.message,
+            "git rebase --continue failed: hook rejected"
+        );
+        assert_eq!(notification.retry, Some(GitAction::ContinueRebase));
+    }
+
+    #[test]
+    fn test_error_notification_keeps_context() {
+        let error = anyhow::anyhow!("permission denied").context("Failed to write src/lib.rs");
+        let notification = Notification::error("Action failed".to_string(), &error, None);
+
+        assert_eq!(
+            notification.message,
+            "Failed to write src/lib.rs: permission denied"
+        );
+        assert_eq!(notification.retry, None);
+    }
+}
@@ -1,5 +1,16 @@
-use crate::domain::{ConflictedFile, GitOperation, Resolution};
-use crate::git::FileStatus;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use super::editor::HunkEditor;
+use super::history::{Change, History};
+use super::line_picker::LinePicker;
+use super::notification::{GitAction, Notification};
+use crate::config::Config;
+use crate::domain::{ConflictedFile, FileResolution, GitOperation, Resolution};
+use crate::git::{ConflictSnapshot, FileStatus};
+use crate::tui::highlight_cache::HighlightCache;
 
 /// Represents the application mode
 #[derive(Debug, Clone, PartialEq)]
@@ -29,6 +40,31 @@ pub enum PaneFocus {
     CodeView,
 }
 
+/// Layout of the code pane in conflict mode
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CodeLayout {
+    /// Whole file with the conflicts inline
+    Unified,
+    /// Ours | base | theirs columns with the result below
+    ThreeWay,
+}
+
+impl FromStr for CodeLayout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "unified" => Ok(Self::Unified),
+            "three-way" => Ok(Self::ThreeWay),
+            _ => Err(format!(
+                "unknown layout '{}' (expected unified or three-way)",
+                s
+            )),
+        }
+    }
+}
+
 /// Main application state
 pub struct AppState {
     /// All conflicted files
@@ -59,6 +95,32 @@ pub struct AppState {
     pub show_commit_modal: bool,
     /// Commit error message (if any)
     pub commit_error: Option<String>,
+    /// Inline editor for hand-merging the current conflict (if open)
+    pub editor: Option<HunkEditor>,
+    /// Line picker for taking single lines of the current conflict (if open)
+    pub line_picker: Option<LinePicker>,
+    /// Whether the current conflict should be opened in $VISUAL/$EDITOR
+    pub external_edit_requested: bool,
+    /// Message shown in the footer until the next key press
+    pub status_message: Option<String>,
+    /// Error shown over the view until dismissed (or retried)
+    pub notification: Option<Notification>,
+    /// Whether the code pane shows the merged result instead of the markers
+    pub show_result_preview: bool,
+    /// Layout of the code pane in conflict mode
+    pub code_layout: CodeLayout,
+    /// Undo/redo history of resolution changes
+    pub history: History,
+    /// Content-conflict files saki has written resolutions to
+    pub written_files: HashSet<PathBuf>,
+    /// Index/worktree state of applied whole-file conflicts, to restore on undo
+    pub conflict_snapshots: HashMap<PathBuf, ConflictSnapshot>,
+    /// Settings from the config files, environment and command line
+    pub config: Config,
+    /// Highlighted code of the views, rebuilt when resolutions change
+    pub highlights: HighlightCache,
+    /// Runs the git commands of the TUI (`GitAction::run`, faked in tests)
+    pub git_runner: fn(&GitAction) -> anyhow::Result<()>,
 }
 
 impl AppState {
@@ -79,6 +141,19 @@ impl AppState {
             commit_message: String::new(),
             show_commit_modal: false,
             commit_error: None,
+            editor: None,
+            line_picker: None,
+            external_edit_requested: false,
+            status_message: None,
+            notification: None,
+            show_result_preview: false,
+            code_layout: CodeLayout::Unified,
+            history: History::new(),
+            written_files: HashSet::new(),
+            conflict_snapshots: HashMap::new(),
+            config: Config::default(),
+            highlights: HighlightCache::default(),
+            git_runner: GitAction::run,
         }
     }
 
@@ -99,14 +174,48 @@ impl AppState {
             commit_message: String::new(),
             show_commit_modal: false,
             commit_error: None,
+            editor: None,
+            line_picker: None,
+            external_edit_requested: false,
+            status_message: None,
+            notification: None,
+            show_result_preview: false,
+            code_layout: CodeLayout::Unified,
+            history: History::new(),
+            written_files: HashSet::new(),
+            conflict_snapshots: HashMap::new(),
+            config: Config::default(),
+            highlights: HighlightCache::default(),
+            git_runner: GitAction::run,
         }
     }
 
+    /// Apply the user's settings (call before the first draw)
+    pub fn set_config(&mut self, config: Config) {
+        self.code_layout = config.layout;
+        self.config = config;
+    }
+
     /// Toggle the help dialog
     pub fn toggle_help(&mut self) {
         self.show_help = !self.show_help;
     }
 
+    /// Toggle the merged result preview in the code pane
+    pub fn toggle_result_preview(&mut self) {
+        self.show_result_preview = !self.show_result_preview;
+        self.reset_scroll();
+    }
+
+    /// Switch between the unified and three-way code layouts
+    pub fn toggle_code_layout(&mut self) {
+        self.code_layout = match self.code_layout {
+            CodeLayout::Unified => CodeLayout::ThreeWay,
+            CodeLayout::ThreeWay => CodeLayout::Unified,
+        };
+        self.reset_scroll();
+    }
+
     /// Toggle focus between file list and code view
     pub fn toggle_focus(&mut self) {
         self.focus = match self.focus {
@@ -152,6 +261,10 @@ impl AppState {
                     }
                 }
             }
+            // The three-way layout scrolls relative to the current conflict
+            if self.code_layout == CodeLayout::ThreeWay {
+                self.reset_scroll();
+            }
         }
     }
 
@@ -163,6 +276,9 @@ impl AppState {
                     *conflict_index -= 1;
                 }
             }
+            if self.code_layout == CodeLayout::ThreeWay {
+                self.reset_scroll();
+            }
         }
     }
 
@@ -170,24 +286,292 @@ impl AppState {
     pub fn set_current_resolution(&mut self, resolution: Resolution) {
         if self.focus == PaneFocus::CodeView {
             if let ViewMode::SplitPane { conflict_index } = self.view_mode {
-                if self.selected_file < self.files.len() {
-                    self.files[self.selected_file].set_resolution(conflict_index, resolution);
-                }
+                self.change_hunk_resolution(self.selected_file, conflict_index, Some(resolution));
             }
         }
     }
 
-    /// Clear resolution for the current conflict (undo)
+    /// Set resolution for a conflict of the selected file, regardless of focus
+    pub fn set_hunk_resolution(&mut self, conflict_index: usize, resolution: Resolution) {
+        self.change_hunk_resolution(self.selected_file, conflict_index, Some(resolution));
+    }
+
+    /// Set resolution for the current whole-file conflict
+    pub fn set_current_file_resolution(&mut self, resolution: FileResolution) {
+        if self.focus == PaneFocus::CodeView {
+            self.change_file_resolution(self.selected_file, Some(resolution));
+        }
+    }
+
+    /// Clear resolution for the current conflict
     pub fn clear_current_resolution(&mut self) {
         if self.focus == PaneFocus::CodeView {
             if let ViewMode::SplitPane { conflict_index } = self.view_mode {
-                if self.selected_file < self.files.len() {
-                    self.files[self.selected_file].clear_resolution(conflict_index);
+                if self.current_file_is_file_conflict() {
+                    self.change_file_resolution(self.selected_file, None);
+                } else {
+                    self.change_hunk_resolution(self.selected_file, conflict_index, None);
                 }
             }
         }
     }
 
+    /// Change a hunk resolution and record it in the undo history
+    fn change_hunk_resolution(
+        &mut self,
+        file_index: usize,
+        conflict_index: usize,
+        after: Option<Resolution>,
+    ) {
+        let file = match self.files.get_mut(file_index) {
+            Some(file) => file,
+            None => return,
+        };
+        // Picking a side for an automatic resolution (even the same one) confirms it
+        file.confirm_auto_resolution(conflict_index);
+        let slot = match file.resolutions.get_mut(conflict_index) {
+            Some(slot) => slot,
+            None => return,
+        };
+        if *slot == after {
+            return;
+        }
+
+        let before = std::mem::replace(slot, after.clone());
+        self.history.record(Change::Hunk {
+            file: file_index,
+            conflict: conflict_index,
+            before,
+            after,
+        });
+    }
+
+    /// Change a whole-file resolution and record it in the undo history
+    fn change_file_resolution(&mut self, file_index: usize, after: Option<FileResolution>) {
+        let file = match self.files.get_mut(file_index) {
+            Some(file) => file,
+            None => return,
+        };
+
+        let before = file.file_resolution;
+        match after {
+            Some(resolution) => file.set_file_resolution(resolution),
+            None => file.clear_file_resolution(),
+        }
+
+        if file.file_resolution != before {
+            self.history.record(Change::File {
+                file: file_index,
+                before,
+                after: file.file_resolution,
+            });
+        }
+    }
+
+    /// Undo the last resolution change, returning the index of the file it touched
+    pub fn undo(&mut self) -> Option<usize> {
+        let change = self.history.undo()?;
+        Some(self.apply_change(change))
+    }
+
+    /// Redo the last undone resolution change, returning the index of the file it touched
+    pub fn redo(&mut self) -> Option<usize> {
+        let change = self.history.redo()?;
+        Some(self.apply_change(change))
+    }
+
+    /// Apply a change from the history and select the conflict it touched
+    fn apply_change(&mut self, change: Change) -> usize {
+        let file_index = change.file();
+        match change {
+            Change::Hunk {
+                file,
+                conflict,
+                after,
+                ..
+            } => {
+                if let Some(f) = self.files.get_mut(file) {
+                    if let Some(slot) = f.resolutions.get_mut(conflict) {
+                        *slot = after;
+                    }
+                    f.confirm_auto_resolution(conflict);
+                }
+                self.view_mode = ViewMode::SplitPane {
+                    conflict_index: conflict,
+                };
+            }
+            Change::File { file, after, .. } => {
+                if let Some(f) = self.files.get_mut(file) {
+                    f.file_resolution = after;
+                }
+                self.view_mode = ViewMode::SplitPane { conflict_index: 0 };
+            }
+        }
+
+        self.selected_file = file_index;
+        file_index
+    }
+
+    /// Get the resolution chosen for the current conflict
+    pub fn current_resolution(&self) -> Option<&Resolution> {
+        let conflict_index = self.current_conflict_index()?;
+        self.current_file()?
+            .resolutions
+            .get(conflict_index)?
+            .as_ref()
+    }
+
+    /// Warn that writing files rebuilt from the index replaces their hand edits
+    pub fn warn_rebuilt_files(&mut self) {
+        let count = self
+            .files
+            .iter()
+            .filter(|f| f.is_rebuilt_from_index())
+            .count();
+        if count > 0 {
+            self.set_status_message(format!(
+                "Rebuilt {} file{} with hand-edited markers from the index: saving replaces the edits, undo brings them back",
+                count,
+                if count == 1 { "" } else { "s" }
+            ));
+        }
+    }
+
+    /// Pre-fill trivial conflicts in every file, returning how many were resolved
+    ///
+    /// They stay marked as automatic (and the footer says so) until reviewed.
+    pub fn auto_resolve(&mut self) -> usize {
+        let count: usize = self.files.iter_mut().map(|f| f.auto_resolve()).sum();
+        if count > 0 {
+            let accept = match self.config.keys.accept_auto_hint() {
+                Some(hint) => format!(", {}", hint),
+                None => String::new(),
+            };
+            self.set_status_message(format!(
+                "Auto-resolved {} trivial conflict{}: review them{}",
+                count,
+                if count == 1 { "" } else { "s" },
+                accept
+            ));
+        }
+        count
+    }
+
+    /// Confirm the automatic resolutions of the selected file, returning how many there were
+    pub fn accept_auto_resolutions(&mut self) -> usize {
+        match self.files.get_mut(self.selected_file) {
+            Some(file) => {
+                let count = file.auto_resolved_count();
+                file.confirm_auto_resolutions();
+                count
+            }
+            None => 0,
+        }
+    }
+
+    /// Open the inline editor on the current conflict, seeded with a resolution's content
+    pub fn open_hunk_editor(&mut self, seed: Resolution) {
+        if self.focus != PaneFocus::CodeView {
+            return;
+        }
+        let conflict_index = match self.current_conflict_index() {
+            Some(index) => index,
+            None => return,
+        };
+        if let Some(conflict) = self
+            .current_file()
+            .and_then(|f| f.conflicts.get(conflict_index))
+        {
+            self.editor = Some(HunkEditor::new(conflict_index, &conflict.resolve(seed)));
+        }
+    }
+
+    /// Check if the inline editor is open
+    pub fn is_editing(&self) -> bool {
+        self.editor.is_some()
+    }
+
+    /// Close the inline editor and store its text as a custom resolution
+    pub fn save_hunk_editor(&mut self) {
+        if let Some(editor) = self.editor.take() {
+            self.set_hunk_resolution(editor.conflict_index, Resolution::Custom(editor.text()));
+        }
+    }
+
+    /// Close the inline editor without changing the resolution
+    pub fn cancel_hunk_editor(&mut self) {
+        self.editor = None;
+    }
+
+    /// Open the line picker on the current conflict, starting from its resolution
+    pub fn open_line_picker(&mut self) {
+        if self.focus != PaneFocus::CodeView {
+            return;
+        }
+        let conflict_index = match self.current_conflict_index() {
+            Some(index) => index,
+            None => return,
+        };
+        let resolution = self.current_resolution();
+        let conflict = match self
+            .current_file()
+            .and_then(|f| f.conflicts.get(conflict_index))
+        {
+            Some(conflict) => conflict,
+            None => return,
+        };
+        match LinePicker::new(conflict_index, conflict, resolution) {
+            Some(picker) => self.line_picker = Some(picker),
+            None => self.set_status_message(
+                "This conflict was edited by hand: clear its resolution to pick lines".to_string(),
+            ),
+        }
+    }
+
+    /// Check if the line picker is open
+    pub fn is_picking_lines(&self) -> bool {
+        self.line_picker.is_some()
+    }
+
+    /// Close the line picker and resolve the conflict with the picked lines
+    pub fn save_line_picker(&mut self) {
+        if let Some(picker) = self.line_picker.take() {
+            self.set_hunk_resolution(picker.conflict_index, picker.resolution());
+        }
+    }
+
+    /// Close the line picker without changing the resolution
+    pub fn cancel_line_picker(&mut self) {
+        self.line_picker = None;
+    }
+
+    /// Ask the main loop to open the current conflict in the user's editor
+    pub fn request_external_edit(&mut self) {
+        if self.focus == PaneFocus::CodeView && !self.current_file_is_file_conflict() {
+            self.external_edit_requested = true;
+        }
+    }
+
+    /// Show a message in the footer
+    pub fn set_status_message(&mut self, message: String) {
+        self.status_message = Some(message);
+    }
+
+    /// Show an error over the view, replacing any shown before
+    pub fn notify(&mut self, notification: Notification) {
+        self.notification = Some(notification);
+    }
+
+    /// Close the notification, returning it (e.g. to retry its command)
+    pub fn dismiss_notification(&mut self) -> Option<Notification> {
+        self.notification.take()
+    }
+
+    /// Check if the selected file is a whole-file conflict
+    pub fn current_file_is_file_conflict(&self) -> bool {
+        self.current_file().is_some_and(|f| f.is_file_conflict())
+    }
+
     /// Go back to file list focus
     pub fn back_to_file_list(&mut self) {
         self.focus = PaneFocus::FileList;
@@ -372,6 +756,7 @@ impl AppState {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::app::GitAction;
     use crate::domain::ConflictHunk;
     use crate::git::StatusChange;
     use std::path::PathBuf;
@@ -399,6 +784,10 @@ mod tests {
             end_line: 5,
             current: "current content".to_string(),
             incoming: "incoming content".to_string(),
+            base: None,
+            current_label: None,
+            base_label: None,
+            incoming_label: None,
         };
         ConflictedFile::new(
             PathBuf::from(path),
@@ -407,6 +796,287 @@ mod tests {
         )
     }
 
+    // --- Whole-file Conflict Tests ---
+
+    #[test]
+    fn test_set_current_file_resolution() {
+        use crate::domain::FileConflictKind;
+
+        let files = vec![ConflictedFile::new_file_conflict(
+            PathBuf::from("deleted.rs"),
+            FileConflictKind::DeletedByThem,
+        )];
+        let mut state = AppState::new(files, GitOperation::Merge);
+        state.focus = PaneFocus::CodeView;
+        assert!(state.current_file_is_file_conflict());
+
+        state.set_current_file_resolution(FileResolution::Delete);
+        assert!(state.all_files_resolved());
+
+        state.clear_current_resolution();
+        assert!(!state.all_files_resolved());
+    }
+
+    // --- Hunk Editor Tests ---
+
+    #[test]
+    fn test_hunk_editor_saves_custom_resolution() {
+        let files = vec![create_test_conflicted_file("file1.rs")];
+        let mut state = AppState::new(files, GitOperation::Merge);
+        state.focus = PaneFocus::CodeView;
+
+        state.open_hunk_editor(Resolution::Incoming);
+        let editor = state.editor.as_mut().unwrap();
+        assert_eq!(editor.text(), "incoming content");
+        editor.move_end();
+        editor.insert_str(" + fix");
+
+        state.save_hunk_editor();
+        assert!(!state.is_editing());
+        assert_eq!(
+            state.current_resolution(),
+            Some(&Resolution::Custom("incoming content + fix".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_hunk_editor_cancel_keeps_resolution() {
+        let files = vec![create_test_conflicted_file("file1.rs")];
+        let mut state = AppState::new(files, GitOperation::Merge);
+        state.focus = PaneFocus::CodeView;
+        state.set_current_resolution(Resolution::Current);
+
+        state.open_hunk_editor(Resolution::Current);
+        state.editor.as_mut().unwrap().insert_str("junk");
+        state.cancel_hunk_editor();
+
+        assert!(!state.is_editing());
+        assert_eq!(state.current_resolution(), Some(&Resolution::Current));
+    }
+
+    #[test]
+    fn test_hunk_editor_requires_code_view_focus() {
+        let files = vec![create_test_conflicted_file("file1.rs")];
+        let mut state = AppState::new(files, GitOperation::Merge);
+
+        state.open_hunk_editor(Resolution::Current);
+        assert!(!state.is_editing());
+    }
+
+    // --- Line Picker Tests ---
+
+    #[test]
+    fn test_line_picker_saves_picked_lines() {
+        let files = vec![create_test_conflicted_file("file1.rs")];
+        let mut state = AppState::new(files, GitOperation::Merge);
+        state.focus = PaneFocus::CodeView;
+        state.set_current_resolution(Resolution::Current);
+
+        state.open_line_picker();
+        let picker = state.line_picker.as_mut().unwrap();
+        assert!(picker.is_picked(0));
+        picker.move_down();
+        picker.toggle();
+
+        state.save_line_picker();
+        assert!(!state.is_picking_lines());
+        let resolution = state.current_resolution().unwrap().clone();
+        assert_eq!(
+            state.files[0].conflicts[0].resolve(resolution),
+            "current content\nincoming content"
+        );
+    }
+
+    #[test]
+    fn test_line_picker_keeps_hand_edit() {
+        let files = vec![create_test_conflicted_file("file1.rs")];
+        let mut state = AppState::new(files, GitOperation::Merge);
+        state.focus = PaneFocus::CodeView;
+        let custom = Resolution::Custom("hand merged".to_string());
+        state.set_current_resolution(custom.clone());
+
+        state.open_line_picker();
+        assert!(!state.is_picking_lines());
+        assert_eq!(state.current_resolution(), Some(&custom));
+        assert!(state.status_message.is_some());
+    }
+
+    #[test]
+    fn test_toggle_result_preview_resets_scroll() {
+        let files = vec![create_test_conflicted_file("file1.rs")];
+        let mut state = AppState::new(files, GitOperation::Merge);
+        state.scroll_offset = 5;
+
+        state.toggle_result_preview();
+        assert!(state.show_result_preview);
+        assert_eq!(state.scroll_offset, 0);
+
+        state.toggle_result_preview();
+        assert!(!state.show_result_preview);
+    }
+
+    #[test]
+    fn test_notification_dismiss_returns_retry() {
+        let mut state = AppState::new_staging(Vec::new());
+        let error = anyhow::anyhow!("git add failed: fatal: pathspec");
+        state.notify(Notification::git_failure(
+            GitAction::Stage("a.rs".to_string()),
+            &error,
+        ));
+        assert!(state.notification.is_some());
+
+        let notification = state.dismiss_notification().unwrap();
+        assert_eq!(
+            notification.retry,
+            Some(GitAction::Stage("a.rs".to_string()))
+        );
+        assert!(state.notification.is_none());
+        assert!(state.dismiss_notification().is_none());
+    }
+
+    #[test]
+    fn test_set_config_opens_configured_layout() {
+        let files = vec![create_test_conflicted_file("file1.rs")];
+        let mut state = AppState::new(files, GitOperation::Merge);
+        assert!(state.config.auto_save);
+
+        state.set_config(Config {
+            layout: CodeLayout::ThreeWay,
+            auto_save: false,
+            ..Config::default()
+        });
+        assert_eq!(state.code_layout, CodeLayout::ThreeWay);
+        assert!(!state.config.auto_save);
+        assert_eq!("three-way".parse(), Ok(CodeLayout::ThreeWay));
+        assert!("sideways".parse::<CodeLayout>().is_err());
+    }
+
+    #[test]
+    fn test_toggle_code_layout() {
+        let files = vec![create_test_conflicted_file("file1.rs")];
+        let mut state = AppState::new(files, GitOperation::Merge);
+        assert_eq!(state.code_layout, CodeLayout::Unified);
+
+        state.toggle_code_layout();
+        assert_eq!(state.code_layout, CodeLayout::ThreeWay);
+
+        // Moving between conflicts resets the scroll in three-way layout
+        state.focus = PaneFocus::CodeView;
+        state.scroll_offset = 4;
+        state.next_conflict();
+        assert_eq!(state.scroll_offset, 0);
+
+        state.toggle_code_layout();
+        assert_eq!(state.code_layout, CodeLayout::Unified);
+    }
+
+    // --- Undo/Redo Tests ---
+
+    #[test]
+    fn test_undo_redo_hunk_resolution() {
+        let files = vec![create_test_conflicted_file("file1.rs")];
+        let mut state = AppState::new(files, GitOperation::Merge);
+        state.focus = PaneFocus::CodeView;
+
+        state.set_current_resolution(Resolution::Current);
+        state.set_current_resolution(Resolution::Both);
+
+        assert_eq!(state.undo(), Some(0));
+        assert_eq!(state.current_resolution(), Some(&Resolution::Current));
+        assert_eq!(state.undo(), Some(0));
+        assert_eq!(state.current_resolution(), None);
+        assert_eq!(state.undo(), None);
+
+        assert_eq!(state.redo(), Some(0));
+        assert_eq!(state.current_resolution(), Some(&Resolution::Current));
+    }
+
+    #[test]
+    fn test_clear_resolution_is_undoable() {
+        let files = vec![create_test_conflicted_file("file1.rs")];
+        let mut state = AppState::new(files, GitOperation::Merge);
+        state.focus = PaneFocus::CodeView;
+
+        state.set_current_resolution(Resolution::Incoming);
+        state.clear_current_resolution();
+        assert_eq!(state.current_resolution(), None);
+
+        state.undo();
+        assert_eq!(state.current_resolution(), Some(&Resolution::Incoming));
+    }
+
+    #[test]
+    fn test_overriding_auto_resolution_confirms_it() {
+        let mut file = create_test_conflicted_file("file1.rs");
+        file.conflicts[0].incoming = "current content".to_string();
+        file.auto_resolve();
+        let mut state = AppState::new(vec![file], GitOperation::Merge);
+        state.focus = PaneFocus::CodeView;
+
+        // Picking the same side still confirms it, without a history entry
+        state.set_current_resolution(Resolution::Current);
+        assert_eq!(state.files[0].auto_resolution(0), None);
+        assert_eq!(state.undo(), None);
+
+        state.files[0].clear_resolution(0);
+        state.files[0].auto_resolve();
+        assert_eq!(state.accept_auto_resolutions(), 1);
+        assert_eq!(state.current_resolution(), Some(&Resolution::Current));
+        assert_eq!(state.files[0].auto_resolved_count(), 0);
+    }
+
+    #[test]
+    fn test_pending_auto_resolutions_are_not_resolved() {
+        let mut file = create_test_conflicted_file("file1.rs");
+        file.conflicts[0].incoming = "current content".to_string();
+        let mut state = AppState::new(vec![file], GitOperation::Rebase);
+        state.auto_resolve();
+        assert!(!state.all_files_resolved());
+
+        state.accept_auto_resolutions();
+        assert!(state.all_files_resolved());
+    }
+
+    #[test]
+    fn test_undo_selects_changed_file() {
+        let files = vec![
+            create_test_conflicted_file("file1.rs"),
+            create_test_conflicted_file("file2.rs"),
+        ];
+        let mut state = AppState::new(files, GitOperation::Merge);
+        state.focus = PaneFocus::CodeView;
+        state.set_current_resolution(Resolution::Current);
+
+        state.selected_file = 1;
+        assert_eq!(state.undo(), Some(0));
+        assert_eq!(state.selected_file, 0);
+    }
+
+    #[test]
+    fn test_same_resolution_is_not_recorded() {
+        let files = vec![create_test_conflicted_file("file1.rs")];
+        let mut state = AppState::new(files, GitOperation::Merge);
+        state.focus = PaneFocus::CodeView;
+
+        state.set_current_resolution(Resolution::Current);
+        state.set_current_resolution(Resolution::Current);
+        state.undo();
+        assert!(!state.history.can_undo());
+    }
+
+    #[test]
+    fn test_request_external_edit() {
+        let files = vec![create_test_conflicted_file("file1.rs")];
+        let mut state = AppState::new(files, GitOperation::Merge);
+
+        state.request_external_edit();
+        assert!(!state.external_edit_requested);
+
+        state.focus = PaneFocus::CodeView;
+        state.request_external_edit();
+        assert!(state.external_edit_requested);
+    }
+
     // --- Staging Mode Tests ---
 
     #[test]
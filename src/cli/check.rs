@@ -0,0 +1,274 @@
+use anyhow::{Context, Result};
+use git2::{Delta, Repository};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::git::attributes::{conflict_marker_size, is_check_disabled, AttrSource};
+use crate::git::detector::repo_relative_path;
+use crate::git::loader::is_binary;
+use crate::git::{find_leftover_markers, LeftoverMarker};
+
+/// Which files `saki check` scans
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckScope {
+    /// Staged versions of the files changed in the index (for pre-commit hooks)
+    Staged,
+    /// Every tracked file in the working tree (for CI)
+    All,
+    /// Working tree versions of the given files
+    Paths(Vec<PathBuf>),
+}
+
+/// What to scan and how
+#[derive(Debug, Clone)]
+pub struct CheckOptions {
+    pub scope: CheckScope,
+    /// Marker size for files without a `conflict-marker-size` attribute
+    pub marker_size: usize,
+}
+
+/// A leftover conflict marker found in a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// Path relative to the repository root
+    pub path: PathBuf,
+    pub marker: LeftoverMarker,
+}
+
+/// Staged content of every file added or modified in the index
+fn staged_files(repo: &Repository) -> Result<Vec<(PathBuf, Vec<u8>)>> {
+    let head_tree = match repo.head() {
+        Ok(head) => Some(head.peel_to_tree().context("Failed to read HEAD tree")?),
+        // No commits yet: everything in the index is staged
+        Err(_) => None,
+    };
+    let diff = repo
+        .diff_tree_to_index(head_tree.as_ref(), None, None)
+        .context("Failed to diff the index against HEAD")?;
+
+    let mut files = Vec::new();
+    for delta in diff.deltas() {
+        if matches!(delta.status(), Delta::Deleted | Delta::Conflicted) {
+            continue;
+        }
+        let new_file = delta.new_file();
+        let path = match new_file.path() {
+            Some(path) => path.to_path_buf(),
+            None => continue,
+        };
+        let blob = repo
+            .find_blob(new_file.id())
+            .with_context(|| format!("Failed to read staged blob for {}", path.display()))?;
+        files.push((path, blob.content().to_vec()));
+    }
+    Ok(files)
+}
+
+/// Working tree content of every tracked file
+fn tracked_files(repo: &Repository) -> Result<Vec<(PathBuf, Vec<u8>)>> {
+    let workdir = repo
+        .workdir()
+        .context("Repository has no working directory")?;
+    let index = repo.index().context("Failed to read repository index")?;
+
+    let mut files = Vec::new();
+    for entry in index.iter() {
+        let path = PathBuf::from(String::from_utf8_lossy(&entry.path).into_owned());
+        // Deleted in the worktree but not staged yet: nothing to scan
+        if let Ok(content) = fs::read(workdir.join(&path)) {
+            files.push((path, content));
+        }
+    }
+    files.dedup_by(|a, b| a.0 == b.0);
+    Ok(files)
+}
+
+/// Working tree content of the given files
+fn given_files(repo: &Repository, paths: &[PathBuf]) -> Result<Vec<(PathBuf, Vec<u8>)>> {
+    paths
+        .iter()
+        .map(|path| {
+            let content =
+                fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+            Ok((repo_relative_path(repo, path)?, content))
+        })
+        .collect()
+}
+
+/// Scan one file, honoring the `.gitattributes` from `source`
+fn check_file(
+    repo: &Repository,
+    path: &Path,
+    content: &[u8],
+    source: AttrSource,
+    marker_size: usize,
+) -> Vec<Finding> {
+    if is_check_disabled(repo, path, source) || is_binary(content) {
+        return Vec::new();
+    }
+    let content = String::from_utf8_lossy(content);
+    let marker_size = conflict_marker_size(repo, path, source).unwrap_or(marker_size);
+
+    find_leftover_markers(&content, marker_size)
+        .into_iter()
+        .map(|marker| Finding {
+            path: path.to_path_buf(),
+            marker,
+        })
+        .collect()
+}
+
+/// Find leftover conflict markers in the files selected by `options`
+pub fn check_repository(repo: &Repository, options: &CheckOptions) -> Result<Vec<Finding>> {
+    // Staged content is checked against the staged `.gitattributes`, as committed
+    let (files, source) = match &options.scope {
+        CheckScope::Staged => (staged_files(repo)?, AttrSource::Index),
+        CheckScope::All => (tracked_files(repo)?, AttrSource::Worktree),
+        CheckScope::Paths(paths) => (given_files(repo, paths)?, AttrSource::Worktree),
+    };
+
+    Ok(files
+        .iter()
+        .flat_map(|(path, content)| check_file(repo, path, content, source, options.marker_size))
+        .collect())
+}
+
+/// Print every leftover marker as `file:line: message`, returning the process exit code
+pub fn run_check(repo: &Repository, options: &CheckOptions) -> Result<i32> {
+    let findings = check_repository(repo, options)?;
+
+    for finding in &findings {
+        println!(
+            "{}:{}: {}",
+            finding.path.display(),
+            finding.marker.line() + 1,
+            finding.marker.description()
+        );
+    }
+    if findings.is_empty() {
+        Ok(0)
+    } else {
+        eprintln!(
+            "Found {} leftover conflict marker{}",
+            findings.len(),
+            if findings.len() == 1 { "" } else { "s" }
+        );
+        Ok(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::DEFAULT_MARKER_SIZE;
+    use crate::test_support::git;
+    use tempfile::TempDir;
+
+    const LEFTOVER: &str = "a\n<<<<<<< HEAD\nb\n=======\nc\n>>>>>>> feature\n";
+
+    fn options(scope: CheckScope) -> CheckOptions {
+        CheckOptions {
+            scope,
+            marker_size: DEFAULT_MARKER_SIZE,
+        }
+    }
+
+    /// Repository with one committed clean file
+    fn repo() -> (TempDir, Repository) {
+        let dir = TempDir::new().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        fs::write(dir.path().join("clean.txt"), "clean\n").unwrap();
+        git(dir.path(), &["add", "."]);
+        git(dir.path(), &["commit", "-q", "-m", "init"]);
+        let repo = Repository::open(dir.path()).unwrap();
+        (dir, repo)
+    }
+
+    #[test]
+    fn test_check_staged_files() {
+        let (dir, repo) = repo();
+        fs::write(dir.path().join("bad.txt"), LEFTOVER).unwrap();
+        git(dir.path(), &["add", "bad.txt"]);
+        // Unstaged fixes don't count: the staged blob is what gets committed
+        fs::write(dir.path().join("bad.txt"), "fixed\n").unwrap();
+
+        let findings = check_repository(&repo, &options(CheckScope::Staged)).unwrap();
+        assert_eq!(
+            findings,
+            vec![Finding {
+                path: PathBuf::from("bad.txt"),
+                marker: LeftoverMarker::Block {
+                    start_line: 1,
+                    end_line: 5
+                },
+            }]
+        );
+        assert_eq!(run_check(&repo, &options(CheckScope::Staged)).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_check_all_and_paths() {
+        let (dir, repo) = repo();
+        fs::write(dir.path().join("clean.txt"), LEFTOVER).unwrap();
+
+        assert!(check_repository(&repo, &options(CheckScope::Staged))
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            check_repository(&repo, &options(CheckScope::All))
+                .unwrap()
+                .len(),
+            1
+        );
+        let paths = CheckScope::Paths(vec![dir.path().join("clean.txt")]);
+        assert_eq!(check_repository(&repo, &options(paths)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_check_honors_attributes() {
+        let (dir, repo) = repo();
+        fs::write(
+            dir.path().join(".gitattributes"),
+            "fixtures/* -saki-check\n*.adoc conflict-marker-size=9\n",
+        )
+        .unwrap();
+        fs::create_dir(dir.path().join("fixtures")).unwrap();
+        fs::write(dir.path().join("fixtures/conflict.txt"), LEFTOVER).unwrap();
+        fs::write(
+            dir.path().join("doc.adoc"),
+            "<<<<<<< not a marker here\n<<<<<<<<< HEAD\n>>>>>>>>> x\n",
+        )
+        .unwrap();
+        git(dir.path(), &["add", "."]);
+
+        let findings = check_repository(&repo, &options(CheckScope::Staged)).unwrap();
+        assert_eq!(
+            findings,
+            vec![Finding {
+                path: PathBuf::from("doc.adoc"),
+                marker: LeftoverMarker::Block {
+                    start_line: 1,
+                    end_line: 2
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn test_check_staged_uses_staged_attributes() {
+        let (dir, repo) = repo();
+        fs::write(dir.path().join("bad.txt"), LEFTOVER).unwrap();
+        git(dir.path(), &["add", "bad.txt"]);
+        // Turning the check off without staging it doesn't skip the staged file
+        fs::write(dir.path().join(".gitattributes"), "bad.txt -saki-check\n").unwrap();
+
+        assert_eq!(
+            check_repository(&repo, &options(CheckScope::Staged))
+                .unwrap()
+                .len(),
+            1
+        );
+        let paths = CheckScope::Paths(vec![dir.path().join("bad.txt")]);
+        assert!(check_repository(&repo, &options(paths)).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,248 @@
+use anyhow::{Context, Result};
+use git2::Repository;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Line identifying hooks written by saki
+const HOOK_SIGNATURE: &str = "# Installed by saki";
+
+/// Name of the pre-commit hook
+const HOOK_NAME: &str = "pre-commit";
+
+/// Name an existing pre-commit hook is moved to, so ours can run it first
+const CHAINED_HOOK_NAME: &str = "pre-commit.saki-chained";
+
+/// What `install_hook` did
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookInstall {
+    /// Wrote a new hook
+    Installed,
+    /// Moved an existing hook aside and installed ours in front of it
+    Chained,
+    /// Our hook was already there and got rewritten
+    Updated,
+}
+
+/// Directory git runs hooks from, honoring `core.hooksPath`
+pub fn hooks_dir(repo: &Repository) -> Result<PathBuf> {
+    let config = repo.config().context("Failed to read git config")?;
+
+    match config.get_path("core.hooksPath") {
+        // Relative paths are resolved from where hooks run: the top of the worktree
+        Ok(path) if path.is_relative() => Ok(repo.workdir().unwrap_or(repo.path()).join(path)),
+        Ok(path) => Ok(path),
+        // Hooks live in the git dir shared by all worktrees, not a worktree's own
+        Err(_) => Ok(repo.commondir().join("hooks")),
+    }
+}
+
+/// Quote a string for `sh`
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// Script for the pre-commit hook, running `saki_command check` after any chained hook
+fn hook_script(saki_command: &str) -> String {
+    format!(
+        "#!/bin/sh\n\
+         {signature} (saki hooks install); remove with: saki hooks uninstall\n\
+         chained=\"$(dirname \"$0\")/{chained}\"\n\
+         if [ -x \"$chained\" ]; then\n\
+         \x20   \"$chained\" \"$@\" || exit $?\n\
+         fi\n\
+         exec {saki} check\n",
+        signature = HOOK_SIGNATURE,
+        chained = CHAINED_HOOK_NAME,
+        saki = shell_quote(saki_command),
+    )
+}
+
+/// Check if the hook at `path` was written by saki
+fn is_saki_hook(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|content| content.contains(HOOK_SIGNATURE))
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+        .with_context(|| format!("Failed to make {} executable", path.display()))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// Install the pre-commit hook, keeping any existing hook as a chained one
+pub fn install_hook(repo: &Repository, saki_command: &str) -> Result<HookInstall> {
+    let dir = hooks_dir(repo)?;
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create hooks directory: {}", dir.display()))?;
+    let hook = dir.join(HOOK_NAME);
+    let chained = dir.join(CHAINED_HOOK_NAME);
+
+    let outcome = if is_saki_hook(&hook) {
+        HookInstall::Updated
+    } else if hook.exists() {
+        if chained.exists() {
+            anyhow::bail!(
+                "Both {} and {} exist; move one of them out of the way first",
+                hook.display(),
+                chained.display()
+            );
+        }
+        fs::rename(&hook, &chained)
+            .with_context(|| format!("Failed to move existing hook to {}", chained.display()))?;
+        HookInstall::Chained
+    } else {
+        HookInstall::Installed
+    };
+
+    fs::write(&hook, hook_script(saki_command))
+        .with_context(|| format!("Failed to write hook: {}", hook.display()))?;
+    make_executable(&hook)?;
+    Ok(outcome)
+}
+
+/// Remove the pre-commit hook, putting back the hook it chained to
+pub fn uninstall_hook(repo: &Repository) -> Result<()> {
+    let dir = hooks_dir(repo)?;
+    let hook = dir.join(HOOK_NAME);
+    let chained = dir.join(CHAINED_HOOK_NAME);
+
+    if !hook.exists() {
+        anyhow::bail!("No pre-commit hook installed in {}", dir.display());
+    }
+    if !is_saki_hook(&hook) {
+        anyhow::bail!(
+            "{} was not installed by saki; leaving it alone",
+            hook.display()
+        );
+    }
+
+    fs::remove_file(&hook).with_context(|| format!("Failed to remove {}", hook.display()))?;
+    if chained.exists() {
+        fs::rename(&chained, &hook)
+            .with_context(|| format!("Failed to restore {}", hook.display()))?;
+    }
+    Ok(())
+}
+
+/// Install the hook, pointing it at the running saki binary
+pub fn run_hooks_install(repo: &Repository) -> Result<i32> {
+    let saki = std::env::current_exe()
+        .ok()
+        .and_then(|path| path.to_str().map(str::to_string))
+        .unwrap_or_else(|| "saki".to_string());
+    let hook = hooks_dir(repo)?.join(HOOK_NAME);
+
+    match install_hook(repo, &saki)? {
+        HookInstall::Installed => println!("Installed {}", hook.display()),
+        HookInstall::Chained => println!(
+            "Installed {} (the existing hook still runs first, as {})",
+            hook.display(),
+            CHAINED_HOOK_NAME
+        ),
+        HookInstall::Updated => println!("Updated {}", hook.display()),
+    }
+    Ok(0)
+}
+
+/// Remove the hook
+pub fn run_hooks_uninstall(repo: &Repository) -> Result<i32> {
+    uninstall_hook(repo)?;
+    println!("Removed the saki pre-commit hook");
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, try_git};
+    use tempfile::TempDir;
+
+    fn repo() -> (TempDir, Repository) {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        (dir, repo)
+    }
+
+    #[test]
+    fn test_hooks_dir_default_and_hooks_path() {
+        let (dir, repo) = repo();
+        assert_eq!(hooks_dir(&repo).unwrap(), repo.path().join("hooks"));
+
+        git(dir.path(), &["config", "core.hooksPath", ".githooks"]);
+        let repo = Repository::open(dir.path()).unwrap();
+        assert_eq!(
+            hooks_dir(&repo).unwrap(),
+            repo.workdir().unwrap().join(".githooks")
+        );
+    }
+
+    #[test]
+    fn test_hooks_dir_in_worktree_uses_common_dir() {
+        let (dir, repo) = repo();
+        git(dir.path(), &["commit", "-q", "--allow-empty", "-m", "init"]);
+        let worktree = dir.path().join("wt");
+        git(dir.path(), &["worktree", "add", "-q", "wt"]);
+
+        let worktree_repo = Repository::open(&worktree).unwrap();
+        assert!(worktree_repo.is_worktree());
+        assert_eq!(
+            hooks_dir(&worktree_repo).unwrap().canonicalize().unwrap(),
+            repo.path().join("hooks").canonicalize().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_install_and_uninstall() {
+        let (_dir, repo) = repo();
+        let hook = hooks_dir(&repo).unwrap().join(HOOK_NAME);
+
+        assert_eq!(install_hook(&repo, "saki").unwrap(), HookInstall::Installed);
+        let script = fs::read_to_string(&hook).unwrap();
+        assert!(script.contains("exec 'saki' check"));
+        assert_eq!(install_hook(&repo, "saki").unwrap(), HookInstall::Updated);
+
+        uninstall_hook(&repo).unwrap();
+        assert!(!hook.exists());
+        assert!(uninstall_hook(&repo).is_err());
+    }
+
+    #[test]
+    fn test_install_chains_existing_hook() {
+        let (_dir, repo) = repo();
+        let dir = hooks_dir(&repo).unwrap();
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(HOOK_NAME), "#!/bin/sh\necho mine\n").unwrap();
+
+        assert_eq!(install_hook(&repo, "saki").unwrap(), HookInstall::Chained);
+        assert!(is_saki_hook(&dir.join(HOOK_NAME)));
+        assert_eq!(
+            fs::read_to_string(dir.join(CHAINED_HOOK_NAME)).unwrap(),
+            "#!/bin/sh\necho mine\n"
+        );
+
+        uninstall_hook(&repo).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.join(HOOK_NAME)).unwrap(),
+            "#!/bin/sh\necho mine\n"
+        );
+        assert!(!dir.join(CHAINED_HOOK_NAME).exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_installed_hook_runs_on_commit() {
+        let (dir, repo) = repo();
+        // Stand-in for saki: fail like `saki check` would
+        install_hook(&repo, "false").unwrap();
+        fs::write(dir.path().join("a.txt"), "a\n").unwrap();
+        git(dir.path(), &["add", "."]);
+
+        assert!(!try_git(dir.path(), &["commit", "-q", "-m", "blocked"]));
+    }
+}
@@ -0,0 +1,230 @@
+use anyhow::{Context, Result};
+use git2::Repository;
+use serde::Serialize;
+
+use crate::domain::{ConflictedFile, FileConflictKind, GitOperation};
+use crate::git::detector::repo_relative_path;
+use crate::git::{detect_git_operation, find_conflicted_files, load_conflicted_files};
+
+/// Every conflicted file in the repository, as emitted by `saki list --json`
+#[derive(Debug, Clone, Serialize)]
+pub struct ConflictReport {
+    /// Operation in progress: "merge", "rebase", "interactive_rebase" or null
+    pub operation: Option<&'static str>,
+    pub files: Vec<FileReport>,
+    /// Conflicted files that could not be loaded
+    pub errors: Vec<LoadError>,
+}
+
+/// A conflicted file and its hunks
+#[derive(Debug, Clone, Serialize)]
+pub struct FileReport {
+    /// Path relative to the repository root
+    pub path: String,
+    /// "content" for hunk conflicts, otherwise the kind of whole-file conflict
+    pub kind: &'static str,
+    /// Human-readable summary of a whole-file conflict
+    pub description: Option<String>,
+    pub hunks: Vec<HunkReport>,
+}
+
+/// One conflict hunk inside a file
+#[derive(Debug, Clone, Serialize)]
+pub struct HunkReport {
+    /// Line of the `<<<<<<<` marker (1-based)
+    pub start_line: usize,
+    /// Line of the `>>>>>>>` marker (1-based)
+    pub end_line: usize,
+    pub current: String,
+    pub incoming: String,
+    pub base: Option<String>,
+    pub labels: HunkLabels,
+}
+
+/// Text following each conflict marker (branch names, commit subjects)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HunkLabels {
+    pub current: Option<String>,
+    pub base: Option<String>,
+    pub incoming: Option<String>,
+}
+
+/// A file that is conflicted but could not be parsed
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadError {
+    pub message: String,
+}
+
+/// Machine-readable name of a git operation
+fn operation_name(operation: GitOperation) -> Option<&'static str> {
+    match operation {
+        GitOperation::None => None,
+        GitOperation::Merge => Some("merge"),
+        GitOperation::Rebase => Some("rebase"),
+        GitOperation::RebaseInteractive => Some("interactive_rebase"),
+    }
+}
+
+/// Machine-readable name of a whole-file conflict kind
+fn kind_name(kind: &FileConflictKind) -> &'static str {
+    match kind {
+        FileConflictKind::DeletedByUs => "deleted_by_us",
+        FileConflictKind::DeletedByThem => "deleted_by_them",
+        FileConflictKind::BothAdded => "both_added",
+        FileConflictKind::RenamedDifferently { .. } => "renamed_differently",
+        FileConflictKind::Binary { .. } => "binary",
+    }
+}
+
+/// Build the report entry for a loaded file
+pub fn file_report(repo: &Repository, file: &ConflictedFile) -> FileReport {
+    let path = repo_relative_path(repo, &file.path)
+        .unwrap_or_else(|_| file.path.clone())
+        .to_string_lossy()
+        .into_owned();
+    FileReport {
+        path,
+        kind: file.file_conflict.as_ref().map_or("content", kind_name),
+        description: file.file_conflict.as_ref().map(|kind| kind.description()),
+        hunks: file
+            .conflicts
+            .iter()
+            .map(|hunk| HunkReport {
+                start_line: hunk.start_line + 1,
+                end_line: hunk.end_line + 1,
+                current: hunk.current.clone(),
+                incoming: hunk.incoming.clone(),
+                base: hunk.base.clone(),
+                labels: HunkLabels {
+                    current: hunk.current_label.clone(),
+                    base: hunk.base_label.clone(),
+                    incoming: hunk.incoming_label.clone(),
+                },
+            })
+            .collect(),
+    }
+}
+
+/// Collect every conflicted file in the repository
+///
+/// Files that fail to load are listed in `errors`; failing to read the
+/// repository state at all is an error, not an empty report.
+pub fn conflict_report(repo: &Repository) -> Result<ConflictReport> {
+    let operation = operation_name(detect_git_operation(repo)?);
+    let paths = find_conflicted_files(repo)?;
+
+    let mut report = ConflictReport {
+        operation,
+        files: Vec::new(),
+        errors: Vec::new(),
+    };
+    for result in load_conflicted_files(repo, &paths) {
+        match result {
+            Ok(file) => report.files.push(file_report(repo, &file)),
+            Err(e) => report.errors.push(LoadError {
+                message: format!("{:#}", e),
+            }),
+        }
+    }
+    Ok(report)
+}
+
+/// Print the conflict report, as JSON or as a short human-readable summary
+pub fn run_list(repo: &Repository, json: bool) -> Result<i32> {
+    let report = conflict_report(repo)?;
+
+    if json {
+        let output =
+            serde_json::to_string_pretty(&report).context("Failed to serialize conflict report")?;
+        println!("{}", output);
+        return Ok(0);
+    }
+
+    match report.operation {
+        Some(operation) => println!("{} in progress", operation),
+        None => println!("No merge or rebase in progress"),
+    }
+    for file in &report.files {
+        match &file.description {
+            Some(description) => println!("  {}: {}", file.path, description),
+            None => println!(
+                "  {}: {} conflict{}",
+                file.path,
+                file.hunks.len(),
+                if file.hunks.len() == 1 { "" } else { "s" }
+            ),
+        }
+    }
+    for error in &report.errors {
+        eprintln!("Warning: {}", error.message);
+    }
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, git};
+    use tempfile::TempDir;
+
+    /// Merge in progress with one diff3-style content conflict in `file.txt`
+    fn conflicted_repo() -> TempDir {
+        test_support::conflicted_repo("diff3", "a\nbase\nb\n", "a\nours\nb\n", "a\ntheirs\nb\n")
+    }
+
+    #[test]
+    fn test_conflict_report() {
+        let dir = conflicted_repo();
+        let repo = Repository::open(dir.path()).unwrap();
+        let report = conflict_report(&repo).unwrap();
+
+        assert_eq!(report.operation, Some("merge"));
+        assert!(report.errors.is_empty());
+        assert_eq!(report.files.len(), 1);
+
+        let file = &report.files[0];
+        assert_eq!(file.path, "file.txt");
+        assert_eq!(file.kind, "content");
+
+        let hunk = &file.hunks[0];
+        assert_eq!((hunk.start_line, hunk.end_line), (2, 8));
+        assert_eq!(hunk.current, "ours");
+        assert_eq!(hunk.base.as_deref(), Some("base"));
+        assert_eq!(hunk.incoming, "theirs");
+        assert_eq!(hunk.labels.current.as_deref(), Some("HEAD"));
+        assert_eq!(hunk.labels.incoming.as_deref(), Some("feature"));
+        assert!(hunk.labels.base.is_some());
+    }
+
+    #[test]
+    fn test_report_without_operation() {
+        let dir = TempDir::new().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        let repo = Repository::open(dir.path()).unwrap();
+        let report = conflict_report(&repo).unwrap();
+
+        assert_eq!(report.operation, None);
+        assert!(report.files.is_empty());
+    }
+
+    #[test]
+    fn test_report_fails_on_unreadable_status() {
+        let dir = TempDir::new().unwrap();
+        git(dir.path(), &["init", "-q", "--bare"]);
+        let repo = Repository::open(dir.path()).unwrap();
+
+        // A bare repository has no worktree status to read
+        assert!(conflict_report(&repo).is_err());
+    }
+
+    #[test]
+    fn test_report_serializes_to_json() {
+        let dir = conflicted_repo();
+        let repo = Repository::open(dir.path()).unwrap();
+        let json = serde_json::to_value(conflict_report(&repo).unwrap()).unwrap();
+
+        assert_eq!(json["operation"], "merge");
+        assert_eq!(json["files"][0]["hunks"][0]["labels"]["current"], "HEAD");
+        assert_eq!(json["files"][0]["description"], serde_json::Value::Null);
+    }
+}
@@ -0,0 +1,237 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+
+use crate::app::AppState;
+use crate::config::Config;
+use crate::domain::{ConflictedFile, GitOperation};
+use crate::git::attributes::{encoding_for, marker_size_for};
+use crate::git::detector::open_repository;
+use crate::git::encoding::{decode, detect_encoding, encode};
+use crate::git::{apply_resolutions, merge_file, parse_conflict_content, DEFAULT_MARKER_SIZE};
+use crate::tui::run_app;
+
+/// Build the conflicted file for `git mergetool` from its four paths
+///
+/// Needs no repository state: the sides are merged again in memory with libgit2.
+/// Returns `None` when the sides merge cleanly (the result is already in `merged`).
+pub fn load_mergetool_file(
+    base: &Path,
+    local: &Path,
+    remote: &Path,
+    merged: &Path,
+) -> Result<Option<ConflictedFile>> {
+    let read = |path: &Path| {
+        fs::read(path).with_context(|| format!("Failed to read file: {}", path.display()))
+    };
+
+    let local_content = read(local)?;
+    let remote_content = read(remote)?;
+    // git passes a missing or empty base for add/add conflicts
+    let base_content = fs::read(base).ok();
+    // Honor `conflict-marker-size` and `working-tree-encoding` when run from inside the repository
+    let (marker_size, declared_encoding) = match open_repository() {
+        Ok(repo) => (marker_size_for(&repo, merged), encoding_for(&repo, merged)),
+        Err(_) => (DEFAULT_MARKER_SIZE, None),
+    };
+
+    // Merge as UTF-8 and encode the result back, so UTF-16 sides merge line by line
+    let encoding = detect_encoding(
+        &[local_content.as_slice(), remote_content.as_slice()].concat(),
+        declared_encoding.as_deref(),
+    )?;
+    let decode_side = |content: &[u8]| {
+        decode(content, encoding)
+            .map(String::into_bytes)
+            .with_context(|| format!("Failed to decode the sides of {}", merged.display()))
+    };
+    let base_content = base_content.as_deref().map(decode_side).transpose()?;
+
+    let content = merge_file(
+        &decode_side(&local_content)?,
+        base_content.as_deref(),
+        &decode_side(&remote_content)?,
+        ["LOCAL", "BASE", "REMOTE"],
+        marker_size,
+    )?;
+
+    match parse_conflict_content(merged, content.clone(), marker_size) {
+        Ok(file) => Ok(Some(file.with_encoding(encoding))),
+        Err(_) => {
+            fs::write(merged, encode(&content, encoding)?)
+                .with_context(|| format!("Failed to write file: {}", merged.display()))?;
+            Ok(None)
+        }
+    }
+}
+
+/// Write the resolved file and get the exit code git expects (non-zero if unresolved)
+pub fn finish_mergetool(file: &ConflictedFile) -> Result<i32> {
+    if !file.is_fully_resolved() {
+        return Ok(1);
+    }
+
+    apply_resolutions(file)?;
+    Ok(0)
+}
+
+/// Run saki as a `git mergetool` backend with the given settings, returning the
+/// process exit code
+pub fn run_mergetool(
+    base: &Path,
+    local: &Path,
+    remote: &Path,
+    merged: &Path,
+    config: Config,
+) -> Result<i32> {
+    let file = match load_mergetool_file(base, local, remote, merged)? {
+        Some(file) => file,
+        None => return Ok(0),
+    };
+
+    let state = run_app(mergetool_state(file, config))?;
+    match state.files.first() {
+        Some(file) => finish_mergetool(file),
+        None => Ok(1),
+    }
+}
+
+/// TUI state for a mergetool session
+///
+/// Never stages: git mergetool stages `$MERGED` itself once saki exits, and
+/// `$MERGED` may not even be inside a repository.
+fn mergetool_state(file: ConflictedFile, mut config: Config) -> AppState {
+    config.auto_stage = false;
+    let mut state = AppState::new(vec![file], GitOperation::Merge);
+    state.set_config(config);
+    state.auto_resolve();
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Resolution;
+    use tempfile::TempDir;
+
+    /// Write the four mergetool files and return the temp dir holding them
+    fn mergetool_files(base: &str, local: &str, remote: &str) -> TempDir {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("BASE"), base).unwrap();
+        fs::write(dir.path().join("LOCAL"), local).unwrap();
+        fs::write(dir.path().join("REMOTE"), remote).unwrap();
+        fs::write(dir.path().join("MERGED"), "").unwrap();
+        dir
+    }
+
+    fn load(dir: &TempDir) -> Result<Option<ConflictedFile>> {
+        let path = dir.path();
+        load_mergetool_file(
+            &path.join("BASE"),
+            &path.join("LOCAL"),
+            &path.join("REMOTE"),
+            &path.join("MERGED"),
+        )
+    }
+
+    #[test]
+    fn test_load_conflicting_sides() {
+        let dir = mergetool_files("a\nb\nc\n", "a\nours\nc\n", "a\ntheirs\nc\n");
+        let file = load(&dir).unwrap().unwrap();
+
+        assert_eq!(file.path, dir.path().join("MERGED"));
+        assert_eq!(file.conflicts.len(), 1);
+        assert_eq!(file.conflicts[0].current, "ours");
+        assert_eq!(file.conflicts[0].base.as_deref(), Some("b"));
+        assert_eq!(file.conflicts[0].incoming, "theirs");
+    }
+
+    #[test]
+    fn test_load_clean_merge_writes_result() {
+        let dir = mergetool_files("a\nb\nc\n", "A\nb\nc\n", "a\nb\nC\n");
+        assert!(load(&dir).unwrap().is_none());
+        assert_eq!(
+            fs::read_to_string(dir.path().join("MERGED")).unwrap(),
+            "A\nb\nC\n"
+        );
+    }
+
+    #[test]
+    fn test_load_without_base() {
+        let dir = mergetool_files("", "ours\n", "theirs\n");
+        fs::remove_file(dir.path().join("BASE")).unwrap();
+
+        let file = load(&dir).unwrap().unwrap();
+        assert_eq!(file.conflicts.len(), 1);
+    }
+
+    #[test]
+    fn test_latin1_sides_are_written_back_as_latin1() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("BASE"), b"caf\xe9\nb\n").unwrap();
+        fs::write(dir.path().join("LOCAL"), b"caf\xe9\nna\xefve\n").unwrap();
+        fs::write(dir.path().join("REMOTE"), b"caf\xe9\nd\xe9j\xe0\n").unwrap();
+        fs::write(dir.path().join("MERGED"), "").unwrap();
+
+        let mut file = load(&dir).unwrap().unwrap();
+        assert_eq!(file.encoding.as_deref(), Some("windows-1252"));
+        assert_eq!(file.conflicts[0].incoming, "déjà");
+
+        file.set_resolution(0, Resolution::Incoming);
+        assert_eq!(finish_mergetool(&file).unwrap(), 0);
+        assert_eq!(
+            fs::read(dir.path().join("MERGED")).unwrap(),
+            b"caf\xe9\nd\xe9j\xe0\n"
+        );
+    }
+
+    #[test]
+    fn test_mergetool_session_never_stages() {
+        let dir = mergetool_files("a\nb\nc\n", "a\nours\nc\n", "a\ntheirs\nc\n");
+        let file = load(&dir).unwrap().unwrap();
+        let config = Config {
+            auto_stage: true,
+            ..Config::default()
+        };
+
+        let state = mergetool_state(file, config);
+        assert!(!state.config.auto_stage);
+        assert!(state.config.auto_save);
+    }
+
+    #[test]
+    fn test_finish_exit_codes() {
+        let dir = mergetool_files("a\nb\nc\n", "a\nours\nc\n", "a\ntheirs\nc\n");
+        let mut file = load(&dir).unwrap().unwrap();
+        assert_eq!(finish_mergetool(&file).unwrap(), 1);
+
+        file.set_resolution(0, Resolution::Incoming);
+        assert_eq!(finish_mergetool(&file).unwrap(), 0);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("MERGED")).unwrap(),
+            "a\ntheirs\nc\n"
+        );
+    }
+
+    #[test]
+    fn test_finish_waits_for_auto_resolution_review() {
+        let dir = mergetool_files("a\nb\nc\n", "a\nsame\nc\n", "a\nsame \nc\n");
+        let mut file = load(&dir).unwrap().unwrap();
+        let merged = fs::read_to_string(dir.path().join("MERGED")).unwrap();
+        assert_eq!(file.auto_resolve(), 1);
+
+        // Quitting before accepting leaves $MERGED conflicted
+        assert_eq!(finish_mergetool(&file).unwrap(), 1);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("MERGED")).unwrap(),
+            merged
+        );
+
+        file.confirm_auto_resolutions();
+        assert_eq!(finish_mergetool(&file).unwrap(), 0);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("MERGED")).unwrap(),
+            "a\nsame\nc\n"
+        );
+    }
+}
@@ -0,0 +1,13 @@
+pub mod check;
+pub mod hooks;
+pub mod list;
+pub mod mergetool;
+pub mod resolve;
+
+pub use check::{check_repository, run_check, CheckOptions, CheckScope, Finding};
+pub use hooks::{
+    hooks_dir, install_hook, run_hooks_install, run_hooks_uninstall, uninstall_hook, HookInstall,
+};
+pub use list::{conflict_report, run_list, ConflictReport};
+pub use mergetool::{finish_mergetool, load_mergetool_file, run_mergetool};
+pub use resolve::{run_resolve, select_resolutions, HunkSelector, ResolveOptions};
@@ -0,0 +1,497 @@
+use anyhow::Result;
+use git2::Repository;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::domain::{AutoResolution, ConflictedFile, FileConflictKind, FileResolution, Resolution};
+use crate::git::{
+    apply_file_resolution, apply_partial_resolutions, apply_resolutions, load_conflicted_files,
+    mark_resolved,
+};
+
+/// Parse a side name as used on the command line
+fn parse_side(side: &str) -> Result<Resolution, String> {
+    match side {
+        "ours" | "current" => Ok(Resolution::Current),
+        "theirs" | "incoming" => Ok(Resolution::Incoming),
+        "both" => Ok(Resolution::Both),
+        other => Err(format!(
+            "unknown side '{}' (expected ours, theirs or both)",
+            other
+        )),
+    }
+}
+
+/// Resolution for a single conflict, given as `N=side` (N starts at 1)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HunkSelector {
+    /// Conflict number (1-based, as shown in the TUI)
+    pub number: usize,
+    pub resolution: Resolution,
+}
+
+impl FromStr for HunkSelector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (number, side) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected N=SIDE, got '{}'", s))?;
+        let number = number
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|n| *n > 0)
+            .ok_or_else(|| format!("invalid conflict number '{}'", number))?;
+
+        Ok(Self {
+            number,
+            resolution: parse_side(side.trim())?,
+        })
+    }
+}
+
+/// What to resolve and how
+#[derive(Debug, Clone, Default)]
+pub struct ResolveOptions {
+    /// Resolution for every conflict not picked by a hunk selector
+    pub default: Option<Resolution>,
+    /// Resolutions for specific conflicts
+    pub hunks: Vec<HunkSelector>,
+    /// Stage the files once resolved
+    pub stage: bool,
+    /// Resolve trivial conflicts (identical, trailing whitespace only, one side unchanged) first,
+    /// and write files even if other conflicts are left
+    pub auto: bool,
+}
+
+/// Pick the resolutions for a file from the options
+///
+/// Fails if a selector points past the last conflict or conflicts are left
+/// unresolved (unless `auto` allows a partial resolution). Passing `auto` is
+/// consent to the automatic resolutions, so they come back confirmed; the
+/// returned list says which conflicts were resolved automatically and why.
+pub fn select_resolutions(
+    file: &mut ConflictedFile,
+    options: &ResolveOptions,
+) -> Result<Vec<Option<AutoResolution>>> {
+    // Writing the conflicts rebuilt from the index would throw the hand edits away
+    if file.is_rebuilt_from_index() {
+        anyhow::bail!(
+            "{}: conflict markers were edited by hand; resolve it in the TUI, or restore the markers with `git checkout -m`",
+            file.path_string()
+        );
+    }
+    if let Some(kind) = file.file_conflict.clone() {
+        if !options.hunks.is_empty() {
+            anyhow::bail!(
+                "{}: --hunk does not apply to a whole-file conflict ({})",
+                file.path_string(),
+                kind.description()
+            );
+        }
+        let resolution = match (&kind, &options.default) {
+            // Taking the side that deleted the file accepts the deletion
+            (FileConflictKind::DeletedByUs, Some(Resolution::Current))
+            | (FileConflictKind::DeletedByThem, Some(Resolution::Incoming)) => {
+                FileResolution::Delete
+            }
+            (_, Some(Resolution::Current)) => FileResolution::KeepOurs,
+            (_, Some(Resolution::Incoming)) => FileResolution::KeepTheirs,
+            _ => anyhow::bail!(
+                "{}: whole-file conflict ({}) needs --ours or --theirs",
+                file.path_string(),
+                kind.description()
+            ),
+        };
+        file.set_file_resolution(resolution);
+        if !file.is_fully_resolved() {
+            anyhow::bail!(
+                "{}: '{}' does not apply to this conflict ({})",
+                file.path_string(),
+                kind.resolution_label(resolution),
+                kind.description()
+            );
+        }
+        return Ok(Vec::new());
+    }
+
+    if options.auto {
+        file.auto_resolve();
+    }
+
+    for selector in &options.hunks {
+        if selector.number > file.conflicts.len() {
+            anyhow::bail!(
+                "{}: no conflict {} (file has {})",
+                file.path_string(),
+                selector.number,
+                file.conflicts.len()
+            );
+        }
+        file.set_resolution(selector.number - 1, selector.resolution.clone());
+    }
+
+    if let Some(default) = &options.default {
+        for index in 0..file.conflicts.len() {
+            if file.resolutions[index].is_none() {
+                file.set_resolution(index, default.clone());
+            }
+        }
+    }
+
+    let autos = file.auto_resolved.clone();
+    file.confirm_auto_resolutions();
+
+    if options.auto && file.resolved_count() > 0 {
+        return Ok(autos);
+    }
+    if !file.is_fully_resolved() {
+        anyhow::bail!(
+            "{}: {} of {} conflicts left unresolved (use --ours, --theirs, --both or more --hunk)",
+            file.path_string(),
+            file.total_conflicts() - file.resolved_count(),
+            file.total_conflicts()
+        );
+    }
+    Ok(autos)
+}
+
+/// Resolve one loaded file, write it and optionally stage it
+///
+/// Returns the automatic resolutions picked, as `select_resolutions` does.
+fn resolve_one(
+    repo: &Repository,
+    file: &mut ConflictedFile,
+    options: &ResolveOptions,
+) -> Result<Vec<Option<AutoResolution>>> {
+    let autos = select_resolutions(file, options)?;
+
+    if file.is_file_conflict() {
+        apply_file_resolution(repo, file, options.stage)?;
+        return Ok(autos);
+    }
+
+    if !file.is_fully_resolved() {
+        // Only reachable with --auto: write what could be resolved, leave it unstaged
+        apply_partial_resolutions(file)?;
+        return Ok(autos);
+    }
+    apply_resolutions(file)?;
+    if options.stage {
+        mark_resolved(repo, &file.path)?;
+    }
+    Ok(autos)
+}
+
+/// Describe what was done to a resolved file, `autos` being the automatic resolutions
+fn describe(file: &ConflictedFile, autos: &[Option<AutoResolution>], stage: bool) -> String {
+    let how = match (&file.file_conflict, file.file_resolution) {
+        (Some(kind), Some(resolution)) => kind.resolution_label(resolution),
+        _ => {
+            let mut sides: Vec<String> = file
+                .resolutions
+                .iter()
+                .enumerate()
+                .filter_map(|(index, resolution)| {
+                    let resolution = resolution.as_ref()?;
+                    Some(match autos.get(index).copied().flatten() {
+                        Some(reason) => format!("auto, {}", reason.as_str()),
+                        None => resolution.as_str().to_string(),
+                    })
+                })
+                .collect();
+            sides.dedup();
+            format!(
+                "{} conflict{}: {}",
+                file.resolved_count(),
+                if file.resolved_count() == 1 { "" } else { "s" },
+                sides.join(", ")
+            )
+        }
+    };
+
+    if !file.is_fully_resolved() {
+        let left = file.total_conflicts() - file.resolved_count();
+        return format!(
+            "Partially resolved {} ({}; {} left)",
+            file.path_string(),
+            how,
+            left
+        );
+    }
+    let staged = if stage { ", staged" } else { "" };
+    format!("Resolved {} ({}{})", file.path_string(), how, staged)
+}
+
+/// Resolve the given files without the TUI, returning the process exit code
+///
+/// Every file is attempted; the exit code is non-zero if any of them failed.
+pub fn run_resolve(repo: &Repository, paths: &[PathBuf], options: &ResolveOptions) -> Result<i32> {
+    let mut failed = false;
+
+    for result in load_conflicted_files(repo, paths) {
+        let outcome = result.and_then(|mut file| {
+            let autos = resolve_one(repo, &mut file, options)?;
+            Ok((file, autos))
+        });
+
+        match outcome {
+            Ok((file, autos)) => {
+                println!("{}", describe(&file, &autos, options.stage));
+                // Conflicts left by --auto still need attention
+                failed |= !file.is_fully_resolved();
+            }
+            Err(e) => {
+                eprintln!("Error: {:#}", e);
+                failed = true;
+            }
+        }
+    }
+
+    Ok(i32::from(failed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ConflictHunk;
+    use crate::test_support;
+    use std::fs;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    /// Repository where `file.txt` has two conflicts
+    fn conflicted_repo() -> TempDir {
+        test_support::conflicted_repo(
+            "merge",
+            "a\n1\nb\nc\nd\ne\n2\nf\n",
+            "a\nours1\nb\nc\nd\ne\nours2\nf\n",
+            "a\ntheirs1\nb\nc\nd\ne\ntheirs2\nf\n",
+        )
+    }
+
+    fn two_conflicts() -> ConflictedFile {
+        ConflictedFile::new(
+            PathBuf::from("f.txt"),
+            vec![
+                ConflictHunk::new("a".to_string(), "b".to_string(), 0, 4),
+                ConflictHunk::new("c".to_string(), "d".to_string(), 5, 9),
+            ],
+            String::new(),
+        )
+    }
+
+    #[test]
+    fn test_parse_hunk_selector() {
+        assert_eq!(
+            "2=theirs".parse::<HunkSelector>().unwrap(),
+            HunkSelector {
+                number: 2,
+                resolution: Resolution::Incoming
+            }
+        );
+        assert!("0=ours".parse::<HunkSelector>().is_err());
+        assert!("1=mine".parse::<HunkSelector>().is_err());
+        assert!("ours".parse::<HunkSelector>().is_err());
+    }
+
+    #[test]
+    fn test_select_hunks_then_default() {
+        let mut file = two_conflicts();
+        let options = ResolveOptions {
+            default: Some(Resolution::Current),
+            hunks: vec!["2=both".parse().unwrap()],
+            ..Default::default()
+        };
+
+        select_resolutions(&mut file, &options).unwrap();
+        assert_eq!(file.resolutions[0], Some(Resolution::Current));
+        assert_eq!(file.resolutions[1], Some(Resolution::Both));
+    }
+
+    #[test]
+    fn test_select_fails_when_unresolved() {
+        let mut file = two_conflicts();
+        let options = ResolveOptions {
+            hunks: vec!["1=ours".parse().unwrap()],
+            ..Default::default()
+        };
+
+        let err = select_resolutions(&mut file, &options).unwrap_err();
+        assert!(err.to_string().contains("1 of 2 conflicts left unresolved"));
+    }
+
+    #[test]
+    fn test_select_fails_on_missing_hunk() {
+        let mut file = two_conflicts();
+        let options = ResolveOptions {
+            hunks: vec!["3=ours".parse().unwrap()],
+            ..Default::default()
+        };
+        assert!(select_resolutions(&mut file, &options).is_err());
+    }
+
+    #[test]
+    fn test_select_auto_then_default() {
+        let mut file = two_conflicts();
+        file.conflicts[1].incoming = "c".to_string();
+        let options = ResolveOptions {
+            auto: true,
+            ..Default::default()
+        };
+
+        let autos = select_resolutions(&mut file, &options).unwrap();
+        assert_eq!(file.resolutions, vec![None, Some(Resolution::Current)]);
+        assert_eq!(autos, vec![None, Some(AutoResolution::IdenticalSides)]);
+        assert_eq!(file.auto_resolved_count(), 0);
+        assert!(describe(&file, &autos, false).contains("auto, identical sides; 1 left"));
+
+        let options = ResolveOptions {
+            default: Some(Resolution::Incoming),
+            auto: true,
+            ..Default::default()
+        };
+        select_resolutions(&mut file, &options).unwrap();
+        assert!(file.is_fully_resolved());
+    }
+
+    #[test]
+    fn test_run_resolve_auto_writes_partial_result() {
+        let dir = test_support::conflicted_repo(
+            "merge",
+            "a\n1\nb\nc\nd\ne\n2\nf\n",
+            "a\nx\nb\nc\nd\ne\nours\nf\n",
+            "a\nx \nb\nc\nd\ne\ntheirs\nf\n",
+        );
+        let path = dir.path();
+
+        let repo = Repository::open(path).unwrap();
+        let file_path = repo.workdir().unwrap().join("file.txt");
+        let options = ResolveOptions {
+            auto: true,
+            stage: true,
+            ..Default::default()
+        };
+
+        let code = run_resolve(&repo, std::slice::from_ref(&file_path), &options).unwrap();
+        assert_eq!(code, 1);
+        let content = fs::read_to_string(&file_path).unwrap();
+        assert!(content.starts_with("a\nx\nb\n"));
+        assert!(content.contains("<<<<<<< HEAD\nours\n"));
+        // Left conflicted, so not staged
+        let index = Repository::open(path).unwrap().index().unwrap();
+        assert!(index.has_conflicts());
+    }
+
+    #[test]
+    fn test_run_resolve_writes_and_stages() {
+        let dir = conflicted_repo();
+        let repo = Repository::open(dir.path()).unwrap();
+        let path = repo.workdir().unwrap().join("file.txt");
+        let options = ResolveOptions {
+            default: Some(Resolution::Incoming),
+            hunks: vec!["1=ours".parse().unwrap()],
+            stage: true,
+            auto: false,
+        };
+
+        assert_eq!(
+            run_resolve(&repo, std::slice::from_ref(&path), &options).unwrap(),
+            0
+        );
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "a\nours1\nb\nc\nd\ne\ntheirs2\nf\n"
+        );
+        let index = Repository::open(dir.path()).unwrap().index().unwrap();
+        assert!(!index.has_conflicts());
+    }
+
+    #[test]
+    fn test_run_resolve_stages_whole_file_resolution_only_when_asked() {
+        let dir = test_support::whole_file_conflict_repo();
+        let repo = Repository::open(dir.path()).unwrap();
+        let path = repo.workdir().unwrap().join("del.txt");
+        let options = ResolveOptions {
+            default: Some(Resolution::Incoming),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            run_resolve(&repo, std::slice::from_ref(&path), &options).unwrap(),
+            0
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "x\ny\n");
+        let index = Repository::open(dir.path()).unwrap().index().unwrap();
+        assert!(index.get_path(Path::new("del.txt"), 3).is_some());
+
+        let options = ResolveOptions {
+            stage: true,
+            ..options
+        };
+        assert_eq!(
+            run_resolve(&repo, std::slice::from_ref(&path), &options).unwrap(),
+            0
+        );
+        let index = Repository::open(dir.path()).unwrap().index().unwrap();
+        assert!(index.get_path(Path::new("del.txt"), 3).is_none());
+        assert!(index.get_path(Path::new("del.txt"), 0).is_some());
+    }
+
+    #[test]
+    fn test_run_resolve_takes_deletion_from_deleting_side() {
+        let dir = test_support::whole_file_conflict_repo();
+        let repo = Repository::open(dir.path()).unwrap();
+        let path = repo.workdir().unwrap().join("del.txt");
+        let options = ResolveOptions {
+            default: Some(Resolution::Current),
+            stage: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            run_resolve(&repo, std::slice::from_ref(&path), &options).unwrap(),
+            0
+        );
+        assert!(!path.exists());
+        let index = Repository::open(dir.path()).unwrap().index().unwrap();
+        assert!((0..=3).all(|stage| index.get_path(Path::new("del.txt"), stage).is_none()));
+    }
+
+    #[test]
+    fn test_run_resolve_leaves_hand_edited_markers_alone() {
+        let dir = conflicted_repo();
+        let repo = Repository::open(dir.path()).unwrap();
+        let path = repo.workdir().unwrap().join("file.txt");
+        let edited = fs::read_to_string(&path)
+            .unwrap()
+            .replace("\nours1\n", "\nours1, merged by hand\n");
+        fs::write(&path, &edited).unwrap();
+        let options = ResolveOptions {
+            default: Some(Resolution::Incoming),
+            ..Default::default()
+        };
+
+        let code = run_resolve(&repo, std::slice::from_ref(&path), &options).unwrap();
+        assert_eq!(code, 1);
+        assert_eq!(fs::read_to_string(&path).unwrap(), edited);
+    }
+
+    #[test]
+    fn test_run_resolve_reports_failure() {
+        let dir = conflicted_repo();
+        let repo = Repository::open(dir.path()).unwrap();
+        let path = repo.workdir().unwrap().join("file.txt");
+        let original = fs::read_to_string(&path).unwrap();
+
+        let code = run_resolve(
+            &repo,
+            std::slice::from_ref(&path),
+            &ResolveOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(code, 1);
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+    }
+}
@@ -0,0 +1,298 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+use crate::app::CodeLayout;
+use crate::git::CommitOptions;
+use crate::tui::keymap::Keymap;
+
+/// Name of the repository-local config file, read from the worktree root
+pub const REPO_CONFIG_FILE: &str = ".saki.toml";
+
+/// Keys a repository config may set; the rest (commit flags, staging, theme
+/// paths) stay with the user, as a cloned repository is not trusted with them
+const REPO_KEYS: [&str; 3] = ["theme", "layout", "keys"];
+
+/// User settings, layered: defaults < user config < repo config < env < CLI flags
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Theme name or path (see `Theme::load`)
+    pub theme: Option<String>,
+    /// Layout the code pane opens with
+    pub layout: CodeLayout,
+    /// Write files as soon as all their conflicts are resolved
+    pub auto_save: bool,
+    /// Stage files when saki writes their resolution
+    pub auto_stage: bool,
+    /// Check for a newer release at startup
+    pub update_check: bool,
+    /// Options passed to `git commit` from the commit modal
+    pub commit: CommitOptions,
+    /// Key bindings, the defaults with the `[keys.*]` tables applied
+    pub keys: Keymap,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            theme: None,
+            layout: CodeLayout::Unified,
+            auto_save: true,
+            auto_stage: false,
+            update_check: true,
+            commit: CommitOptions::default(),
+            keys: Keymap::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Load the user config, then the repo config (in `workdir`) on top of it
+    ///
+    /// The repo config may only set the keys in `REPO_KEYS`.
+    pub fn load(workdir: Option<&Path>) -> Result<Self> {
+        let user = config_dir().map(|dir| dir.join("config.toml"));
+        Self::load_files(user, workdir)
+    }
+
+    /// Load the user config at `user`, then the repo config in `workdir`
+    fn load_files(user: Option<PathBuf>, workdir: Option<&Path>) -> Result<Self> {
+        let mut files = Vec::new();
+        let mut table = toml::Table::new();
+
+        if let Some(path) = user.filter(|path| path.is_file()) {
+            merge_tables(&mut table, read_table(&path)?);
+            files.push(path);
+        }
+
+        let repo = workdir.map(|dir| dir.join(REPO_CONFIG_FILE));
+        if let Some(path) = repo.filter(|path| path.is_file()) {
+            let layer = read_table(&path)?;
+            check_repo_table(&layer)
+                .with_context(|| format!("Invalid config {}", path.display()))?;
+            merge_tables(&mut table, layer);
+            files.push(path);
+        }
+
+        Self::from_table(table).with_context(|| {
+            let names: Vec<String> = files.iter().map(|p| p.display().to_string()).collect();
+            format!("Invalid config in {}", names.join(" or "))
+        })
+    }
+
+    /// Parse a single config file
+    pub fn from_toml(content: &str) -> Result<Self> {
+        Self::from_table(content.parse()?)
+    }
+
+    fn from_table(table: toml::Table) -> Result<Self> {
+        Ok(toml::Value::Table(table).try_into()?)
+    }
+
+    /// Apply the `SAKI_*` environment variables, looked up with `var`
+    pub fn apply_env(&mut self, var: impl Fn(&str) -> Option<String>) -> Result<()> {
+        if let Some(theme) = var("SAKI_THEME") {
+            self.theme = Some(theme);
+        }
+        if let Some(layout) = var("SAKI_LAYOUT") {
+            self.layout = layout.parse().map_err(anyhow::Error::msg)?;
+        }
+        if let Some(value) = var("SAKI_AUTO_SAVE") {
+            self.auto_save = parse_bool("SAKI_AUTO_SAVE", &value)?;
+        }
+        if let Some(value) = var("SAKI_AUTO_STAGE") {
+            self.auto_stage = parse_bool("SAKI_AUTO_STAGE", &value)?;
+        }
+        // Set to anything to disable, as before config files existed
+        if var("SAKI_NO_UPDATE_CHECK").is_some() {
+            self.update_check = false;
+        }
+        Ok(())
+    }
+}
+
+/// Directory holding `config.toml` and `themes/`: `$XDG_CONFIG_HOME/saki` or `~/.config/saki`
+pub fn config_dir() -> Option<PathBuf> {
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config.join("saki"))
+}
+
+fn read_table(path: &Path) -> Result<toml::Table> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config {}", path.display()))?;
+    content
+        .parse()
+        .with_context(|| format!("Invalid config {}", path.display()))
+}
+
+/// Refuse repo config keys outside `REPO_KEYS`, and themes given as paths
+fn check_repo_table(table: &toml::Table) -> Result<()> {
+    if let Some(key) = table.keys().find(|key| !REPO_KEYS.contains(&key.as_str())) {
+        anyhow::bail!(
+            "'{}' can only be set in the user config (a repository may set: {})",
+            key,
+            REPO_KEYS.join(", ")
+        );
+    }
+    if let Some(toml::Value::String(theme)) = table.get("theme") {
+        let is_name = theme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !is_name {
+            anyhow::bail!(
+                "theme '{}' must be a theme name, not a path, in {}",
+                theme,
+                REPO_CONFIG_FILE
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Overlay `layer` onto `base`, merging nested tables key by key
+fn merge_tables(base: &mut toml::Table, layer: toml::Table) {
+    for (key, value) in layer {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(layer)) => {
+                merge_tables(base, layer)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn parse_bool(name: &str, value: &str) -> Result<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => anyhow::bail!("{} must be true or false, got '{}'", name, value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::keymap::{Action, KeyMode};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_parse_config() {
+        let config = Config::from_toml(
+            r#"
+            theme = "light"
+            layout = "three-way"
+            auto_save = false
+            auto_stage = true
+
+            [commit]
+            sign_off = true
+
+            [keys.conflict]
+            undo = "z"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.theme.as_deref(), Some("light"));
+        assert_eq!(config.layout, CodeLayout::ThreeWay);
+        assert!(!config.auto_save);
+        assert!(config.auto_stage);
+        assert!(config.update_check);
+        assert!(config.commit.sign_off);
+        assert!(!config.commit.no_verify);
+        assert_eq!(
+            config
+                .keys
+                .label(KeyMode::Conflict, Action::Undo)
+                .as_deref(),
+            Some("z")
+        );
+
+        assert_eq!(Config::from_toml("").unwrap(), Config::default());
+        assert!(Config::from_toml("autosave = false").is_err());
+        assert!(Config::from_toml("layout = \"sideways\"").is_err());
+        assert!(Config::from_toml("[keys.conflict]\nstage = \"s\"").is_err());
+    }
+
+    #[test]
+    fn test_repo_config_overrides_user_config() {
+        let mut table: toml::Table = "auto_save = false\n[commit]\nsign_off = true"
+            .parse()
+            .unwrap();
+        merge_tables(
+            &mut table,
+            "theme = \"light\"\n[commit]\nno_verify = true"
+                .parse()
+                .unwrap(),
+        );
+        let config = Config::from_table(table).unwrap();
+
+        assert!(!config.auto_save);
+        assert_eq!(config.theme.as_deref(), Some("light"));
+        assert!(config.commit.sign_off);
+        assert!(config.commit.no_verify);
+    }
+
+    #[test]
+    fn test_repo_config_only_sets_safe_keys() {
+        let check = |content: &str| check_repo_table(&content.parse().unwrap());
+
+        assert!(check(
+            "theme = \"solarized\"\nlayout = \"three-way\"\n[keys.conflict]\nundo = \"z\""
+        )
+        .is_ok());
+        assert!(check("[commit]\nno_verify = true").is_err());
+        assert!(check("auto_stage = true").is_err());
+        assert!(check("theme = \"/tmp/evil.toml\"").is_err());
+        assert!(check("theme = \"../themes/dark\"").is_err());
+    }
+
+    #[test]
+    fn test_load_rejects_unsafe_repo_config() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let user = dir.path().join("config.toml");
+        let load = || Config::load_files(Some(user.clone()), Some(dir.path()));
+        std::fs::write(&user, "auto_stage = true").unwrap();
+        std::fs::write(dir.path().join(REPO_CONFIG_FILE), "layout = \"three-way\"").unwrap();
+        let config = load().unwrap();
+        assert_eq!(config.layout, CodeLayout::ThreeWay);
+        assert!(config.auto_stage);
+
+        std::fs::write(
+            dir.path().join(REPO_CONFIG_FILE),
+            "[commit]\nno_verify = true",
+        )
+        .unwrap();
+        let err = load().unwrap_err();
+        assert!(format!("{:#}", err).contains("'commit' can only be set in the user config"));
+    }
+
+    #[test]
+    fn test_env_overrides_config() {
+        let env: HashMap<&str, &str> = [
+            ("SAKI_THEME", "high-contrast"),
+            ("SAKI_AUTO_SAVE", "0"),
+            ("SAKI_NO_UPDATE_CHECK", ""),
+        ]
+        .into_iter()
+        .collect();
+        let mut config = Config::from_toml("theme = \"light\"\nauto_stage = true").unwrap();
+        config
+            .apply_env(|name| env.get(name).map(|v| v.to_string()))
+            .unwrap();
+
+        assert_eq!(config.theme.as_deref(), Some("high-contrast"));
+        assert!(!config.auto_save);
+        assert!(config.auto_stage);
+        assert!(!config.update_check);
+
+        assert!(config
+            .apply_env(|name| (name == "SAKI_AUTO_STAGE").then(|| "maybe".to_string()))
+            .is_err());
+    }
+}
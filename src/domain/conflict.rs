@@ -1,6 +1,12 @@
 use std::path::PathBuf;
+use std::sync::OnceLock;
 
-use super::Resolution;
+use super::{
+    hunk_changes, AutoResolution, FileConflictKind, FileResolution, HunkChanges, Resolution, Side,
+};
+
+/// Marker length git uses unless `conflict-marker-size` says otherwise
+pub const DEFAULT_MARKER_SIZE: usize = 7;
 
 /// Represents a single conflict hunk within a file
 #[derive(Debug, Clone)]
@@ -9,6 +15,14 @@ pub struct ConflictHunk {
     pub current: String,
     /// Content from the incoming branch
     pub incoming: String,
+    /// Content from the common ancestor (only with diff3/zdiff3 conflict style)
+    pub base: Option<String>,
+    /// Text after the `<<<<<<<` marker (usually `HEAD`)
+    pub current_label: Option<String>,
+    /// Text after the `|||||||` marker
+    pub base_label: Option<String>,
+    /// Text after the `>>>>>>>` marker (branch name, or commit and subject when rebasing)
+    pub incoming_label: Option<String>,
     /// Starting line number of the conflict in the original file
     pub start_line: usize,
     /// Ending line number of the conflict in the original file
@@ -21,21 +35,166 @@ impl ConflictHunk {
         Self {
             current,
             incoming,
+            base: None,
+            current_label: None,
+            base_label: None,
+            incoming_label: None,
             start_line,
             end_line,
         }
     }
 
+    /// Attach the common ancestor content to this hunk
+    pub fn with_base(mut self, base: String) -> Self {
+        self.base = Some(base);
+        self
+    }
+
+    /// Attach the labels found after the conflict markers
+    pub fn with_labels(
+        mut self,
+        current: Option<String>,
+        base: Option<String>,
+        incoming: Option<String>,
+    ) -> Self {
+        self.current_label = current;
+        self.base_label = base;
+        self.incoming_label = incoming;
+        self
+    }
+
+    /// Title for the current side: its marker label, or a generic name
+    pub fn current_title(&self) -> &str {
+        self.current_label.as_deref().unwrap_or("Current (HEAD)")
+    }
+
+    /// Title for the common ancestor: its marker label, or a generic name
+    pub fn base_title(&self) -> &str {
+        self.base_label.as_deref().unwrap_or("Base")
+    }
+
+    /// Title for the incoming side: its marker label, or a generic name
+    pub fn incoming_title(&self) -> &str {
+        self.incoming_label.as_deref().unwrap_or("Incoming")
+    }
+
+    /// Resolution that is safe to pick without asking, and why
+    ///
+    /// Identical sides come first, then (with a base) a side that left the base
+    /// untouched, then sides that only differ in trailing whitespace or line
+    /// endings. Indentation changes are never resolved automatically.
+    pub fn auto_resolution(&self) -> Option<(Resolution, AutoResolution)> {
+        if self.current == self.incoming {
+            return Some((Resolution::Current, AutoResolution::IdenticalSides));
+        }
+        if let Some(base) = &self.base {
+            if *base == self.current {
+                return Some((Resolution::Incoming, AutoResolution::OnlyIncomingChanged));
+            }
+            if *base == self.incoming {
+                return Some((Resolution::Current, AutoResolution::OnlyCurrentChanged));
+            }
+        }
+        if self
+            .current
+            .lines()
+            .map(str::trim_end)
+            .eq(self.incoming.lines().map(str::trim_end))
+        {
+            return Some((Resolution::Current, AutoResolution::WhitespaceOnly));
+        }
+        None
+    }
+
     /// Get the resolved content based on the resolution strategy
     pub fn resolve(&self, resolution: Resolution) -> String {
         match resolution {
             Resolution::Current => self.current.clone(),
             Resolution::Incoming => self.incoming.clone(),
-            Resolution::Both => {
-                format!("{}\n{}", self.current.trim(), self.incoming.trim())
+            // Sides are kept verbatim: indentation and trailing whitespace are content
+            Resolution::Both => match (self.current.is_empty(), self.incoming.is_empty()) {
+                (true, _) => self.incoming.clone(),
+                (false, true) => self.current.clone(),
+                (false, false) => format!("{}\n{}", self.current, self.incoming),
+            },
+            Resolution::Custom(text) => text,
+            // Every picked line takes the hunk's ending, whichever side it came from
+            Resolution::Lines(selection) => {
+                let current = raw_lines(&self.current);
+                let incoming = raw_lines(&self.incoming);
+                let cr = if self.line_ending() == "\r\n" {
+                    "\r"
+                } else {
+                    ""
+                };
+                selection
+                    .lines
+                    .iter()
+                    .filter_map(|&(side, index)| match side {
+                        Side::Current => current.get(index),
+                        Side::Incoming => incoming.get(index),
+                    })
+                    .map(|line| format!("{}{}", line.strip_suffix('\r').unwrap_or(line), cr))
+                    .collect::<Vec<_>>()
+                    .join("\n")
             }
         }
     }
+
+    /// Line ending most lines of both sides use (`"\r\n"` or `"\n"`)
+    pub fn line_ending(&self) -> &'static str {
+        let mut lines = raw_lines(&self.current);
+        lines.extend(raw_lines(&self.incoming));
+        dominant_line_ending(&lines)
+    }
+}
+
+/// `"\r\n"` if most of the lines end with `\r`, else `"\n"`
+fn dominant_line_ending(lines: &[&str]) -> &'static str {
+    let crlf = lines.iter().filter(|line| line.ends_with('\r')).count();
+    if crlf * 2 > lines.len() {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+/// Split content into lines like `str::lines`, but keep the `\r` of CRLF endings
+///
+/// Hunks and merged lines are built from these so files are written back with
+/// the line endings they had.
+pub fn raw_lines(content: &str) -> Vec<&str> {
+    content
+        .split_inclusive('\n')
+        .map(|line| line.strip_suffix('\n').unwrap_or(line))
+        .collect()
+}
+
+/// Where a line of the merged result comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineOrigin {
+    /// Outside of any conflict
+    Context,
+    /// Content of a resolved conflict
+    Resolved,
+    /// An unresolved conflict, markers included
+    Unresolved,
+}
+
+/// A line of the file as it would be written
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergedLine {
+    pub text: String,
+    pub origin: LineOrigin,
+}
+
+impl MergedLine {
+    fn new(text: &str, origin: LineOrigin) -> Self {
+        Self {
+            text: text.to_string(),
+            origin,
+        }
+    }
 }
 
 /// Represents a file with conflicts
@@ -47,41 +206,135 @@ pub struct ConflictedFile {
     pub conflicts: Vec<ConflictHunk>,
     /// Resolution choices for each conflict (None if not yet resolved)
     pub resolutions: Vec<Option<Resolution>>,
+    /// Why each conflict was resolved automatically (None once picked or confirmed by hand)
+    pub auto_resolved: Vec<Option<AutoResolution>>,
     /// Original file content (before parsing conflicts)
     pub original_content: String,
+    /// Whole-file conflict (delete/add/rename), when the file has no hunks to resolve
+    pub file_conflict: Option<FileConflictKind>,
+    /// Resolution chosen for a whole-file conflict
+    pub file_resolution: Option<FileResolution>,
+    /// Encoding the file is stored in on disk (`None` for UTF-8)
+    pub encoding: Option<String>,
+    /// Length of the file's conflict markers (`conflict-marker-size`)
+    pub marker_size: usize,
+    /// Bytes on disk when the conflicts had to be rebuilt from the index, as the
+    /// markers were edited by hand; `original_content` is then libgit2's merge
+    pub worktree_content: Option<Vec<u8>>,
+    /// Changed spans of each conflict's sides, diffed on first use
+    changes: Vec<OnceLock<HunkChanges>>,
 }
 
 impl ConflictedFile {
     /// Create a new conflicted file
     pub fn new(path: PathBuf, conflicts: Vec<ConflictHunk>, original_content: String) -> Self {
         let resolutions = vec![None; conflicts.len()];
+        let auto_resolved = vec![None; conflicts.len()];
+        let changes = conflicts.iter().map(|_| OnceLock::new()).collect();
         Self {
             path,
             conflicts,
             resolutions,
+            auto_resolved,
             original_content,
+            file_conflict: None,
+            file_resolution: None,
+            encoding: None,
+            marker_size: DEFAULT_MARKER_SIZE,
+            worktree_content: None,
+            changes,
+        }
+    }
+
+    /// Record the encoding the file has to be written back in
+    pub fn with_encoding(mut self, encoding: Option<&str>) -> Self {
+        self.encoding = encoding.map(str::to_string);
+        self
+    }
+
+    /// Record the length of the markers the file's conflicts were found with
+    pub fn with_marker_size(mut self, marker_size: usize) -> Self {
+        self.marker_size = marker_size;
+        self
+    }
+
+    /// Record the file's bytes on disk, for conflicts rebuilt from the index
+    pub fn with_worktree_content(mut self, content: Option<Vec<u8>>) -> Self {
+        self.worktree_content = content;
+        self
+    }
+
+    /// Check if the conflicts were rebuilt from the index, so writing the file
+    /// replaces the hand edits on disk
+    pub fn is_rebuilt_from_index(&self) -> bool {
+        self.worktree_content.is_some()
+    }
+
+    /// Create a file with a whole-file conflict (deleted, added or renamed on both sides)
+    pub fn new_file_conflict(path: PathBuf, kind: FileConflictKind) -> Self {
+        Self {
+            file_conflict: Some(kind),
+            ..Self::new(path, Vec::new(), String::new())
         }
     }
 
+    /// Changed spans of a conflict's sides, computed once and kept for the next frames
+    pub fn hunk_changes(&self, conflict_index: usize) -> Option<&HunkChanges> {
+        let conflict = self.conflicts.get(conflict_index)?;
+        let changes = self.changes.get(conflict_index)?;
+        Some(changes.get_or_init(|| hunk_changes(conflict)))
+    }
+
+    /// Check if this is a whole-file conflict rather than a content conflict
+    pub fn is_file_conflict(&self) -> bool {
+        self.file_conflict.is_some()
+    }
+
     /// Check if all conflicts have been resolved
+    ///
+    /// Automatic resolutions only count once they are confirmed.
     pub fn is_fully_resolved(&self) -> bool {
-        self.resolutions.iter().all(|r| r.is_some())
+        if self.is_file_conflict() {
+            return self.file_resolution.is_some();
+        }
+        self.resolutions.iter().all(|r| r.is_some()) && self.auto_resolved_count() == 0
     }
 
     /// Get the number of resolved conflicts
     pub fn resolved_count(&self) -> usize {
+        if self.is_file_conflict() {
+            return usize::from(self.file_resolution.is_some());
+        }
         self.resolutions.iter().filter(|r| r.is_some()).count()
     }
 
     /// Get the total number of conflicts
     pub fn total_conflicts(&self) -> usize {
+        if self.is_file_conflict() {
+            return 1;
+        }
         self.conflicts.len()
     }
 
+    /// Set the resolution for a whole-file conflict (ignored if it doesn't apply)
+    pub fn set_file_resolution(&mut self, resolution: FileResolution) {
+        if let Some(kind) = &self.file_conflict {
+            if kind.allows(resolution) {
+                self.file_resolution = Some(resolution);
+            }
+        }
+    }
+
+    /// Clear the resolution for a whole-file conflict
+    pub fn clear_file_resolution(&mut self) {
+        self.file_resolution = None;
+    }
+
     /// Set resolution for a specific conflict
     pub fn set_resolution(&mut self, conflict_index: usize, resolution: Resolution) {
         if conflict_index < self.resolutions.len() {
             self.resolutions[conflict_index] = Some(resolution);
+            self.confirm_auto_resolution(conflict_index);
         }
     }
 
@@ -89,9 +342,102 @@ impl ConflictedFile {
     pub fn clear_resolution(&mut self, conflict_index: usize) {
         if conflict_index < self.resolutions.len() {
             self.resolutions[conflict_index] = None;
+            self.confirm_auto_resolution(conflict_index);
+        }
+    }
+
+    /// Resolve every unresolved conflict that has a safe automatic resolution
+    ///
+    /// Returns how many conflicts were resolved. They stay marked as automatic
+    /// until the user picks a resolution for them or confirms them.
+    pub fn auto_resolve(&mut self) -> usize {
+        let mut count = 0;
+        for (index, conflict) in self.conflicts.iter().enumerate() {
+            if self.resolutions[index].is_some() {
+                continue;
+            }
+            if let Some((resolution, reason)) = conflict.auto_resolution() {
+                self.resolutions[index] = Some(resolution);
+                self.auto_resolved[index] = Some(reason);
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Why a conflict was resolved automatically, if it was and is not confirmed yet
+    pub fn auto_resolution(&self, conflict_index: usize) -> Option<AutoResolution> {
+        self.auto_resolved.get(conflict_index).copied().flatten()
+    }
+
+    /// Get the number of automatic resolutions still waiting for review
+    pub fn auto_resolved_count(&self) -> usize {
+        self.auto_resolved.iter().flatten().count()
+    }
+
+    /// Drop the automatic marker of a conflict, keeping its resolution
+    pub fn confirm_auto_resolution(&mut self, conflict_index: usize) {
+        if let Some(slot) = self.auto_resolved.get_mut(conflict_index) {
+            *slot = None;
         }
     }
 
+    /// Drop the automatic markers of every conflict, keeping their resolutions
+    pub fn confirm_auto_resolutions(&mut self) {
+        self.auto_resolved.fill(None);
+    }
+
+    /// Line ending most lines of the file use (`"\r\n"` or `"\n"`)
+    pub fn line_ending(&self) -> &'static str {
+        dominant_line_ending(&raw_lines(&self.original_content))
+    }
+
+    /// Build the file as it would be written with the current resolutions
+    ///
+    /// Unresolved conflicts keep their original lines, markers included. Lines
+    /// keep their own `\r`; hand-written text takes the file's dominant ending.
+    pub fn merged_lines(&self) -> Vec<MergedLine> {
+        let lines = raw_lines(&self.original_content);
+        let crlf = self.line_ending() == "\r\n";
+        let mut merged = Vec::new();
+        let mut current_line = 0;
+
+        for (conflict, resolution) in self.conflicts.iter().zip(&self.resolutions) {
+            let start = conflict.start_line.min(lines.len());
+            let end = (conflict.end_line + 1).min(lines.len());
+
+            for line in &lines[current_line.min(start)..start] {
+                merged.push(MergedLine::new(line, LineOrigin::Context));
+            }
+
+            match resolution {
+                Some(resolution) => {
+                    let custom = matches!(resolution, Resolution::Custom(_));
+                    for line in raw_lines(&conflict.resolve(resolution.clone())) {
+                        let mut line = MergedLine::new(line, LineOrigin::Resolved);
+                        if custom && crlf && !line.text.ends_with('\r') {
+                            line.text.push('\r');
+                        }
+                        merged.push(line);
+                    }
+                }
+                None => {
+                    for line in &lines[start..end] {
+                        merged.push(MergedLine::new(line, LineOrigin::Unresolved));
+                    }
+                }
+            }
+
+            current_line = end;
+        }
+
+        for line in &lines[current_line.min(lines.len())..] {
+            merged.push(MergedLine::new(line, LineOrigin::Context));
+        }
+
+        merged
+    }
+
     /// Get the file name as a string
     pub fn file_name(&self) -> String {
         self.path
@@ -110,6 +456,7 @@ impl ConflictedFile {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::LineSelection;
 
     #[test]
     fn test_conflict_hunk_creation() {
@@ -125,6 +472,30 @@ mod tests {
         assert_eq!(hunk.end_line, 20);
     }
 
+    #[test]
+    fn test_conflict_hunk_with_base() {
+        let hunk = ConflictHunk::new("current".to_string(), "incoming".to_string(), 0, 7)
+            .with_base("base".to_string());
+        assert_eq!(hunk.base.as_deref(), Some("base"));
+        assert_eq!(hunk.resolve(Resolution::Current), "current");
+    }
+
+    #[test]
+    fn test_conflict_hunk_titles() {
+        let hunk = ConflictHunk::new("current".to_string(), "incoming".to_string(), 0, 4);
+        assert_eq!(hunk.current_title(), "Current (HEAD)");
+        assert_eq!(hunk.incoming_title(), "Incoming");
+
+        let hunk = hunk.with_labels(
+            Some("HEAD".to_string()),
+            None,
+            Some("feature/x".to_string()),
+        );
+        assert_eq!(hunk.current_title(), "HEAD");
+        assert_eq!(hunk.base_title(), "Base");
+        assert_eq!(hunk.incoming_title(), "feature/x");
+    }
+
     #[test]
     fn test_conflict_hunk_resolve_current() {
         let hunk = ConflictHunk::new("current".to_string(), "incoming".to_string(), 0, 5);
@@ -144,9 +515,95 @@ mod tests {
     }
 
     #[test]
-    fn test_conflict_hunk_resolve_both_with_whitespace() {
-        let hunk = ConflictHunk::new("  current  ".to_string(), "  incoming  ".to_string(), 0, 5);
-        assert_eq!(hunk.resolve(Resolution::Both), "current\nincoming");
+    fn test_conflict_hunk_resolve_custom() {
+        let hunk = ConflictHunk::new("current".to_string(), "incoming".to_string(), 0, 5);
+        let custom = Resolution::Custom("hand\nmerged".to_string());
+        assert_eq!(hunk.resolve(custom), "hand\nmerged");
+    }
+
+    #[test]
+    fn test_conflict_hunk_resolve_lines() {
+        let hunk = ConflictHunk::new("a1\r\na2\r\na3\r".to_string(), "b1\r\nb2".to_string(), 0, 7);
+        let selection = LineSelection {
+            lines: vec![(Side::Current, 0), (Side::Incoming, 1), (Side::Current, 2)],
+        };
+
+        // Picks interleave in order, and all take the hunk's CRLF ending
+        assert_eq!(
+            hunk.resolve(Resolution::Lines(selection)),
+            "a1\r\nb2\r\na3\r"
+        );
+        assert_eq!(
+            hunk.resolve(Resolution::Lines(LineSelection::default())),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_merged_lines_mixes_resolved_and_unresolved() {
+        let content = "a\n<<<<<<< HEAD\nx\n=======\ny\n>>>>>>> b\nm\n<<<<<<< HEAD\np\n=======\nq\n>>>>>>> b\nz\n";
+        let conflicts = vec![
+            ConflictHunk::new("x".to_string(), "y".to_string(), 1, 5),
+            ConflictHunk::new("p".to_string(), "q".to_string(), 7, 11),
+        ];
+        let mut file = ConflictedFile::new(PathBuf::from("f.txt"), conflicts, content.to_string());
+        file.set_resolution(0, Resolution::Incoming);
+
+        let merged = file.merged_lines();
+        let texts: Vec<&str> = merged.iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "a",
+                "y",
+                "m",
+                "<<<<<<< HEAD",
+                "p",
+                "=======",
+                "q",
+                ">>>>>>> b",
+                "z"
+            ]
+        );
+        assert_eq!(merged[0].origin, LineOrigin::Context);
+        assert_eq!(merged[1].origin, LineOrigin::Resolved);
+        assert_eq!(merged[3].origin, LineOrigin::Unresolved);
+        assert_eq!(merged[8].origin, LineOrigin::Context);
+    }
+
+    #[test]
+    fn test_conflict_hunk_resolve_both_keeps_whitespace() {
+        let hunk = ConflictHunk::new("  current  ".to_string(), "\tincoming".to_string(), 0, 5);
+        assert_eq!(hunk.resolve(Resolution::Both), "  current  \n\tincoming");
+    }
+
+    #[test]
+    fn test_conflict_hunk_resolve_both_with_empty_side() {
+        let hunk = ConflictHunk::new(String::new(), "incoming".to_string(), 0, 4);
+        assert_eq!(hunk.resolve(Resolution::Both), "incoming");
+    }
+
+    #[test]
+    fn test_raw_lines_keeps_carriage_returns() {
+        assert_eq!(raw_lines("a\r\nb\nc"), vec!["a\r", "b", "c"]);
+        assert_eq!(raw_lines("a\n"), vec!["a"]);
+        assert!(raw_lines("").is_empty());
+    }
+
+    #[test]
+    fn test_merged_lines_preserve_crlf() {
+        let content = "a\r\n<<<<<<< HEAD\r\nours\r\n=======\r\ntheirs\r\n>>>>>>> x\r\nb\n";
+        let hunk = ConflictHunk::new("ours\r".to_string(), "theirs\r".to_string(), 1, 5);
+        let mut file = ConflictedFile::new(PathBuf::from("f"), vec![hunk], content.to_string());
+        assert_eq!(file.line_ending(), "\r\n");
+
+        file.set_resolution(0, Resolution::Both);
+        let texts: Vec<String> = file.merged_lines().into_iter().map(|l| l.text).collect();
+        assert_eq!(texts, vec!["a\r", "ours\r", "theirs\r", "b"]);
+
+        file.set_resolution(0, Resolution::Custom("x\ny".to_string()));
+        let texts: Vec<String> = file.merged_lines().into_iter().map(|l| l.text).collect();
+        assert_eq!(texts, vec!["a\r", "x\r", "y\r", "b"]);
     }
 
     #[test]
@@ -231,6 +688,118 @@ mod tests {
         file.clear_resolution(10);
     }
 
+    #[test]
+    fn test_hunk_auto_resolution() {
+        let hunk = |current: &str, base: Option<&str>, incoming: &str| {
+            let hunk = ConflictHunk::new(current.to_string(), incoming.to_string(), 0, 4);
+            match base {
+                Some(base) => hunk.with_base(base.to_string()),
+                None => hunk,
+            }
+        };
+
+        assert_eq!(
+            hunk("same", None, "same").auto_resolution(),
+            Some((Resolution::Current, AutoResolution::IdenticalSides))
+        );
+        assert_eq!(
+            hunk("old", Some("old"), "new").auto_resolution(),
+            Some((Resolution::Incoming, AutoResolution::OnlyIncomingChanged))
+        );
+        assert_eq!(
+            hunk("new", Some("old"), "old").auto_resolution(),
+            Some((Resolution::Current, AutoResolution::OnlyCurrentChanged))
+        );
+        assert_eq!(
+            hunk("if x {\n    y();  \n}", None, "if x {\r\n    y();\r\n}").auto_resolution(),
+            Some((Resolution::Current, AutoResolution::WhitespaceOnly))
+        );
+        // Indentation is meaningful in some languages, so it needs a person
+        assert_eq!(
+            hunk("if x {\n    y();\n}", None, "if x {\n\ty();\n}").auto_resolution(),
+            None
+        );
+        assert_eq!(hunk("a b", None, "ab").auto_resolution(), None);
+        assert_eq!(hunk("a", Some("b"), "c").auto_resolution(), None);
+    }
+
+    #[test]
+    fn test_hunk_changes_are_computed_once() {
+        let file = ConflictedFile::new(
+            PathBuf::from("f.txt"),
+            vec![ConflictHunk::new(
+                "let x = 1;".to_string(),
+                "let x = 2;".to_string(),
+                0,
+                4,
+            )],
+            String::new(),
+        );
+
+        let changes = file.hunk_changes(0).unwrap();
+        assert_eq!(changes.current, vec![vec![8..9]]);
+        assert!(std::ptr::eq(changes, file.hunk_changes(0).unwrap()));
+        assert!(file.hunk_changes(1).is_none());
+    }
+
+    #[test]
+    fn test_auto_resolve_marks_until_confirmed() {
+        let conflicts = vec![
+            ConflictHunk::new("a".to_string(), "a".to_string(), 0, 4),
+            ConflictHunk::new("b".to_string(), "c".to_string(), 5, 9),
+            ConflictHunk::new("d ".to_string(), "d".to_string(), 10, 14),
+        ];
+        let mut file = ConflictedFile::new(PathBuf::from("f.txt"), conflicts, String::new());
+        file.set_resolution(2, Resolution::Incoming);
+
+        assert_eq!(file.auto_resolve(), 1);
+        assert_eq!(file.resolutions[0], Some(Resolution::Current));
+        assert_eq!(
+            file.auto_resolution(0),
+            Some(AutoResolution::IdenticalSides)
+        );
+        assert_eq!(file.resolutions[1], None);
+        // Choices made by hand are left alone
+        assert_eq!(file.resolutions[2], Some(Resolution::Incoming));
+        assert_eq!(file.auto_resolution(2), None);
+
+        file.set_resolution(0, Resolution::Incoming);
+        assert_eq!(file.auto_resolution(0), None);
+
+        file.clear_resolution(0);
+        assert_eq!(file.auto_resolve(), 1);
+        file.set_resolution(1, Resolution::Current);
+        // Everything has a resolution, but the automatic one is not reviewed yet
+        assert!(!file.is_fully_resolved());
+        file.confirm_auto_resolutions();
+        assert!(file.is_fully_resolved());
+        assert_eq!(file.auto_resolved_count(), 0);
+        assert_eq!(file.resolutions[0], Some(Resolution::Current));
+    }
+
+    #[test]
+    fn test_file_conflict_resolution() {
+        let mut file = ConflictedFile::new_file_conflict(
+            PathBuf::from("a.txt"),
+            FileConflictKind::DeletedByUs,
+        );
+
+        assert!(file.is_file_conflict());
+        assert_eq!(file.total_conflicts(), 1);
+        assert!(!file.is_fully_resolved());
+
+        // KeepOurs makes no sense when we deleted the file
+        file.set_file_resolution(FileResolution::KeepOurs);
+        assert!(!file.is_fully_resolved());
+
+        file.set_file_resolution(FileResolution::Delete);
+        assert!(file.is_fully_resolved());
+        assert_eq!(file.resolved_count(), 1);
+
+        file.clear_file_resolution();
+        assert_eq!(file.resolved_count(), 0);
+    }
+
     #[test]
     fn test_conflicted_file_name() {
         let path = PathBuf::from("/path/to/test.txt");
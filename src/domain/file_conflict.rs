@@ -0,0 +1,175 @@
+use std::path::PathBuf;
+
+/// Metadata about one side of a binary conflict
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobInfo {
+    /// Blob object id (hex)
+    pub id: String,
+    /// Size in bytes
+    pub size: usize,
+    /// Most recent commit that introduced this version ("<short id> <summary>")
+    pub last_commit: Option<String>,
+}
+
+/// Represents a conflict on the whole file rather than on hunks inside it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileConflictKind {
+    /// We deleted the file, they modified it
+    DeletedByUs,
+    /// They deleted the file, we modified it
+    DeletedByThem,
+    /// Both sides added a file at the same path
+    BothAdded,
+    /// Both sides renamed the file, to different paths
+    RenamedDifferently {
+        /// Path the file was renamed to on our side (HEAD)
+        ours: PathBuf,
+        /// Path the file was renamed to on their side (incoming)
+        theirs: PathBuf,
+    },
+    /// Both sides changed a binary file, which can't be merged hunk by hunk
+    Binary {
+        /// Our version (HEAD)
+        ours: BlobInfo,
+        /// Their version (incoming)
+        theirs: BlobInfo,
+    },
+}
+
+/// Represents the resolution chosen for a whole-file conflict
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileResolution {
+    /// Keep our version of the file (or our rename)
+    KeepOurs,
+    /// Keep their version of the file (or their rename)
+    KeepTheirs,
+    /// Delete the file
+    Delete,
+}
+
+impl FileConflictKind {
+    /// Get a short description of the conflict
+    pub fn description(&self) -> String {
+        match self {
+            FileConflictKind::DeletedByUs => {
+                "Deleted in current (HEAD), modified in incoming".to_string()
+            }
+            FileConflictKind::DeletedByThem => {
+                "Modified in current (HEAD), deleted in incoming".to_string()
+            }
+            FileConflictKind::BothAdded => "Added on both sides".to_string(),
+            FileConflictKind::Binary { .. } => "Binary file changed on both sides".to_string(),
+            FileConflictKind::RenamedDifferently { ours, theirs } => format!(
+                "Renamed to {} in current (HEAD) and to {} in incoming",
+                ours.display(),
+                theirs.display()
+            ),
+        }
+    }
+
+    /// Get the resolutions that make sense for this kind of conflict
+    pub fn available_resolutions(&self) -> &'static [FileResolution] {
+        match self {
+            FileConflictKind::DeletedByUs => &[FileResolution::KeepTheirs, FileResolution::Delete],
+            FileConflictKind::DeletedByThem => &[FileResolution::KeepOurs, FileResolution::Delete],
+            FileConflictKind::BothAdded
+            | FileConflictKind::RenamedDifferently { .. }
+            | FileConflictKind::Binary { .. } => {
+                &[FileResolution::KeepOurs, FileResolution::KeepTheirs]
+            }
+        }
+    }
+
+    /// Check if a resolution applies to this kind of conflict
+    pub fn allows(&self, resolution: FileResolution) -> bool {
+        self.available_resolutions().contains(&resolution)
+    }
+
+    /// Get a display string for a resolution in the context of this conflict
+    pub fn resolution_label(&self, resolution: FileResolution) -> String {
+        match (self, resolution) {
+            (_, FileResolution::Delete) => "Delete file".to_string(),
+            (FileConflictKind::DeletedByUs, FileResolution::KeepTheirs)
+            | (FileConflictKind::DeletedByThem, FileResolution::KeepOurs) => {
+                "Keep file".to_string()
+            }
+            (FileConflictKind::RenamedDifferently { ours, .. }, FileResolution::KeepOurs) => {
+                format!("Take rename from current ({})", ours.display())
+            }
+            (FileConflictKind::RenamedDifferently { theirs, .. }, FileResolution::KeepTheirs) => {
+                format!("Take rename from incoming ({})", theirs.display())
+            }
+            (_, FileResolution::KeepOurs) => "Keep current (HEAD) version".to_string(),
+            (_, FileResolution::KeepTheirs) => "Keep incoming version".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deleted_by_us_resolutions() {
+        let kind = FileConflictKind::DeletedByUs;
+        assert!(kind.allows(FileResolution::KeepTheirs));
+        assert!(kind.allows(FileResolution::Delete));
+        assert!(!kind.allows(FileResolution::KeepOurs));
+        assert_eq!(
+            kind.resolution_label(FileResolution::KeepTheirs),
+            "Keep file"
+        );
+    }
+
+    #[test]
+    fn test_deleted_by_them_resolutions() {
+        let kind = FileConflictKind::DeletedByThem;
+        assert!(kind.allows(FileResolution::KeepOurs));
+        assert!(kind.allows(FileResolution::Delete));
+        assert!(!kind.allows(FileResolution::KeepTheirs));
+    }
+
+    #[test]
+    fn test_both_added_cannot_delete() {
+        let kind = FileConflictKind::BothAdded;
+        assert!(kind.allows(FileResolution::KeepOurs));
+        assert!(kind.allows(FileResolution::KeepTheirs));
+        assert!(!kind.allows(FileResolution::Delete));
+    }
+
+    #[test]
+    fn test_renamed_differently_labels() {
+        let kind = FileConflictKind::RenamedDifferently {
+            ours: PathBuf::from("ours.txt"),
+            theirs: PathBuf::from("theirs.txt"),
+        };
+        assert_eq!(
+            kind.resolution_label(FileResolution::KeepOurs),
+            "Take rename from current (ours.txt)"
+        );
+        assert_eq!(
+            kind.resolution_label(FileResolution::KeepTheirs),
+            "Take rename from incoming (theirs.txt)"
+        );
+        assert!(kind.description().contains("ours.txt"));
+    }
+
+    #[test]
+    fn test_binary_takes_a_side() {
+        let blob = |id: &str| BlobInfo {
+            id: id.to_string(),
+            size: 42,
+            last_commit: None,
+        };
+        let kind = FileConflictKind::Binary {
+            ours: blob("aaaa"),
+            theirs: blob("bbbb"),
+        };
+        assert_eq!(kind.available_resolutions().len(), 2);
+        assert!(!kind.allows(FileResolution::Delete));
+        assert_eq!(
+            kind.resolution_label(FileResolution::KeepTheirs),
+            "Keep incoming version"
+        );
+    }
+}
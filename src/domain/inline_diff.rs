@@ -0,0 +1,309 @@
+use std::ops::Range;
+
+use super::ConflictHunk;
+
+/// Byte ranges of a line that differ from its counterpart
+pub type LineChanges = Vec<Range<usize>>;
+
+/// Largest LCS table built before giving up and treating everything as changed
+const MAX_TABLE_SIZE: usize = 1_000_000;
+
+/// Changed spans of every line on each side of a conflict
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HunkChanges {
+    pub current: Vec<LineChanges>,
+    pub base: Vec<LineChanges>,
+    pub incoming: Vec<LineChanges>,
+}
+
+/// Changed spans of a conflict's sides, line by line (as split by `str::lines`)
+///
+/// With a base (diff3), each side is compared to the base so only what that side
+/// changed stands out; the base shows what either side changed. Without one, the
+/// sides are compared to each other.
+pub fn hunk_changes(hunk: &ConflictHunk) -> HunkChanges {
+    let current: Vec<&str> = hunk.current.lines().collect();
+    let incoming: Vec<&str> = hunk.incoming.lines().collect();
+
+    match &hunk.base {
+        Some(base) => {
+            let base: Vec<&str> = base.lines().collect();
+            let (base_vs_current, current) = diff_lines(&base, &current);
+            let (base_vs_incoming, incoming) = diff_lines(&base, &incoming);
+            let base = base_vs_current
+                .into_iter()
+                .zip(base_vs_incoming)
+                .map(|(mut ours, theirs)| {
+                    ours.extend(theirs);
+                    ours
+                })
+                .collect();
+            HunkChanges {
+                current,
+                base,
+                incoming,
+            }
+        }
+        None => {
+            let (current, incoming) = diff_lines(&current, &incoming);
+            HunkChanges {
+                current,
+                base: Vec::new(),
+                incoming,
+            }
+        }
+    }
+}
+
+/// Changed spans of each line of two versions of a block of text
+///
+/// Identical lines are matched first; the lines between matches are paired up
+/// in order and diffed word by word, and lines left without a partner are
+/// changed entirely.
+pub fn diff_lines(old: &[&str], new: &[&str]) -> (Vec<LineChanges>, Vec<LineChanges>) {
+    let mut old_changes = vec![Vec::new(); old.len()];
+    let mut new_changes = vec![Vec::new(); new.len()];
+
+    let mut diff_gap = |old_lines: Range<usize>, new_lines: Range<usize>| {
+        let paired = old_lines.len().min(new_lines.len());
+        for (i, j) in old_lines.clone().zip(new_lines.clone()) {
+            let (old_spans, new_spans) = diff_line(old[i], new[j]);
+            old_changes[i] = old_spans;
+            new_changes[j] = new_spans;
+        }
+        for i in old_lines.skip(paired) {
+            old_changes[i] = whole_line(old[i]);
+        }
+        for j in new_lines.skip(paired) {
+            new_changes[j] = whole_line(new[j]);
+        }
+    };
+
+    let (mut i, mut j) = (0, 0);
+    for (match_i, match_j) in common_subsequence(old, new) {
+        diff_gap(i..match_i, j..match_j);
+        i = match_i + 1;
+        j = match_j + 1;
+    }
+    diff_gap(i..old.len(), j..new.len());
+
+    (old_changes, new_changes)
+}
+
+/// Changed spans of two versions of a line: (spans in `old`, spans in `new`)
+///
+/// Words, runs of whitespace and single punctuation characters are compared
+/// first; each changed stretch is then narrowed down to the characters that
+/// actually differ, so `count + 1` vs `count + 2` only marks the digits.
+pub fn diff_line(old: &str, new: &str) -> (LineChanges, LineChanges) {
+    if old == new {
+        return (Vec::new(), Vec::new());
+    }
+
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+    let old_words: Vec<&str> = old_tokens.iter().map(|r| &old[r.clone()]).collect();
+    let new_words: Vec<&str> = new_tokens.iter().map(|r| &new[r.clone()]).collect();
+
+    let mut old_changes = Vec::new();
+    let mut new_changes = Vec::new();
+    let mut push_gap = |old_gap: Range<usize>, new_gap: Range<usize>| {
+        let old_span = token_span(&old_tokens, old_gap, old.len());
+        let new_span = token_span(&new_tokens, new_gap, new.len());
+        let (old_span, new_span) = trim_common(old, old_span, new, new_span);
+        if !old_span.is_empty() {
+            old_changes.push(old_span);
+        }
+        if !new_span.is_empty() {
+            new_changes.push(new_span);
+        }
+    };
+
+    let (mut i, mut j) = (0, 0);
+    for (match_i, match_j) in common_subsequence(&old_words, &new_words) {
+        if i < match_i || j < match_j {
+            push_gap(i..match_i, j..match_j);
+        }
+        i = match_i + 1;
+        j = match_j + 1;
+    }
+    if i < old_tokens.len() || j < new_tokens.len() {
+        push_gap(i..old_tokens.len(), j..new_tokens.len());
+    }
+
+    (old_changes, new_changes)
+}
+
+fn whole_line(line: &str) -> LineChanges {
+    if line.is_empty() {
+        Vec::new()
+    } else {
+        std::iter::once(0..line.len()).collect()
+    }
+}
+
+/// Split a line into words, runs of whitespace and single other characters
+fn tokenize(line: &str) -> Vec<Range<usize>> {
+    #[derive(PartialEq)]
+    enum Class {
+        Word,
+        Space,
+        Other,
+    }
+    let class = |c: char| {
+        if c.is_alphanumeric() || c == '_' {
+            Class::Word
+        } else if c.is_whitespace() {
+            Class::Space
+        } else {
+            Class::Other
+        }
+    };
+
+    let mut tokens: Vec<Range<usize>> = Vec::new();
+    let mut previous: Option<Class> = None;
+    for (index, c) in line.char_indices() {
+        let current = class(c);
+        let extends = current != Class::Other && previous.as_ref() == Some(&current);
+        match tokens.last_mut() {
+            Some(last) if extends => last.end = index + c.len_utf8(),
+            _ => tokens.push(index..index + c.len_utf8()),
+        }
+        previous = Some(current);
+    }
+    tokens
+}
+
+/// Byte range covered by a run of tokens (an empty range where the run would start)
+fn token_span(tokens: &[Range<usize>], run: Range<usize>, len: usize) -> Range<usize> {
+    if run.is_empty() {
+        let at = tokens.get(run.start).map_or(len, |t| t.start);
+        return at..at;
+    }
+    tokens[run.start].start..tokens[run.end - 1].end
+}
+
+/// Shrink two spans by the characters they start and end with in common
+fn trim_common(
+    old: &str,
+    old_span: Range<usize>,
+    new: &str,
+    new_span: Range<usize>,
+) -> (Range<usize>, Range<usize>) {
+    let old_text = &old[old_span.clone()];
+    let new_text = &new[new_span.clone()];
+
+    let prefix: usize = old_text
+        .chars()
+        .zip(new_text.chars())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum();
+    let suffix: usize = old_text[prefix..]
+        .chars()
+        .rev()
+        .zip(new_text[prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum();
+
+    (
+        old_span.start + prefix..old_span.end - suffix,
+        new_span.start + prefix..new_span.end - suffix,
+    )
+}
+
+/// Index pairs of a longest common subsequence of two sequences
+///
+/// Sequences too long to compare cheaply share nothing.
+fn common_subsequence<T: PartialEq>(a: &[T], b: &[T]) -> Vec<(usize, usize)> {
+    if a.is_empty() || b.is_empty() || a.len() * b.len() > MAX_TABLE_SIZE {
+        return Vec::new();
+    }
+
+    // lengths[i][j]: LCS length of a[i..] and b[j..]
+    let width = b.len() + 1;
+    let mut lengths = vec![0u32; (a.len() + 1) * width];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lengths[i * width + j] = if a[i] == b[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The text of each changed span
+    fn spans<'a>(line: &'a str, changes: &[Range<usize>]) -> Vec<&'a str> {
+        changes.iter().map(|r| &line[r.clone()]).collect()
+    }
+
+    #[test]
+    fn test_diff_line_narrows_to_changed_characters() {
+        let old = "let total = count + 1;";
+        let new = "let total = count + 2;";
+        let (old_changes, new_changes) = diff_line(old, new);
+
+        assert_eq!(spans(old, &old_changes), vec!["1"]);
+        assert_eq!(spans(new, &new_changes), vec!["2"]);
+    }
+
+    #[test]
+    fn test_diff_line_words() {
+        let old = "fn load(path: &Path)";
+        let new = "pub fn load(path: &Path, strict: bool)";
+        let (old_changes, new_changes) = diff_line(old, new);
+
+        assert!(old_changes.is_empty());
+        assert_eq!(spans(new, &new_changes), vec!["pub ", ", strict: bool"]);
+    }
+
+    #[test]
+    fn test_diff_line_multibyte() {
+        let (old_changes, new_changes) = diff_line("café au lait", "cafè au lait");
+        assert_eq!(spans("café au lait", &old_changes), vec!["é"]);
+        assert_eq!(spans("cafè au lait", &new_changes), vec!["è"]);
+    }
+
+    #[test]
+    fn test_diff_lines_pairs_and_whole_lines() {
+        let old = ["a", "value = 1", "z"];
+        let new = ["a", "value = 2", "added", "z"];
+        let (old_changes, new_changes) = diff_lines(&old, &new);
+
+        assert_eq!(old_changes, vec![vec![], vec![8..9], vec![]]);
+        assert_eq!(new_changes, vec![vec![], vec![8..9], vec![0..5], vec![]]);
+    }
+
+    #[test]
+    fn test_hunk_changes_against_base() {
+        let hunk = ConflictHunk::new("x = 2".to_string(), "x = 1\ny".to_string(), 0, 6)
+            .with_base("x = 1".to_string());
+        let changes = hunk_changes(&hunk);
+
+        assert_eq!(changes.current, vec![vec![4..5]]);
+        assert_eq!(changes.incoming, vec![vec![], vec![0..1]]);
+        assert_eq!(changes.base, vec![vec![4..5]]);
+    }
+}
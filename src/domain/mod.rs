@@ -1,7 +1,13 @@
 pub mod conflict;
+pub mod file_conflict;
 pub mod git_operation;
+pub mod inline_diff;
 pub mod resolution;
 
-pub use conflict::{ConflictHunk, ConflictedFile};
+pub use conflict::{
+    raw_lines, ConflictHunk, ConflictedFile, LineOrigin, MergedLine, DEFAULT_MARKER_SIZE,
+};
+pub use file_conflict::{BlobInfo, FileConflictKind, FileResolution};
 pub use git_operation::GitOperation;
-pub use resolution::Resolution;
+pub use inline_diff::{diff_line, diff_lines, hunk_changes, HunkChanges, LineChanges};
+pub use resolution::{AutoResolution, LineSelection, Resolution, Side};
@@ -1,5 +1,5 @@
 /// Represents the resolution strategy for a conflict
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Resolution {
     /// Accept current (HEAD) changes
     Current,
@@ -7,6 +7,53 @@ pub enum Resolution {
     Incoming,
     /// Accept both changes
     Both,
+    /// Hand-merged content written by the user
+    Custom(String),
+    /// Chosen lines of each side, in the order they were picked
+    Lines(LineSelection),
+}
+
+/// Side of a conflict a line comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Current,
+    Incoming,
+}
+
+/// Lines picked from the sides of a conflict, in the order they are written
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LineSelection {
+    /// Side and index of each picked line
+    pub lines: Vec<(Side, usize)>,
+}
+
+impl LineSelection {
+    /// Every line of a side, in order
+    pub fn whole_side(side: Side, len: usize) -> Self {
+        Self {
+            lines: (0..len).map(|index| (side, index)).collect(),
+        }
+    }
+
+    /// Check if no line is picked
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Position of a line in the result, if it is picked
+    pub fn position(&self, side: Side, index: usize) -> Option<usize> {
+        self.lines.iter().position(|&line| line == (side, index))
+    }
+
+    /// Pick a line after the ones already picked, or drop it if it was picked
+    pub fn toggle(&mut self, side: Side, index: usize) {
+        match self.position(side, index) {
+            Some(position) => {
+                self.lines.remove(position);
+            }
+            None => self.lines.push((side, index)),
+        }
+    }
 }
 
 impl Resolution {
@@ -16,6 +63,33 @@ impl Resolution {
             Resolution::Current => "Current (HEAD)",
             Resolution::Incoming => "Incoming",
             Resolution::Both => "Both",
+            Resolution::Custom(_) => "Custom",
+            Resolution::Lines(_) => "Lines",
+        }
+    }
+}
+
+/// Why a conflict was resolved without asking
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoResolution {
+    /// Both sides made the same change
+    IdenticalSides,
+    /// Only the current side differs from the base (diff3)
+    OnlyCurrentChanged,
+    /// Only the incoming side differs from the base (diff3)
+    OnlyIncomingChanged,
+    /// The sides differ in trailing whitespace or line endings only; the current side is kept
+    WhitespaceOnly,
+}
+
+impl AutoResolution {
+    /// Get a display string for the reason
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AutoResolution::IdenticalSides => "identical sides",
+            AutoResolution::OnlyCurrentChanged => "only current changed",
+            AutoResolution::OnlyIncomingChanged => "only incoming changed",
+            AutoResolution::WhitespaceOnly => "whitespace-only difference",
         }
     }
 }
@@ -29,6 +103,26 @@ mod tests {
         assert_eq!(Resolution::Current.as_str(), "Current (HEAD)");
         assert_eq!(Resolution::Incoming.as_str(), "Incoming");
         assert_eq!(Resolution::Both.as_str(), "Both");
+        assert_eq!(Resolution::Custom("x".to_string()).as_str(), "Custom");
+        assert_eq!(
+            Resolution::Lines(LineSelection::default()).as_str(),
+            "Lines"
+        );
+    }
+
+    #[test]
+    fn test_line_selection_keeps_pick_order() {
+        let mut selection = LineSelection::whole_side(Side::Current, 2);
+        selection.toggle(Side::Incoming, 0);
+        selection.toggle(Side::Current, 0);
+        selection.toggle(Side::Current, 0);
+
+        assert_eq!(
+            selection.lines,
+            vec![(Side::Current, 1), (Side::Incoming, 0), (Side::Current, 0)]
+        );
+        assert_eq!(selection.position(Side::Incoming, 0), Some(1));
+        assert_eq!(selection.position(Side::Incoming, 1), None);
     }
 
     #[test]
@@ -39,15 +133,20 @@ mod tests {
         assert_ne!(Resolution::Current, Resolution::Incoming);
         assert_ne!(Resolution::Current, Resolution::Both);
         assert_ne!(Resolution::Incoming, Resolution::Both);
+        assert_ne!(
+            Resolution::Custom("a".to_string()),
+            Resolution::Custom("b".to_string())
+        );
     }
 
     #[test]
-    fn test_resolution_copy_clone() {
+    fn test_resolution_clone() {
         let r1 = Resolution::Current;
-        let r2 = r1; // Copy trait
+        let r2 = r1.clone();
         assert_eq!(r1, r2);
 
-        let r3 = r1; // Copy trait (clone not needed)
-        assert_eq!(r1, r3);
+        let r3 = Resolution::Custom("merged".to_string());
+        let r4 = r3.clone();
+        assert_eq!(r3, r4);
     }
 }
@@ -1,10 +1,20 @@
 use anyhow::{Context, Result};
+use git2::{Index, IndexEntry, IndexTime, Oid, Repository};
 use std::fs;
+use std::path::{Path, PathBuf};
 
-use crate::domain::ConflictedFile;
+use super::detector::repo_relative_path;
+use super::encoding::encode;
+use crate::domain::{ConflictedFile, FileConflictKind, FileResolution};
 
 /// Apply resolutions to a conflicted file and save it
 pub fn apply_resolutions(conflicted_file: &ConflictedFile) -> Result<()> {
+    if conflicted_file.auto_resolved_count() > 0 {
+        anyhow::bail!(
+            "Cannot apply resolutions: {} automatic resolutions are not reviewed yet",
+            conflicted_file.auto_resolved_count()
+        );
+    }
     if !conflicted_file.is_fully_resolved() {
         anyhow::bail!(
             "Cannot apply resolutions: not all conflicts are resolved ({}/{} resolved)",
@@ -13,60 +23,19 @@ pub fn apply_resolutions(conflicted_file: &ConflictedFile) -> Result<()> {
         );
     }
 
-    let content = &conflicted_file.original_content;
-    let lines: Vec<&str> = content.lines().collect();
-    let mut result_lines = Vec::new();
-    let mut current_line = 0;
-
-    for (i, conflict) in conflicted_file.conflicts.iter().enumerate() {
-        // Add lines before this conflict with safe indexing
-        while current_line < conflict.start_line {
-            let line = lines.get(current_line).ok_or_else(|| {
-                anyhow::anyhow!(
-                    "Internal error: line index {} out of bounds (total lines: {})",
-                    current_line,
-                    lines.len()
-                )
-            })?;
-            result_lines.push(line.to_string());
-            current_line += 1;
-        }
-
-        // Add resolved content with safe indexing
-        let resolution = conflicted_file
-            .resolutions
-            .get(i)
-            .and_then(|r| *r)
-            .ok_or_else(|| {
-                anyhow::anyhow!(
-                    "Internal error: conflict {} should be resolved but wasn't",
-                    i
-                )
-            })?;
-
-        let resolved_content = conflict.resolve(resolution);
-        for line in resolved_content.lines() {
-            result_lines.push(line.to_string());
-        }
-
-        // Skip past the conflict markers
-        current_line = conflict.end_line + 1;
-    }
+    apply_partial_resolutions(conflicted_file)
+}
 
-    // Add remaining lines after the last conflict with safe indexing
-    while current_line < lines.len() {
-        let line = lines.get(current_line).ok_or_else(|| {
-            anyhow::anyhow!(
-                "Internal error: line index {} out of bounds (total lines: {})",
-                current_line,
-                lines.len()
-            )
-        })?;
-        result_lines.push(line.to_string());
-        current_line += 1;
-    }
+/// Save a file with the conflicts resolved so far, keeping the markers of the others
+pub fn apply_partial_resolutions(conflicted_file: &ConflictedFile) -> Result<()> {
+    let content = &conflicted_file.original_content;
+    let result_lines: Vec<String> = conflicted_file
+        .merged_lines()
+        .into_iter()
+        .map(|line| line.text)
+        .collect();
 
-    // Preserve original line endings and trailing newline behavior
+    // Lines carry their own `\r`, so only the `\n`s and the trailing newline are added back
     let original_had_trailing_newline = content.ends_with('\n');
     let final_content = if original_had_trailing_newline {
         format!("{}\n", result_lines.join("\n"))
@@ -74,41 +43,286 @@ pub fn apply_resolutions(conflicted_file: &ConflictedFile) -> Result<()> {
         result_lines.join("\n")
     };
 
-    // Atomic write using temp file + rename
-    let parent_dir = conflicted_file
-        .path
-        .parent()
-        .unwrap_or_else(|| std::path::Path::new("."));
+    let bytes = encode(&final_content, conflicted_file.encoding.as_deref())
+        .with_context(|| format!("Failed to encode {}", conflicted_file.path.display()))?;
+    write_atomically(&conflicted_file.path, &bytes)
+}
+
+/// Write the original (conflicted) content of a file back to disk
+///
+/// Files rebuilt from the index get their bytes from before saki back, hand edits included.
+pub fn restore_original_content(conflicted_file: &ConflictedFile) -> Result<()> {
+    if let Some(bytes) = &conflicted_file.worktree_content {
+        return write_atomically(&conflicted_file.path, bytes);
+    }
+    let bytes = encode(
+        &conflicted_file.original_content,
+        conflicted_file.encoding.as_deref(),
+    )
+    .with_context(|| format!("Failed to encode {}", conflicted_file.path.display()))?;
+    write_atomically(&conflicted_file.path, &bytes)
+}
+
+/// Write a file atomically using a temp file + rename
+fn write_atomically(path: &Path, content: &[u8]) -> Result<()> {
+    let parent_dir = path.parent().unwrap_or_else(|| Path::new("."));
 
     let temp_path = parent_dir.join(format!(
         ".{}.murasaki_rs.tmp",
-        conflicted_file
-            .path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("file")
+        path.file_name().and_then(|n| n.to_str()).unwrap_or("file")
     ));
 
     // Write to temp file first
-    fs::write(&temp_path, &final_content)
+    fs::write(&temp_path, content)
         .with_context(|| format!("Failed to write to temporary file: {}", temp_path.display()))?;
 
     // Atomic rename (on Unix systems, this is guaranteed atomic)
-    fs::rename(&temp_path, &conflicted_file.path).with_context(|| {
+    fs::rename(&temp_path, path).with_context(|| {
         format!(
             "Failed to rename {} to {}",
             temp_path.display(),
-            conflicted_file.path.display()
+            path.display()
         )
     })?;
 
     Ok(())
 }
 
+/// Apply the resolution of a whole-file conflict, staging the result if `stage` is set
+///
+/// Kept files are restored from their index stage blob, deleted files are
+/// removed from the worktree (and from the index when staging). Unstaged, the
+/// conflict stays in the index until the result is added or removed by hand.
+pub fn apply_file_resolution(
+    repo: &Repository,
+    conflicted_file: &ConflictedFile,
+    stage: bool,
+) -> Result<()> {
+    let kind = conflicted_file
+        .file_conflict
+        .as_ref()
+        .context("Cannot apply file resolution: not a whole-file conflict")?;
+    let resolution = conflicted_file
+        .file_resolution
+        .context("Cannot apply file resolution: conflict is not resolved")?;
+
+    let relative = repo_relative_path(repo, &conflicted_file.path)?;
+    let mut index = repo.index().context("Failed to read repository index")?;
+
+    match kind {
+        FileConflictKind::RenamedDifferently { ours, theirs } => {
+            let (keep, number, drop) = match resolution {
+                FileResolution::KeepOurs => (ours, 2, theirs),
+                FileResolution::KeepTheirs => (theirs, 3, ours),
+                FileResolution::Delete => {
+                    anyhow::bail!("A rename conflict cannot be resolved by deleting the file")
+                }
+            };
+            keep_stage(repo, &mut index, keep, number, stage)?;
+            remove_path(repo, &mut index, drop, stage)?;
+            remove_path(repo, &mut index, &relative, stage)?;
+        }
+        _ => match resolution {
+            FileResolution::KeepOurs => keep_stage(repo, &mut index, &relative, 2, stage)?,
+            FileResolution::KeepTheirs => keep_stage(repo, &mut index, &relative, 3, stage)?,
+            FileResolution::Delete => remove_path(repo, &mut index, &relative, stage)?,
+        },
+    }
+
+    if stage {
+        index.write().context("Failed to write repository index")?;
+    }
+    Ok(())
+}
+
+/// Stage a resolved file, replacing its conflict stages in the index
+pub fn mark_resolved(repo: &Repository, path: &Path) -> Result<()> {
+    let relative = repo_relative_path(repo, path)?;
+    let mut index = repo.index().context("Failed to read repository index")?;
+
+    index
+        .add_path(&relative)
+        .with_context(|| format!("Failed to stage {}", relative.display()))?;
+    index.write().context("Failed to write repository index")?;
+    Ok(())
+}
+
+/// Check an index stage out to the worktree, staging it as the resolved version if `add` is set
+///
+/// The entry goes through a scratch index so checkout applies the smudge and
+/// line-ending filters and the entry's file mode, like `git checkout --ours`.
+fn keep_stage(
+    repo: &Repository,
+    index: &mut Index,
+    relative: &Path,
+    stage: i32,
+    add: bool,
+) -> Result<()> {
+    let entry = index
+        .get_path(relative, stage)
+        .with_context(|| format!("No stage {} entry for {}", stage, relative.display()))?;
+    let path = entry.path.clone();
+
+    let mut scratch = Index::new().context("Failed to create index")?;
+    scratch
+        .add(&IndexEntry {
+            flags: entry.flags & !STAGE_FLAGS,
+            ..entry
+        })
+        .with_context(|| format!("Failed to read stage {} of {}", stage, relative.display()))?;
+    let mut checkout = git2::build::CheckoutBuilder::new();
+    checkout
+        .force()
+        .update_index(false)
+        .disable_pathspec_match(true)
+        .path(path);
+    repo.checkout_index(Some(&mut scratch), Some(&mut checkout))
+        .with_context(|| format!("Failed to write file: {}", relative.display()))?;
+
+    if add {
+        index
+            .add_path(relative)
+            .with_context(|| format!("Failed to stage {}", relative.display()))?;
+    }
+    Ok(())
+}
+
+/// Remove a path from the worktree (if present), and from every index stage if `unstage` is set
+fn remove_path(repo: &Repository, index: &mut Index, relative: &Path, unstage: bool) -> Result<()> {
+    let workdir = repo
+        .workdir()
+        .context("Repository has no working directory")?;
+
+    let target = workdir.join(relative);
+    if target.exists() {
+        fs::remove_file(&target)
+            .with_context(|| format!("Failed to delete file: {}", target.display()))?;
+    }
+
+    if unstage {
+        index
+            .remove_path(relative)
+            .with_context(|| format!("Failed to remove {} from the index", relative.display()))?;
+    }
+    Ok(())
+}
+
+/// Bits of the index entry flags holding the stage
+const STAGE_FLAGS: u16 = 0x3000;
+
+/// Index stages and worktree contents of a whole-file conflict, taken before applying it
+#[derive(Debug, Clone)]
+pub struct ConflictSnapshot {
+    /// Conflict stage entries: (path as stored in the index, stage, mode, blob id)
+    entries: Vec<(Vec<u8>, i32, u32, Oid)>,
+    /// Worktree contents of every affected path (None if the file didn't exist)
+    files: Vec<(PathBuf, Option<Vec<u8>>)>,
+}
+
+/// Paths (relative to the repository) touched when applying a whole-file conflict
+fn affected_paths(repo: &Repository, conflicted_file: &ConflictedFile) -> Result<Vec<PathBuf>> {
+    let mut paths = vec![repo_relative_path(repo, &conflicted_file.path)?];
+    if let Some(FileConflictKind::RenamedDifferently { ours, theirs }) =
+        &conflicted_file.file_conflict
+    {
+        paths.push(ours.clone());
+        paths.push(theirs.clone());
+    }
+    Ok(paths)
+}
+
+/// Capture the conflict state of a whole-file conflict so it can be restored later
+pub fn snapshot_file_conflict(
+    repo: &Repository,
+    conflicted_file: &ConflictedFile,
+) -> Result<ConflictSnapshot> {
+    let workdir = repo
+        .workdir()
+        .context("Repository has no working directory")?;
+    let index = repo.index().context("Failed to read repository index")?;
+
+    let mut snapshot = ConflictSnapshot {
+        entries: Vec::new(),
+        files: Vec::new(),
+    };
+    for path in affected_paths(repo, conflicted_file)? {
+        for stage in 1..=3 {
+            if let Some(entry) = index.get_path(&path, stage) {
+                snapshot
+                    .entries
+                    .push((entry.path, stage, entry.mode, entry.id));
+            }
+        }
+        snapshot
+            .files
+            .push((path.clone(), fs::read(workdir.join(&path)).ok()));
+    }
+
+    Ok(snapshot)
+}
+
+/// Put back the index stages and worktree files captured by `snapshot_file_conflict`
+pub fn restore_file_conflict(repo: &Repository, snapshot: &ConflictSnapshot) -> Result<()> {
+    let workdir = repo
+        .workdir()
+        .context("Repository has no working directory")?;
+    let mut index = repo.index().context("Failed to read repository index")?;
+
+    for (path, content) in &snapshot.files {
+        // Drop whatever was staged for the path when the conflict was applied
+        let _ = index.remove_path(path);
+
+        let target = workdir.join(path);
+        match content {
+            Some(content) => {
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent).with_context(|| {
+                        format!("Failed to create directory: {}", parent.display())
+                    })?;
+                }
+                fs::write(&target, content)
+                    .with_context(|| format!("Failed to write file: {}", target.display()))?;
+            }
+            None if target.exists() => {
+                fs::remove_file(&target)
+                    .with_context(|| format!("Failed to delete file: {}", target.display()))?;
+            }
+            None => {}
+        }
+    }
+
+    for (path, stage, mode, id) in &snapshot.entries {
+        let entry = IndexEntry {
+            ctime: IndexTime::new(0, 0),
+            mtime: IndexTime::new(0, 0),
+            dev: 0,
+            ino: 0,
+            mode: *mode,
+            uid: 0,
+            gid: 0,
+            file_size: 0,
+            id: *id,
+            flags: ((*stage as u16) << 12) & STAGE_FLAGS,
+            flags_extended: 0,
+            path: path.clone(),
+        };
+        index.add(&entry).with_context(|| {
+            format!(
+                "Failed to restore conflict for {}",
+                String::from_utf8_lossy(path)
+            )
+        })?;
+    }
+
+    index.write().context("Failed to write repository index")?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::{ConflictHunk, Resolution};
+    use crate::domain::{ConflictHunk, LineSelection, Resolution, Side};
+    use crate::git::parser::{parse_conflicts, DEFAULT_MARKER_SIZE};
     use std::io::Write;
     use tempfile::NamedTempFile;
 
@@ -132,4 +346,147 @@ mod tests {
         assert!(!new_content.contains("incoming"));
         assert!(!new_content.contains("<<<<<<<"));
     }
+
+    #[test]
+    fn test_apply_custom_resolution() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        let content = "line 1\n<<<<<<< HEAD\ncurrent\n=======\nincoming\n>>>>>>> branch\nline 2\n";
+        write!(temp_file, "{}", content).unwrap();
+
+        let path = temp_file.path().to_path_buf();
+        let hunk = ConflictHunk::new("current".to_string(), "incoming".to_string(), 1, 5);
+        let mut file = ConflictedFile::new(path.clone(), vec![hunk], content.to_string());
+
+        file.set_resolution(0, Resolution::Custom("merged a\nmerged b".to_string()));
+        apply_resolutions(&file).unwrap();
+
+        let new_content = fs::read_to_string(&path).unwrap();
+        assert_eq!(new_content, "line 1\nmerged a\nmerged b\nline 2\n");
+    }
+
+    #[test]
+    fn test_apply_preserves_crlf_and_mixed_endings() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        let content = "@echo off\r\n<<<<<<< HEAD\r\n  set A=1  \r\n=======\r\n\tset B=2\r\n>>>>>>> branch\r\nlf line\nend\r\n";
+        write!(temp_file, "{}", content).unwrap();
+
+        let path = temp_file.path().to_path_buf();
+        let mut file = parse_conflicts(&path, DEFAULT_MARKER_SIZE, None).unwrap();
+        file.set_resolution(0, Resolution::Both);
+        apply_resolutions(&file).unwrap();
+
+        assert_eq!(
+            fs::read(&path).unwrap(),
+            b"@echo off\r\n  set A=1  \r\n\tset B=2\r\nlf line\nend\r\n"
+        );
+    }
+
+    #[test]
+    fn test_apply_picked_lines_in_order() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        let content = "top\r\n<<<<<<< HEAD\r\na1\r\na2\r\na3\r\n=======\r\nb1\r\nb2\r\n>>>>>>> branch\r\nend\r\n";
+        write!(temp_file, "{}", content).unwrap();
+
+        let path = temp_file.path().to_path_buf();
+        let mut file = parse_conflicts(&path, DEFAULT_MARKER_SIZE, None).unwrap();
+        let selection = LineSelection {
+            lines: vec![(Side::Current, 0), (Side::Current, 2), (Side::Incoming, 1)],
+        };
+        file.set_resolution(0, Resolution::Lines(selection));
+        apply_resolutions(&file).unwrap();
+
+        assert_eq!(
+            fs::read(&path).unwrap(),
+            b"top\r\na1\r\na3\r\nb2\r\nend\r\n"
+        );
+    }
+
+    /// Take the incoming side of a file in a legacy encoding, then restore it
+    fn assert_round_trip(content: &[u8], declared: Option<&str>, incoming: &str, expected: &[u8]) {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(content).unwrap();
+        let path = temp_file.path().to_path_buf();
+
+        let mut file = parse_conflicts(&path, DEFAULT_MARKER_SIZE, declared).unwrap();
+        assert_eq!(file.conflicts[0].incoming, incoming);
+
+        file.set_resolution(0, Resolution::Incoming);
+        apply_resolutions(&file).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), expected);
+
+        restore_original_content(&file).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), content);
+    }
+
+    #[test]
+    fn test_apply_round_trips_latin1() {
+        assert_round_trip(
+            b"caf\xe9\n<<<<<<< HEAD\nna\xefve\n=======\nd\xe9j\xe0\n>>>>>>> b\n",
+            None,
+            "déjà",
+            b"caf\xe9\nd\xe9j\xe0\n",
+        );
+    }
+
+    #[test]
+    fn test_apply_round_trips_declared_shift_jis() {
+        assert_round_trip(
+            b"\x93\xfa\n<<<<<<< HEAD\n\x96\x7b\n=======\n\x8c\xea\n>>>>>>> b\n",
+            Some("Shift_JIS"),
+            "語",
+            b"\x93\xfa\n\x8c\xea\n",
+        );
+    }
+
+    #[test]
+    fn test_apply_round_trips_utf_16() {
+        let utf_16 = |text: &str| -> Vec<u8> {
+            let mut bytes = vec![0xff, 0xfe];
+            bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+            bytes
+        };
+        assert_round_trip(
+            &utf_16("a\n<<<<<<< HEAD\nx\n=======\ny\n>>>>>>> b\n"),
+            None,
+            "y",
+            &utf_16("a\ny\n"),
+        );
+    }
+
+    #[test]
+    fn test_apply_partial_resolutions_keeps_other_markers() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        let content =
+            "<<<<<<< HEAD\nx\n=======\nx\n>>>>>>> b\nmid\n<<<<<<< HEAD\ny\n=======\nz\n>>>>>>> b\n";
+        write!(temp_file, "{}", content).unwrap();
+
+        let path = temp_file.path().to_path_buf();
+        let mut file = parse_conflicts(&path, DEFAULT_MARKER_SIZE, None).unwrap();
+        file.set_resolution(0, Resolution::Current);
+        assert!(apply_resolutions(&file).is_err());
+
+        apply_partial_resolutions(&file).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "x\nmid\n<<<<<<< HEAD\ny\n=======\nz\n>>>>>>> b\n"
+        );
+    }
+
+    #[test]
+    fn test_restore_original_content() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        let content = "a\n<<<<<<< HEAD\nx\n=======\ny\n>>>>>>> b\n";
+        write!(temp_file, "{}", content).unwrap();
+
+        let path = temp_file.path().to_path_buf();
+        let hunk = ConflictHunk::new("x".to_string(), "y".to_string(), 1, 5);
+        let mut file = ConflictedFile::new(path.clone(), vec![hunk], content.to_string());
+
+        file.set_resolution(0, Resolution::Incoming);
+        apply_resolutions(&file).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "a\ny\n");
+
+        restore_original_content(&file).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+    }
 }
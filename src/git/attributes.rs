@@ -0,0 +1,135 @@
+use git2::{AttrCheckFlags, AttrValue, Repository};
+use std::path::Path;
+
+use super::detector::repo_relative_path;
+use super::parser::DEFAULT_MARKER_SIZE;
+
+/// Attribute that turns off the leftover-marker check for a path (`path -saki-check`)
+pub const CHECK_ATTRIBUTE: &str = "saki-check";
+
+/// Which `.gitattributes` files attributes are read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttrSource {
+    /// The working tree's, then the index's (for worktree files)
+    Worktree,
+    /// The index's only (for staged content, which may not match the worktree)
+    Index,
+}
+
+impl AttrSource {
+    fn flags(self) -> AttrCheckFlags {
+        match self {
+            AttrSource::Worktree => AttrCheckFlags::FILE_THEN_INDEX,
+            AttrSource::Index => AttrCheckFlags::INDEX_ONLY,
+        }
+    }
+}
+
+/// Get the value of a git attribute for a path relative to the repository root
+fn attribute<'r>(
+    repo: &'r Repository,
+    relative: &Path,
+    name: &str,
+    source: AttrSource,
+) -> AttrValue<'r> {
+    AttrValue::from_string(repo.get_attr(relative, name, source.flags()).ok().flatten())
+}
+
+/// Marker size set by the `conflict-marker-size` attribute, if any
+pub fn conflict_marker_size(
+    repo: &Repository,
+    relative: &Path,
+    source: AttrSource,
+) -> Option<usize> {
+    match attribute(repo, relative, "conflict-marker-size", source) {
+        AttrValue::String(value) => value.trim().parse().ok().filter(|size| *size > 0),
+        _ => None,
+    }
+}
+
+/// Marker size to parse a worktree file with: its attribute, or git's default
+pub fn marker_size_for(repo: &Repository, path: &Path) -> usize {
+    repo_relative_path(repo, path)
+        .ok()
+        .and_then(|relative| conflict_marker_size(repo, &relative, AttrSource::Worktree))
+        .unwrap_or(DEFAULT_MARKER_SIZE)
+}
+
+/// Encoding set by the `working-tree-encoding` attribute, if any
+pub fn working_tree_encoding(repo: &Repository, relative: &Path) -> Option<String> {
+    match attribute(
+        repo,
+        relative,
+        "working-tree-encoding",
+        AttrSource::Worktree,
+    ) {
+        AttrValue::String(value) => Some(value.trim().to_string()),
+        _ => None,
+    }
+}
+
+/// Declared encoding of a worktree file
+pub fn encoding_for(repo: &Repository, path: &Path) -> Option<String> {
+    repo_relative_path(repo, path)
+        .ok()
+        .and_then(|relative| working_tree_encoding(repo, &relative))
+}
+
+/// Check if `.gitattributes` excludes a path from the leftover-marker check
+pub fn is_check_disabled(repo: &Repository, relative: &Path, source: AttrSource) -> bool {
+    matches!(
+        attribute(repo, relative, CHECK_ATTRIBUTE, source),
+        AttrValue::False
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn repo_with_attributes(attributes: &str) -> (TempDir, Repository) {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        fs::write(dir.path().join(".gitattributes"), attributes).unwrap();
+        (dir, repo)
+    }
+
+    #[test]
+    fn test_conflict_marker_size() {
+        let (_dir, repo) = repo_with_attributes("*.adoc conflict-marker-size=32\n");
+        assert_eq!(
+            conflict_marker_size(&repo, Path::new("docs/index.adoc"), AttrSource::Worktree),
+            Some(32)
+        );
+        assert_eq!(
+            conflict_marker_size(&repo, Path::new("main.rs"), AttrSource::Worktree),
+            None
+        );
+    }
+
+    #[test]
+    fn test_working_tree_encoding() {
+        let (_dir, repo) = repo_with_attributes("legacy/** working-tree-encoding=Shift_JIS\n");
+        assert_eq!(
+            working_tree_encoding(&repo, Path::new("legacy/menu.c")).as_deref(),
+            Some("Shift_JIS")
+        );
+        assert_eq!(working_tree_encoding(&repo, Path::new("src/lib.rs")), None);
+    }
+
+    #[test]
+    fn test_is_check_disabled() {
+        let (_dir, repo) = repo_with_attributes("fixtures/** -saki-check\n");
+        let disabled = |path: &str| is_check_disabled(&repo, Path::new(path), AttrSource::Worktree);
+        assert!(disabled("fixtures/conflict.txt"));
+        assert!(!disabled("src/lib.rs"));
+        // Not staged yet, so the index doesn't have the attribute
+        assert!(!is_check_disabled(
+            &repo,
+            Path::new("fixtures/conflict.txt"),
+            AttrSource::Index
+        ));
+    }
+}
@@ -1,10 +1,16 @@
 use anyhow::{Context, Result};
+use git2::{IndexEntry, IndexTime, MergeFileOptions, Odb, Repository};
+use serde::Deserialize;
 use std::process::Command;
 
 /// Continue the rebase after resolving conflicts
+///
+/// The TUI owns the terminal, so git can't open an editor: commit messages are
+/// kept as they are (`GIT_EDITOR=true`).
 pub fn continue_rebase() -> Result<()> {
     let output = Command::new("git")
         .args(["rebase", "--continue"])
+        .env("GIT_EDITOR", "true")
         .output()
         .context("Failed to execute git rebase --continue")?;
 
@@ -32,9 +38,12 @@ pub fn abort_rebase() -> Result<()> {
 }
 
 /// Skip the current commit in the rebase
+///
+/// Like `continue_rebase`, without an editor for the commits replayed next.
 pub fn skip_rebase() -> Result<()> {
     let output = Command::new("git")
         .args(["rebase", "--skip"])
+        .env("GIT_EDITOR", "true")
         .output()
         .context("Failed to execute git rebase --skip")?;
 
@@ -136,10 +145,34 @@ pub fn restore_all() -> Result<()> {
     Ok(())
 }
 
+/// Extra `git commit` flags used for commits made from saki
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CommitOptions {
+    /// Add a Signed-off-by trailer (`--signoff`)
+    pub sign_off: bool,
+    /// Skip the pre-commit and commit-msg hooks (`--no-verify`)
+    pub no_verify: bool,
+}
+
+impl CommitOptions {
+    fn args(&self) -> Vec<&'static str> {
+        let mut args = Vec::new();
+        if self.sign_off {
+            args.push("--signoff");
+        }
+        if self.no_verify {
+            args.push("--no-verify");
+        }
+        args
+    }
+}
+
 /// Create a git commit with the given message
-pub fn commit_changes(message: &str) -> Result<()> {
+pub fn commit_changes(message: &str, options: &CommitOptions) -> Result<()> {
     let output = Command::new("git")
         .args(["commit", "-m", message])
+        .args(options.args())
         .output()
         .context("Failed to execute git commit")?;
 
@@ -168,3 +201,113 @@ pub fn get_file_diff(path: &str, staged: bool) -> Result<String> {
     let diff = String::from_utf8_lossy(&output.stdout).to_string();
     Ok(diff)
 }
+
+/// Three-way merge file contents with libgit2 in diff3 style
+///
+/// Returns the merged text, with conflict markers wherever the sides disagree.
+/// A missing base is treated as an empty common ancestor (add/add). The sides
+/// are stored in an in-memory object database, so nothing is written to disk.
+pub fn merge_file(
+    ours: &[u8],
+    base: Option<&[u8]>,
+    theirs: &[u8],
+    labels: [&str; 3],
+    marker_size: usize,
+) -> Result<String> {
+    let odb = Odb::new().context("Failed to create object database")?;
+    odb.add_new_mempack_backend(1)
+        .context("Failed to create in-memory object store")?;
+    let repo = Repository::from_odb(odb).context("Failed to open object database")?;
+
+    let entry = |content: &[u8]| -> Result<IndexEntry> {
+        let id = repo.blob(content).context("Failed to store merge side")?;
+        Ok(IndexEntry {
+            ctime: IndexTime::new(0, 0),
+            mtime: IndexTime::new(0, 0),
+            dev: 0,
+            ino: 0,
+            mode: 0o100644,
+            uid: 0,
+            gid: 0,
+            file_size: content.len() as u32,
+            id,
+            flags: 0,
+            flags_extended: 0,
+            path: b"file".to_vec(),
+        })
+    };
+
+    let mut options = MergeFileOptions::new();
+    options
+        .our_label(labels[0])
+        .ancestor_label(labels[1])
+        .their_label(labels[2])
+        .style_diff3(true)
+        .marker_size(marker_size as u16);
+
+    let merged = repo
+        .merge_file_from_index(
+            &entry(base.unwrap_or_default())?,
+            &entry(ours)?,
+            &entry(theirs)?,
+            Some(&mut options),
+        )
+        .context("Failed to merge file")?;
+
+    String::from_utf8(merged.content().to_vec()).context("Merged content is not valid UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::DEFAULT_MARKER_SIZE;
+
+    #[test]
+    fn test_commit_options_args() {
+        assert!(CommitOptions::default().args().is_empty());
+        let options = CommitOptions {
+            sign_off: true,
+            no_verify: true,
+        };
+        assert_eq!(options.args(), ["--signoff", "--no-verify"]);
+    }
+
+    #[test]
+    fn test_merge_file_conflicting_sides() {
+        let merged = merge_file(
+            b"a\nours\nc\n",
+            Some(b"a\nb\nc\n"),
+            b"a\ntheirs\nc\n",
+            ["ours", "base", "theirs"],
+            DEFAULT_MARKER_SIZE,
+        )
+        .unwrap();
+
+        assert!(merged.contains("<<<<<<< ours"));
+        assert!(merged.contains("||||||| base"));
+        assert!(merged.contains(">>>>>>> theirs"));
+    }
+
+    #[test]
+    fn test_merge_file_clean_merge() {
+        let merged = merge_file(
+            b"ours\nb\nc\n",
+            Some(b"a\nb\nc\n"),
+            b"a\nb\ntheirs\n",
+            ["ours", "base", "theirs"],
+            DEFAULT_MARKER_SIZE,
+        )
+        .unwrap();
+
+        assert_eq!(merged, "ours\nb\ntheirs\n");
+    }
+
+    #[test]
+    fn test_merge_file_marker_size() {
+        let merged =
+            merge_file(b"ours\n", Some(b"base\n"), b"theirs\n", ["a", "b", "c"], 9).unwrap();
+
+        assert!(merged.starts_with("<<<<<<<<< a\n"));
+        assert!(merged.contains("\n=========\n"));
+    }
+}
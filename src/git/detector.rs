@@ -1,10 +1,23 @@
 use anyhow::{Context, Result};
 use git2::{Repository, Status, StatusOptions};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::domain::GitOperation;
 
+/// Blob contents of the index stages recorded for a conflicted path
+#[derive(Debug, Clone, Default)]
+pub struct ConflictStages {
+    /// Stage 1: common ancestor
+    pub base: Option<Vec<u8>>,
+    /// Stage 2: our side (HEAD)
+    pub ours: Option<Vec<u8>>,
+    /// Stage 3: their side (incoming)
+    pub theirs: Option<Vec<u8>>,
+}
+
 /// Detect the current git operation (merge, rebase, or interactive rebase)
+///
+/// Returns `GitOperation::None` when nothing is in progress.
 pub fn detect_git_operation(repo: &Repository) -> Result<GitOperation> {
     let git_dir = repo.path();
 
@@ -22,7 +35,7 @@ pub fn detect_git_operation(repo: &Repository) -> Result<GitOperation> {
         return Ok(GitOperation::Merge);
     }
 
-    anyhow::bail!("No merge or rebase operation in progress")
+    Ok(GitOperation::None)
 }
 
 /// Find all files with conflicts in the repository
@@ -52,11 +65,59 @@ pub fn find_conflicted_files(repo: &Repository) -> Result<Vec<PathBuf>> {
         }
     }
 
-    if conflicted_files.is_empty() {
-        anyhow::bail!("No conflicted files found");
+    Ok(conflicted_files)
+}
+
+/// Convert a working tree path into the repository-relative path used by the index
+pub fn repo_relative_path(repo: &Repository, path: &Path) -> Result<PathBuf> {
+    let workdir = repo
+        .workdir()
+        .context("Repository has no working directory")?;
+
+    if let Ok(relative) = path.strip_prefix(workdir) {
+        return Ok(relative.to_path_buf());
     }
 
-    Ok(conflicted_files)
+    // Canonicalized user input may not share the workdir's prefix (symlinked temp dirs)
+    let canonical_workdir = workdir
+        .canonicalize()
+        .context("Failed to resolve repository working directory")?;
+    let canonical_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+
+    canonical_path
+        .strip_prefix(&canonical_workdir)
+        .map(Path::to_path_buf)
+        .with_context(|| format!("{} is outside the repository", path.display()))
+}
+
+/// Read the base/ours/theirs blobs recorded in the index for a conflicted file
+pub fn read_conflict_stages(repo: &Repository, path: &Path) -> Result<ConflictStages> {
+    let relative = repo_relative_path(repo, path)?;
+    let index = repo.index().context("Failed to read repository index")?;
+
+    let read_stage = |stage: i32| -> Result<Option<Vec<u8>>> {
+        match index.get_path(&relative, stage) {
+            Some(entry) => {
+                let blob = repo
+                    .find_blob(entry.id)
+                    .with_context(|| format!("Failed to read stage {} blob", stage))?;
+                Ok(Some(blob.content().to_vec()))
+            }
+            None => Ok(None),
+        }
+    };
+
+    let stages = ConflictStages {
+        base: read_stage(1)?,
+        ours: read_stage(2)?,
+        theirs: read_stage(3)?,
+    };
+
+    if stages.base.is_none() && stages.ours.is_none() && stages.theirs.is_none() {
+        anyhow::bail!("{} has no conflict stages in the index", relative.display());
+    }
+
+    Ok(stages)
 }
 
 /// Open the git repository in the current directory or parent directories
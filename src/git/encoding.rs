@@ -0,0 +1,172 @@
+use anyhow::{Context, Result};
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, WINDOWS_1252};
+
+/// Byte order marks that identify UTF-16 files without an attribute
+const UTF_16LE_BOM: [u8; 2] = [0xff, 0xfe];
+const UTF_16BE_BOM: [u8; 2] = [0xfe, 0xff];
+
+/// Look up an encoding by label; `None` means UTF-8
+fn lookup(label: &str) -> Result<Option<&'static Encoding>> {
+    let encoding = Encoding::for_label(label.trim().as_bytes())
+        .with_context(|| format!("Unknown encoding: {}", label))?;
+
+    if encoding == encoding_rs::UTF_8 {
+        Ok(None)
+    } else {
+        Ok(Some(encoding))
+    }
+}
+
+/// Pick the encoding of file content, preferring the declared one (`working-tree-encoding`)
+///
+/// Without a declaration, a UTF-16 byte order mark wins, then UTF-8, and
+/// anything else is read as windows-1252 (a superset of Latin-1), which maps
+/// every byte. Returns the encoding's name, or `None` for UTF-8.
+pub fn detect_encoding(content: &[u8], declared: Option<&str>) -> Result<Option<&'static str>> {
+    let encoding = match declared {
+        Some(label) => match lookup(label)? {
+            // A plain `UTF-16` declaration leaves the byte order to the BOM
+            Some(encoding) if encoding == UTF_16LE || encoding == UTF_16BE => {
+                Some(utf_16_by_bom(content).unwrap_or(encoding))
+            }
+            encoding => encoding,
+        },
+        None => match utf_16_by_bom(content) {
+            Some(encoding) => Some(encoding),
+            None if std::str::from_utf8(content).is_ok() => None,
+            None => Some(WINDOWS_1252),
+        },
+    };
+
+    Ok(encoding.map(Encoding::name))
+}
+
+fn utf_16_by_bom(content: &[u8]) -> Option<&'static Encoding> {
+    if content.starts_with(&UTF_16LE_BOM) {
+        Some(UTF_16LE)
+    } else if content.starts_with(&UTF_16BE_BOM) {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+/// Decode content in the named encoding (`None` for UTF-8)
+///
+/// Fails unless encoding the text again gives back exactly the same bytes, so
+/// regions nobody touches are written back unchanged. A BOM is kept as U+FEFF.
+pub fn decode(content: &[u8], encoding: Option<&str>) -> Result<String> {
+    let name = match encoding {
+        Some(name) => name,
+        None => {
+            return String::from_utf8(content.to_vec()).context("Content is not valid UTF-8");
+        }
+    };
+    let text = match lookup(name)? {
+        Some(encoding) => encoding.decode_without_bom_handling(content).0.into_owned(),
+        None => return decode(content, None),
+    };
+
+    if encode(&text, Some(name))? != content {
+        anyhow::bail!("Content cannot be read as {} without losing bytes", name);
+    }
+    Ok(text)
+}
+
+/// Encode text for writing in the named encoding (`None` for UTF-8)
+pub fn encode(text: &str, encoding: Option<&str>) -> Result<Vec<u8>> {
+    let encoding = match encoding {
+        Some(name) => match lookup(name)? {
+            Some(encoding) => encoding,
+            None => return Ok(text.as_bytes().to_vec()),
+        },
+        None => return Ok(text.as_bytes().to_vec()),
+    };
+
+    // encoding_rs only decodes UTF-16, so encode it by hand
+    if encoding == UTF_16LE || encoding == UTF_16BE {
+        let little_endian = encoding == UTF_16LE;
+        return Ok(text
+            .encode_utf16()
+            .flat_map(|unit| {
+                if little_endian {
+                    unit.to_le_bytes()
+                } else {
+                    unit.to_be_bytes()
+                }
+            })
+            .collect());
+    }
+
+    let (bytes, _, unmappable) = encoding.encode(text);
+    if unmappable {
+        anyhow::bail!(
+            "Text contains characters {} cannot represent",
+            encoding.name()
+        );
+    }
+    Ok(bytes.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_encoding() {
+        assert_eq!(detect_encoding("naïve ✓".as_bytes(), None).unwrap(), None);
+        assert_eq!(
+            detect_encoding(b"caf\xe9\n", None).unwrap(),
+            Some("windows-1252")
+        );
+        assert_eq!(
+            detect_encoding(b"\xff\xfea\0", None).unwrap(),
+            Some("UTF-16LE")
+        );
+        assert_eq!(
+            detect_encoding(b"\xfe\xff\0a", Some("UTF-16")).unwrap(),
+            Some("UTF-16BE")
+        );
+        assert_eq!(
+            detect_encoding(b"\x93\xfa", Some("Shift_JIS")).unwrap(),
+            Some("Shift_JIS")
+        );
+        assert_eq!(detect_encoding(b"abc", Some("utf-8")).unwrap(), None);
+        assert!(detect_encoding(b"abc", Some("klingon")).is_err());
+    }
+
+    #[test]
+    fn test_latin1_round_trip() {
+        let content = b"caf\xe9 cr\xe8me\r\n";
+        let text = decode(content, Some("windows-1252")).unwrap();
+
+        assert_eq!(text, "café crème\r\n");
+        assert_eq!(encode(&text, Some("windows-1252")).unwrap(), content);
+    }
+
+    #[test]
+    fn test_shift_jis_round_trip() {
+        let content = encode("日本語\n", Some("Shift_JIS")).unwrap();
+        assert_eq!(&content[..2], b"\x93\xfa");
+
+        assert_eq!(decode(&content, Some("Shift_JIS")).unwrap(), "日本語\n");
+        assert!(encode("😀", Some("Shift_JIS")).is_err());
+    }
+
+    #[test]
+    fn test_utf_16_keeps_bom() {
+        let content = b"\xff\xfea\0\n\0";
+        let text = decode(content, Some("UTF-16LE")).unwrap();
+
+        assert_eq!(text, "\u{feff}a\n");
+        assert_eq!(encode(&text, Some("UTF-16LE")).unwrap(), content);
+        assert_eq!(encode("a", Some("UTF-16BE")).unwrap(), b"\0a");
+    }
+
+    #[test]
+    fn test_decode_rejects_lossy_content() {
+        assert!(decode(b"\xff", None).is_err());
+        // A lone trailing byte is not a UTF-16 code unit
+        assert!(decode(b"\xff\xfea", Some("UTF-16LE")).is_err());
+    }
+}
@@ -0,0 +1,687 @@
+use anyhow::{Context, Result};
+use git2::{Commit, ObjectType, Oid, Repository};
+use std::path::{Path, PathBuf};
+
+use super::attributes::{encoding_for, marker_size_for};
+use super::detector::{read_conflict_stages, repo_relative_path};
+use super::parser::{decode_conflict_stages, parse_conflicts, parse_conflicts_from_index};
+use crate::domain::{BlobInfo, ConflictedFile, FileConflictKind};
+
+/// How many commits to walk back when looking up the last commit of a binary blob
+const HISTORY_SEARCH_LIMIT: usize = 500;
+
+/// Share of lines a destination must keep from the ancestor to pair as its rename,
+/// like git's default rename threshold
+const RENAME_SIMILARITY: f64 = 0.5;
+
+/// Refs pointing at the incoming side, depending on the operation in progress
+const INCOMING_HEADS: [&str; 4] = [
+    "MERGE_HEAD",
+    "REBASE_HEAD",
+    "CHERRY_PICK_HEAD",
+    "REVERT_HEAD",
+];
+
+/// A rename/rename conflict: one base path renamed differently on each side
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenameConflict {
+    /// Repository-relative path in the common ancestor
+    pub original: PathBuf,
+    /// Repository-relative path on our side (HEAD)
+    pub ours: PathBuf,
+    /// Repository-relative path on their side (incoming)
+    pub theirs: PathBuf,
+}
+
+/// Find rename/rename conflicts recorded in the index
+///
+/// Git records these as the original path with only a stage 1 entry and each
+/// destination with only a stage 2 or stage 3 entry. Destinations whose blob
+/// still matches the ancestor are paired first, then the most similar ones;
+/// an ancestor without a similar enough destination on each side is skipped.
+pub fn find_rename_conflicts(repo: &Repository) -> Result<Vec<RenameConflict>> {
+    let index = repo.index().context("Failed to read repository index")?;
+
+    let mut base_only: Vec<(Oid, PathBuf)> = Vec::new();
+    let mut ours_only: Vec<(Oid, PathBuf)> = Vec::new();
+    let mut theirs_only: Vec<(Oid, PathBuf)> = Vec::new();
+
+    for conflict in index
+        .conflicts()
+        .context("Failed to read index conflicts")?
+    {
+        let conflict = conflict.context("Failed to read index conflict")?;
+        let entry_path = |path: &[u8]| PathBuf::from(String::from_utf8_lossy(path).as_ref());
+
+        match (&conflict.ancestor, &conflict.our, &conflict.their) {
+            (Some(base), None, None) => base_only.push((base.id, entry_path(&base.path))),
+            (None, Some(ours), None) => ours_only.push((ours.id, entry_path(&ours.path))),
+            (None, None, Some(theirs)) => theirs_only.push((theirs.id, entry_path(&theirs.path))),
+            _ => {}
+        }
+    }
+
+    let mut renames = Vec::new();
+    for (base_id, original) in base_only {
+        let ours = take_best_match(repo, &mut ours_only, base_id)?;
+        let theirs = take_best_match(repo, &mut theirs_only, base_id)?;
+
+        if let (Some(ours), Some(theirs)) = (ours, theirs) {
+            renames.push(RenameConflict {
+                original,
+                ours,
+                theirs,
+            });
+        }
+    }
+
+    Ok(renames)
+}
+
+/// Take the candidate renamed from the ancestor blob: an unchanged copy, or the
+/// most similar one at or above `RENAME_SIMILARITY`
+fn take_best_match(
+    repo: &Repository,
+    candidates: &mut Vec<(Oid, PathBuf)>,
+    base_id: Oid,
+) -> Result<Option<PathBuf>> {
+    let mut best: Option<(usize, f64)> = None;
+    for (position, (id, _)) in candidates.iter().enumerate() {
+        if *id == base_id {
+            best = Some((position, 1.0));
+            break;
+        }
+        let similarity = blob_similarity(repo, base_id, *id)?;
+        let better = best.is_none_or(|(_, best)| similarity > best);
+        if similarity >= RENAME_SIMILARITY && better {
+            best = Some((position, similarity));
+        }
+    }
+    Ok(best.map(|(position, _)| candidates.remove(position).1))
+}
+
+/// Share of lines two blobs have in common, from 0.0 to 1.0 (binary blobs share none)
+fn blob_similarity(repo: &Repository, old: Oid, new: Oid) -> Result<f64> {
+    let old = repo
+        .find_blob(old)
+        .context("Failed to read ancestor blob")?;
+    let new = repo.find_blob(new).context("Failed to read renamed blob")?;
+    if old.is_binary() || new.is_binary() {
+        return Ok(0.0);
+    }
+
+    let patch = git2::Patch::from_blobs(&old, None, &new, None, None)
+        .context("Failed to compare renamed blobs")?;
+    let (_, _, deletions) = patch.line_stats()?;
+    let line_count = |content: &[u8]| content.split_inclusive(|&b| b == b'\n').count();
+    let total = line_count(old.content()) + line_count(new.content());
+    if total == 0 {
+        return Ok(1.0);
+    }
+    let kept = line_count(old.content()).saturating_sub(deletions);
+    Ok((2 * kept) as f64 / total as f64)
+}
+
+/// Load a single conflicted path, classifying whole-file conflicts
+pub fn load_conflicted_file(repo: &Repository, path: &Path) -> Result<ConflictedFile> {
+    let marker_size = marker_size_for(repo, path);
+    let encoding = encoding_for(repo, path);
+    let encoding = encoding.as_deref();
+
+    // Paths without index stages (e.g. given explicitly) can only be parsed from markers
+    let stages = match read_conflict_stages(repo, path) {
+        Ok(stages) => stages,
+        Err(_) => return parse_conflicts(path, marker_size, encoding),
+    };
+
+    match (&stages.ours, &stages.theirs) {
+        (None, Some(_)) => {
+            return Ok(ConflictedFile::new_file_conflict(
+                path.to_path_buf(),
+                FileConflictKind::DeletedByUs,
+            ))
+        }
+        (Some(_), None) => {
+            return Ok(ConflictedFile::new_file_conflict(
+                path.to_path_buf(),
+                FileConflictKind::DeletedByThem,
+            ))
+        }
+        _ => {}
+    }
+
+    if let (Some(ours), Some(theirs)) = (&stages.ours, &stages.theirs) {
+        if is_binary(ours) || is_binary(theirs) {
+            let relative = repo_relative_path(repo, path)?;
+            return Ok(ConflictedFile::new_file_conflict(
+                path.to_path_buf(),
+                FileConflictKind::Binary {
+                    ours: blob_info(repo, &["HEAD"], &relative, ours)?,
+                    theirs: blob_info(repo, &INCOMING_HEADS, &relative, theirs)?,
+                },
+            ));
+        }
+    }
+
+    // Markers that are gone, broken or edited by hand are rebuilt from the index
+    let from_markers = parse_conflicts(path, marker_size, encoding)
+        .ok()
+        .filter(|file| markers_match_index(repo, path, file, encoding));
+    if let Some(file) = from_markers {
+        return Ok(file);
+    }
+    let worktree_content = std::fs::read(path).ok();
+    parse_conflicts_from_index(repo, path, marker_size, encoding)
+        .map(|file| file.with_worktree_content(worktree_content))
+        .or_else(|e| {
+            // add/add with nothing to merge hunk by hunk: pick one side wholesale
+            if stages.base.is_none() {
+                Ok(ConflictedFile::new_file_conflict(
+                    path.to_path_buf(),
+                    FileConflictKind::BothAdded,
+                ))
+            } else {
+                Err(e)
+            }
+        })
+}
+
+/// Check that the hunks' sides appear in the index stages, as whole lines and in order
+///
+/// Line endings are ignored, as the worktree may use CRLF where the blobs don't;
+/// an empty side can only be checked by its place among the other hunks.
+/// Without readable stages there is nothing to compare with, so the markers stand.
+fn markers_match_index(
+    repo: &Repository,
+    path: &Path,
+    file: &ConflictedFile,
+    encoding: Option<&str>,
+) -> bool {
+    let stages = match decode_conflict_stages(repo, path, encoding) {
+        Ok(stages) => stages,
+        Err(_) => return true,
+    };
+    let lines = |text: &str| -> Vec<String> {
+        text.lines()
+            .map(|line| line.trim_end_matches('\r').to_string())
+            .collect()
+    };
+    let (ours, theirs) = (lines(&stages.ours), lines(&stages.theirs));
+    let (mut ours_from, mut theirs_from) = (0, 0);
+    file.conflicts.iter().all(|hunk| {
+        let found = (
+            find_lines(&ours, &lines(&hunk.current), ours_from),
+            find_lines(&theirs, &lines(&hunk.incoming), theirs_from),
+        );
+        match found {
+            (Some(ours_end), Some(theirs_end)) => {
+                (ours_from, theirs_from) = (ours_end, theirs_end);
+                true
+            }
+            _ => false,
+        }
+    })
+}
+
+/// Find `needle` as consecutive lines of `haystack` starting at or after `from`,
+/// returning the index just past the match
+fn find_lines(haystack: &[String], needle: &[String], from: usize) -> Option<usize> {
+    let last_start = haystack.len().checked_sub(needle.len())?;
+    (from..=last_start)
+        .find(|&start| haystack[start..start + needle.len()] == *needle)
+        .map(|start| start + needle.len())
+}
+
+/// Check if content should be treated as binary (NUL bytes, like git)
+///
+/// UTF-16 text has NULs too, so a byte order mark marks it as text.
+pub fn is_binary(content: &[u8]) -> bool {
+    let is_utf_16 = content.starts_with(&[0xff, 0xfe]) || content.starts_with(&[0xfe, 0xff]);
+    !is_utf_16 && content.iter().take(8000).any(|&b| b == 0)
+}
+
+/// Build the metadata shown for one side of a binary conflict
+fn blob_info(
+    repo: &Repository,
+    start_refs: &[&str],
+    relative: &Path,
+    content: &[u8],
+) -> Result<BlobInfo> {
+    let id = Oid::hash_object(ObjectType::Blob, content).context("Failed to hash blob")?;
+    let last_commit = start_refs
+        .iter()
+        .find_map(|start| last_commit_for_blob(repo, start, relative, id));
+
+    Ok(BlobInfo {
+        id: id.to_string(),
+        size: content.len(),
+        last_commit,
+    })
+}
+
+/// Find the most recent commit reachable from `start` that introduced `blob_id` at `relative`
+fn last_commit_for_blob(
+    repo: &Repository,
+    start: &str,
+    relative: &Path,
+    blob_id: Oid,
+) -> Option<String> {
+    let start = repo.revparse_single(start).ok()?.peel_to_commit().ok()?;
+    let mut revwalk = repo.revwalk().ok()?;
+    revwalk.push(start.id()).ok()?;
+
+    let blob_at = |commit: &Commit| -> Option<Oid> {
+        commit
+            .tree()
+            .ok()?
+            .get_path(relative)
+            .ok()
+            .map(|entry| entry.id())
+    };
+
+    for oid in revwalk.take(HISTORY_SEARCH_LIMIT) {
+        let commit = repo.find_commit(oid.ok()?).ok()?;
+        if blob_at(&commit) != Some(blob_id) {
+            continue;
+        }
+
+        let introduced = commit
+            .parents()
+            .all(|parent| blob_at(&parent) != Some(blob_id));
+        if introduced {
+            let id = commit.id().to_string();
+            return Some(format!(
+                "{} {}",
+                &id[..7],
+                commit.summary().unwrap_or_default()
+            ));
+        }
+    }
+
+    None
+}
+
+/// Load every conflicted path, pairing rename/rename conflicts into a single entry
+///
+/// Returns one result per conflict so a single unreadable file doesn't hide the rest.
+pub fn load_conflicted_files(repo: &Repository, paths: &[PathBuf]) -> Vec<Result<ConflictedFile>> {
+    let workdir = match repo.workdir() {
+        Some(workdir) => workdir,
+        None => return vec![Err(anyhow::anyhow!("Repository has no working directory"))],
+    };
+
+    let relative_paths: Vec<Option<PathBuf>> = paths
+        .iter()
+        .map(|path| repo_relative_path(repo, path).ok())
+        .collect();
+
+    let mut results = Vec::new();
+    let mut covered: Vec<PathBuf> = Vec::new();
+
+    for rename in find_rename_conflicts(repo).unwrap_or_default() {
+        let involved = [&rename.original, &rename.ours, &rename.theirs];
+        let requested = relative_paths
+            .iter()
+            .flatten()
+            .any(|relative| involved.contains(&relative));
+
+        if requested {
+            covered.extend(involved.into_iter().cloned());
+            results.push(Ok(ConflictedFile::new_file_conflict(
+                workdir.join(&rename.original),
+                FileConflictKind::RenamedDifferently {
+                    ours: rename.ours,
+                    theirs: rename.theirs,
+                },
+            )));
+        }
+    }
+
+    for (path, relative) in paths.iter().zip(relative_paths) {
+        if relative.is_some_and(|relative| covered.contains(&relative)) {
+            continue;
+        }
+        results.push(
+            load_conflicted_file(repo, path)
+                .with_context(|| format!("Failed to load {}", path.display())),
+        );
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{FileResolution, Resolution};
+    use crate::git::{
+        apply_file_resolution, apply_resolutions, restore_file_conflict, restore_original_content,
+        snapshot_file_conflict,
+    };
+    use crate::test_support::{conflicted_repo, git, whole_file_conflict_repo};
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_honors_conflict_marker_size() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path();
+        git(path, &["init", "-q", "-b", "main"]);
+        fs::write(
+            path.join(".gitattributes"),
+            "*.adoc conflict-marker-size=10\n",
+        )
+        .unwrap();
+        fs::write(path.join("doc.adoc"), "Title\n=======\nbase\n").unwrap();
+        git(path, &["add", "."]);
+        git(path, &["commit", "-q", "-m", "base"]);
+        git(path, &["checkout", "-q", "-b", "feature"]);
+        fs::write(path.join("doc.adoc"), "Title\n=======\ntheirs\n").unwrap();
+        git(path, &["commit", "-q", "-am", "theirs"]);
+        git(path, &["checkout", "-q", "main"]);
+        fs::write(path.join("doc.adoc"), "Title\n=======\nours\n").unwrap();
+        git(path, &["commit", "-q", "-am", "ours"]);
+        git(path, &["merge", "-q", "feature"]);
+
+        let repo = Repository::open(path).unwrap();
+        let file = load_conflicted_file(&repo, &path.join("doc.adoc")).unwrap();
+
+        assert_eq!(file.conflicts.len(), 1);
+        assert_eq!(file.conflicts[0].current, "ours");
+        assert_eq!(file.conflicts[0].incoming, "theirs");
+        assert_eq!(file.conflicts[0].incoming_label.as_deref(), Some("feature"));
+    }
+
+    #[test]
+    fn test_load_rebuilds_hand_edited_markers() {
+        let dir = conflicted_repo("merge", "a\nbase\nc\n", "a\nours\nc\n", "a\ntheirs\nc\n");
+        let path = dir.path().join("file.txt");
+        let content = fs::read_to_string(&path).unwrap();
+        // A side cut down to part of a line still shows up as a substring of the stage
+        let edited = content.replace("\nours\n", "\nour\n");
+        fs::write(&path, &edited).unwrap();
+
+        let repo = Repository::open(dir.path()).unwrap();
+        let mut file = load_conflicted_file(&repo, &path).unwrap();
+        assert_eq!(file.conflicts.len(), 1);
+        assert_eq!(file.conflicts[0].current, "ours");
+        assert_eq!(file.conflicts[0].base.as_deref(), Some("base"));
+        assert!(file.is_rebuilt_from_index());
+
+        // Undo after writing brings the hand edits back, not libgit2's merge
+        file.set_resolution(0, Resolution::Incoming);
+        apply_resolutions(&file).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "a\ntheirs\nc\n");
+        restore_original_content(&file).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), edited);
+    }
+
+    #[test]
+    fn test_load_keeps_markers_matching_index() {
+        let dir = conflicted_repo("merge", "a\nbase\nc\n", "a\nours\nc\n", "a\ntheirs\nc\n");
+        let path = dir.path().join("file.txt");
+
+        let repo = Repository::open(dir.path()).unwrap();
+        let file = load_conflicted_file(&repo, &path).unwrap();
+        assert_eq!(file.conflicts.len(), 1);
+        assert!(!file.is_rebuilt_from_index());
+    }
+
+    /// Repository where `image.bin` was changed on both sides
+    fn binary_conflict_repo() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path();
+        git(path, &["init", "-q", "-b", "main"]);
+        fs::write(path.join("image.bin"), b"\x89PNG\0base").unwrap();
+        git(path, &["add", "."]);
+        git(path, &["commit", "-q", "-m", "base"]);
+        git(path, &["checkout", "-q", "-b", "feature"]);
+        fs::write(path.join("image.bin"), b"\x89PNG\0theirs").unwrap();
+        git(path, &["commit", "-q", "-am", "their image"]);
+        git(path, &["checkout", "-q", "main"]);
+        fs::write(path.join("image.bin"), b"\x89PNG\0ours!").unwrap();
+        git(path, &["commit", "-q", "-am", "our image"]);
+        git(path, &["merge", "-q", "feature"]);
+        dir
+    }
+
+    #[test]
+    fn test_is_binary() {
+        assert!(is_binary(b"\x89PNG\0data"));
+        assert!(!is_binary("plain text ✓".as_bytes()));
+        assert!(!is_binary(b"caf\xe9 latin-1"));
+        assert!(!is_binary(b"\xff\xfea\0"));
+    }
+
+    #[test]
+    fn test_load_binary_conflict() {
+        let dir = binary_conflict_repo();
+        let repo = Repository::open(dir.path()).unwrap();
+        let path = repo.workdir().unwrap().join("image.bin");
+
+        let file = load_conflicted_file(&repo, &path).unwrap();
+        match file.file_conflict {
+            Some(FileConflictKind::Binary { ours, theirs }) => {
+                assert_eq!(ours.size, 10);
+                assert_eq!(theirs.size, 11);
+                assert!(ours.last_commit.unwrap().ends_with("our image"));
+                assert!(theirs.last_commit.unwrap().ends_with("their image"));
+            }
+            other => panic!("expected binary conflict, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_apply_binary_resolution() {
+        let dir = binary_conflict_repo();
+        let repo = Repository::open(dir.path()).unwrap();
+        let path = repo.workdir().unwrap().join("image.bin");
+
+        let mut file = load_conflicted_file(&repo, &path).unwrap();
+        file.set_file_resolution(FileResolution::KeepTheirs);
+        apply_file_resolution(&repo, &file, true).unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"\x89PNG\0theirs");
+        let index = Repository::open(dir.path()).unwrap().index().unwrap();
+        assert!(!index.has_conflicts());
+    }
+
+    #[test]
+    fn test_find_rename_conflicts() {
+        let dir = whole_file_conflict_repo();
+        let repo = Repository::open(dir.path()).unwrap();
+
+        let renames = find_rename_conflicts(&repo).unwrap();
+        assert_eq!(
+            renames,
+            vec![RenameConflict {
+                original: PathBuf::from("orig.txt"),
+                ours: PathBuf::from("ours.txt"),
+                theirs: PathBuf::from("theirs.txt"),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_rename_match_needs_similar_content() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let base = repo.blob(b"one\ntwo\nthree\nfour\n").unwrap();
+        let edited = repo.blob(b"one\ntwo\nthree\nfive\n").unwrap();
+        let unrelated = repo.blob(b"something\nelse\n").unwrap();
+
+        let mut candidates = vec![
+            (unrelated, PathBuf::from("unrelated.txt")),
+            (edited, PathBuf::from("edited.txt")),
+        ];
+        assert_eq!(
+            take_best_match(&repo, &mut candidates, base).unwrap(),
+            Some(PathBuf::from("edited.txt"))
+        );
+        // Only the unrelated file is left, which is no rename of the ancestor
+        assert_eq!(take_best_match(&repo, &mut candidates, base).unwrap(), None);
+        assert_eq!(candidates.len(), 1);
+    }
+
+    #[test]
+    fn test_load_conflicted_files_classifies_whole_file_conflicts() {
+        let dir = whole_file_conflict_repo();
+        let repo = Repository::open(dir.path()).unwrap();
+        let workdir = repo.workdir().unwrap().to_path_buf();
+
+        let paths: Vec<PathBuf> = ["del.txt", "orig.txt", "ours.txt", "theirs.txt"]
+            .iter()
+            .map(|p| workdir.join(p))
+            .collect();
+        let files: Vec<ConflictedFile> = load_conflicted_files(&repo, &paths)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+
+        assert_eq!(files.len(), 2);
+        assert!(matches!(
+            files[0].file_conflict,
+            Some(FileConflictKind::RenamedDifferently { .. })
+        ));
+        assert_eq!(files[1].file_conflict, Some(FileConflictKind::DeletedByUs));
+    }
+
+    #[test]
+    fn test_apply_delete_resolution() {
+        let dir = whole_file_conflict_repo();
+        let repo = Repository::open(dir.path()).unwrap();
+        let path = repo.workdir().unwrap().join("del.txt");
+
+        let mut file = load_conflicted_file(&repo, &path).unwrap();
+        file.set_file_resolution(FileResolution::Delete);
+
+        apply_file_resolution(&repo, &file, true).unwrap();
+
+        assert!(!path.exists());
+        let index = Repository::open(dir.path()).unwrap().index().unwrap();
+        for stage in 0..=3 {
+            assert!(index.get_path(Path::new("del.txt"), stage).is_none());
+        }
+    }
+
+    #[test]
+    fn test_restore_applied_file_conflict() {
+        let dir = whole_file_conflict_repo();
+        let repo = Repository::open(dir.path()).unwrap();
+        let path = repo.workdir().unwrap().join("del.txt");
+
+        let mut file = load_conflicted_file(&repo, &path).unwrap();
+        let snapshot = snapshot_file_conflict(&repo, &file).unwrap();
+        file.set_file_resolution(FileResolution::Delete);
+        apply_file_resolution(&repo, &file, true).unwrap();
+        assert!(!path.exists());
+
+        restore_file_conflict(&repo, &snapshot).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "x\ny\n");
+        let index = Repository::open(dir.path()).unwrap().index().unwrap();
+        assert!(index.get_path(Path::new("del.txt"), 0).is_none());
+        assert!(index.get_path(Path::new("del.txt"), 1).is_some());
+        assert!(index.get_path(Path::new("del.txt"), 3).is_some());
+
+        // The conflict can be resolved again, the other way
+        file.set_file_resolution(FileResolution::KeepTheirs);
+        apply_file_resolution(&repo, &file, true).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "x\ny\n");
+    }
+
+    /// Repository where `name` was deleted on `main` and changed (and made
+    /// executable) on `feature`, with `*.txt` checked out with CRLF endings
+    #[cfg(unix)]
+    fn deleted_by_us_repo(name: &std::ffi::OsStr) -> TempDir {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path();
+        git(path, &["init", "-q", "-b", "main"]);
+        fs::write(path.join(".gitattributes"), "*.txt eol=crlf\n").unwrap();
+        fs::write(path.join(name), "x\r\n").unwrap();
+        git(path, &["add", "."]);
+        git(path, &["commit", "-q", "-m", "base"]);
+        git(path, &["checkout", "-q", "-b", "feature"]);
+        fs::write(path.join(name), "x\r\ny\r\n").unwrap();
+        fs::set_permissions(path.join(name), fs::Permissions::from_mode(0o755)).unwrap();
+        git(path, &["commit", "-q", "-am", "theirs"]);
+        git(path, &["checkout", "-q", "main"]);
+        fs::remove_file(path.join(name)).unwrap();
+        git(path, &["commit", "-q", "-am", "ours"]);
+        git(path, &["merge", "-q", "feature"]);
+        dir
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_kept_stage_is_checked_out_with_filters_and_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = deleted_by_us_repo("run.txt".as_ref());
+        let repo = Repository::open(dir.path()).unwrap();
+        let path = repo.workdir().unwrap().join("run.txt");
+        fs::remove_file(&path).unwrap();
+
+        let mut file = load_conflicted_file(&repo, &path).unwrap();
+        file.set_file_resolution(FileResolution::KeepTheirs);
+        apply_file_resolution(&repo, &file, false).unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"x\r\ny\r\n");
+        assert_ne!(fs::metadata(&path).unwrap().permissions().mode() & 0o111, 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_restore_file_conflict_keeps_non_utf8_path() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let name = std::ffi::OsStr::from_bytes(b"caf\xe9.txt");
+        let dir = deleted_by_us_repo(name);
+        let repo = Repository::open(dir.path()).unwrap();
+        let path = repo.workdir().unwrap().join(name);
+
+        let mut file = load_conflicted_file(&repo, &path).unwrap();
+        let snapshot = snapshot_file_conflict(&repo, &file).unwrap();
+        file.set_file_resolution(FileResolution::Delete);
+        apply_file_resolution(&repo, &file, true).unwrap();
+        restore_file_conflict(&repo, &snapshot).unwrap();
+
+        let index = Repository::open(dir.path()).unwrap().index().unwrap();
+        let paths: Vec<Vec<u8>> = index.iter().map(|entry| entry.path).collect();
+        assert_eq!(
+            paths,
+            [
+                ".gitattributes".as_bytes(),
+                name.as_bytes(),
+                name.as_bytes()
+            ]
+        );
+        assert!(index.get_path(Path::new(name), 3).is_some());
+    }
+
+    #[test]
+    fn test_apply_rename_resolution() {
+        let dir = whole_file_conflict_repo();
+        let repo = Repository::open(dir.path()).unwrap();
+        let workdir = repo.workdir().unwrap().to_path_buf();
+
+        let mut file = load_conflicted_files(&repo, &[workdir.join("orig.txt")])
+            .remove(0)
+            .unwrap();
+        file.set_file_resolution(FileResolution::KeepTheirs);
+        apply_file_resolution(&repo, &file, true).unwrap();
+
+        assert!(workdir.join("theirs.txt").exists());
+        assert!(!workdir.join("ours.txt").exists());
+
+        let index = Repository::open(dir.path()).unwrap().index().unwrap();
+        assert!(index.get_path(Path::new("theirs.txt"), 0).is_some());
+        for path in ["orig.txt", "ours.txt"] {
+            for stage in 0..=3 {
+                assert!(index.get_path(Path::new(path), stage).is_none());
+            }
+        }
+        assert!(index.get_path(Path::new("theirs.txt"), 3).is_none());
+    }
+}
@@ -1,14 +1,27 @@
 pub mod applier;
+pub mod attributes;
 pub mod commands;
 pub mod detector;
+pub mod encoding;
+pub mod loader;
 pub mod parser;
 pub mod status;
 
-pub use applier::apply_resolutions;
+pub use applier::{
+    apply_file_resolution, apply_partial_resolutions, apply_resolutions, mark_resolved,
+    restore_file_conflict, restore_original_content, snapshot_file_conflict, ConflictSnapshot,
+};
 pub use commands::{
-    abort_rebase, commit_changes, continue_rebase, get_file_diff, restore_all, restore_file,
-    skip_rebase, stage_all, stage_file, unstage_all, unstage_file,
+    abort_rebase, commit_changes, continue_rebase, get_file_diff, merge_file, restore_all,
+    restore_file, skip_rebase, stage_all, stage_file, unstage_all, unstage_file, CommitOptions,
+};
+pub use detector::{
+    detect_git_operation, find_conflicted_files, read_conflict_stages, ConflictStages,
+};
+pub use loader::{load_conflicted_file, load_conflicted_files, RenameConflict};
+pub use parser::{
+    decode_conflict_stages, find_leftover_markers, is_conflict_marker_line, parse_conflict_content,
+    parse_conflicts, parse_conflicts_from_index, DecodedStages, LeftoverMarker,
+    DEFAULT_MARKER_SIZE,
 };
-pub use detector::{detect_git_operation, find_conflicted_files};
-pub use parser::parse_conflicts;
 pub use status::{get_repository_status, FileStatus, FileStatusType, StatusChange};
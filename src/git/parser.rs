@@ -1,19 +1,213 @@
 use anyhow::{Context, Result};
+use git2::Repository;
 use std::fs;
 use std::path::Path;
 
-use crate::domain::{ConflictHunk, ConflictedFile};
+use super::commands::merge_file;
+use super::detector::read_conflict_stages;
+use super::encoding::{decode, detect_encoding};
+pub use crate::domain::DEFAULT_MARKER_SIZE;
+use crate::domain::{raw_lines, ConflictHunk, ConflictedFile};
 
-const CONFLICT_START: &str = "<<<<<<<";
-const CONFLICT_SEPARATOR: &str = "=======";
-const CONFLICT_END: &str = ">>>>>>>";
+const START_MARKER: char = '<';
+const BASE_MARKER: char = '|';
+const SEPARATOR_MARKER: char = '=';
+const END_MARKER: char = '>';
 
-/// Parse conflicts from a file
-pub fn parse_conflicts(file_path: &Path) -> Result<ConflictedFile> {
-    let content = fs::read_to_string(file_path)
+/// Check if a line is a marker of exactly `size` characters, followed by a label or nothing
+fn is_sized_marker(line: &str, marker: char, size: usize) -> bool {
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    let run = line.chars().take_while(|c| *c == marker).count();
+    run == size && line[size..].chars().next().is_none_or(char::is_whitespace)
+}
+
+/// Text following a marker of `size` characters, if any
+fn marker_label(line: &str, size: usize) -> Option<String> {
+    let label = line.get(size..)?.trim();
+    if label.is_empty() {
+        None
+    } else {
+        Some(label.to_string())
+    }
+}
+
+/// Check if a line is a conflict marker of `marker_size` characters (start, base, separator or end)
+pub fn is_conflict_marker_line(line: &str, marker_size: usize) -> bool {
+    [START_MARKER, BASE_MARKER, SEPARATOR_MARKER, END_MARKER]
+        .iter()
+        .any(|marker| is_sized_marker(line, *marker, marker_size))
+}
+
+/// A conflict marker left behind in a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeftoverMarker {
+    /// A complete start ... end block (0-based lines of both markers)
+    Block { start_line: usize, end_line: usize },
+    /// A start marker without a matching end marker
+    Unterminated { line: usize },
+    /// An end marker without a start marker before it
+    Stray { line: usize },
+}
+
+impl LeftoverMarker {
+    /// Line (0-based) where the marker is reported
+    pub fn line(&self) -> usize {
+        match self {
+            LeftoverMarker::Block { start_line, .. } => *start_line,
+            LeftoverMarker::Unterminated { line } | LeftoverMarker::Stray { line } => *line,
+        }
+    }
+
+    /// Describe the marker for a report
+    pub fn description(&self) -> String {
+        match self {
+            LeftoverMarker::Block {
+                start_line,
+                end_line,
+            } => format!(
+                "conflict markers (lines {}-{})",
+                start_line + 1,
+                end_line + 1
+            ),
+            LeftoverMarker::Unterminated { .. } => "unterminated conflict marker".to_string(),
+            LeftoverMarker::Stray { .. } => "stray conflict end marker".to_string(),
+        }
+    }
+}
+
+/// Find conflict markers of the given size left in file content
+///
+/// Only start and end markers are considered, since a lone `=======` is
+/// common in markup (heading underlines).
+pub fn find_leftover_markers(content: &str, marker_size: usize) -> Vec<LeftoverMarker> {
+    let mut markers = Vec::new();
+    let mut open_block = None;
+
+    for (i, line) in content.lines().enumerate() {
+        if is_sized_marker(line, START_MARKER, marker_size) {
+            if let Some(start) = open_block.replace(i) {
+                markers.push(LeftoverMarker::Unterminated { line: start });
+            }
+        } else if is_sized_marker(line, END_MARKER, marker_size) {
+            match open_block.take() {
+                Some(start_line) => markers.push(LeftoverMarker::Block {
+                    start_line,
+                    end_line: i,
+                }),
+                None => markers.push(LeftoverMarker::Stray { line: i }),
+            }
+        }
+    }
+    if let Some(line) = open_block {
+        markers.push(LeftoverMarker::Unterminated { line });
+    }
+
+    markers
+}
+
+/// Parse conflicts from a file whose markers are `marker_size` characters long
+///
+/// The file is decoded with the `declared_encoding` (its `working-tree-encoding`)
+/// or a detected one, which is kept so the file is written back the same way.
+pub fn parse_conflicts(
+    file_path: &Path,
+    marker_size: usize,
+    declared_encoding: Option<&str>,
+) -> Result<ConflictedFile> {
+    let bytes = fs::read(file_path)
         .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
+    let encoding = detect_encoding(&bytes, declared_encoding)?;
+    let content = decode(&bytes, encoding)
+        .with_context(|| format!("Failed to decode file: {}", file_path.display()))?;
 
-    let lines: Vec<&str> = content.lines().collect();
+    Ok(parse_conflict_content(file_path, content, marker_size)?.with_encoding(encoding))
+}
+
+/// Rebuild conflicts for a file from its index stages instead of the worktree markers
+///
+/// Works even when the working file was edited or its markers were stripped,
+/// since the hunks are recomputed from the base/ours/theirs blobs.
+pub fn parse_conflicts_from_index(
+    repo: &Repository,
+    file_path: &Path,
+    marker_size: usize,
+    declared_encoding: Option<&str>,
+) -> Result<ConflictedFile> {
+    let stages = decode_conflict_stages(repo, file_path, declared_encoding)?;
+
+    let merged = merge_file(
+        stages.ours.as_bytes(),
+        stages.base.as_deref().map(str::as_bytes),
+        stages.theirs.as_bytes(),
+        ["HEAD", "base", "incoming"],
+        marker_size,
+    )
+    .with_context(|| format!("Failed to merge index stages for {}", file_path.display()))?;
+
+    Ok(parse_conflict_content(file_path, merged, marker_size)?.with_encoding(stages.encoding))
+}
+
+/// Index stages of a content conflict, decoded to text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedStages {
+    /// Encoding of the worktree file (`None` for UTF-8)
+    pub encoding: Option<&'static str>,
+    pub ours: String,
+    pub base: Option<String>,
+    pub theirs: String,
+}
+
+/// Read and decode the index stages of a content conflict
+pub fn decode_conflict_stages(
+    repo: &Repository,
+    file_path: &Path,
+    declared_encoding: Option<&str>,
+) -> Result<DecodedStages> {
+    let stages = read_conflict_stages(repo, file_path)?;
+
+    let (ours, theirs) = match (&stages.ours, &stages.theirs) {
+        (Some(ours), Some(theirs)) => (ours, theirs),
+        _ => anyhow::bail!(
+            "{} is not a content conflict (one side deleted the file)",
+            file_path.display()
+        ),
+    };
+
+    // Git stores files with a `working-tree-encoding` as UTF-8; others as they are on disk
+    let (encoding, blob_encoding) = match declared_encoding {
+        Some(label) => {
+            // The worktree copy, if still there, tells the UTF-16 byte order
+            let worktree = fs::read(file_path).unwrap_or_default();
+            (detect_encoding(&worktree, Some(label))?, None)
+        }
+        None => {
+            let encoding = detect_encoding(&[ours.as_slice(), theirs.as_slice()].concat(), None)?;
+            (encoding, encoding)
+        }
+    };
+    let decode_stage = |content: &[u8]| {
+        decode(content, blob_encoding)
+            .with_context(|| format!("Failed to decode index stage of {}", file_path.display()))
+    };
+
+    Ok(DecodedStages {
+        encoding,
+        ours: decode_stage(ours)?,
+        base: stages.base.as_deref().map(decode_stage).transpose()?,
+        theirs: decode_stage(theirs)?,
+    })
+}
+
+/// Parse conflict markers out of already-loaded file content
+///
+/// Only markers of exactly `marker_size` characters count, so longer runs such
+/// as `========` heading underlines inside a hunk are kept as content.
+pub fn parse_conflict_content(
+    file_path: &Path,
+    content: String,
+    marker_size: usize,
+) -> Result<ConflictedFile> {
+    let lines = raw_lines(&content);
     let mut conflicts = Vec::new();
     let mut i = 0;
 
@@ -24,14 +218,14 @@ pub fn parse_conflicts(file_path: &Path) -> Result<ConflictedFile> {
             None => break, // Should never happen due to while condition, but safe
         };
 
-        if line.starts_with(CONFLICT_START) {
+        if is_sized_marker(line, START_MARKER, marker_size) {
             let conflict_start_line = i;
 
             // Find separator with safe indexing
             let mut separator_line = None;
             for j in (i + 1)..lines.len() {
                 if let Some(line) = lines.get(j) {
-                    if line.starts_with(CONFLICT_SEPARATOR) {
+                    if is_sized_marker(line, SEPARATOR_MARKER, marker_size) {
                         separator_line = Some(j);
                         break;
                     }
@@ -53,7 +247,7 @@ pub fn parse_conflicts(file_path: &Path) -> Result<ConflictedFile> {
             let mut end_line = None;
             for j in (separator_line + 1)..lines.len() {
                 if let Some(line) = lines.get(j) {
-                    if line.starts_with(CONFLICT_END) {
+                    if is_sized_marker(line, END_MARKER, marker_size) {
                         end_line = Some(j);
                         break;
                     }
@@ -71,18 +265,41 @@ pub fn parse_conflicts(file_path: &Path) -> Result<ConflictedFile> {
                 }
             };
 
+            // diff3/zdiff3 style puts the common ancestor between the current
+            // side and the separator, introduced by a `|||||||` marker
+            let base_line = ((conflict_start_line + 1)..separator_line).find(|&j| {
+                lines
+                    .get(j)
+                    .is_some_and(|line| is_sized_marker(line, BASE_MARKER, marker_size))
+            });
+            let current_end = base_line.unwrap_or(separator_line);
+
             // Extract current and incoming content with safe slicing
             let current_lines = lines
-                .get((conflict_start_line + 1)..separator_line)
+                .get((conflict_start_line + 1)..current_end)
                 .ok_or_else(|| {
                     anyhow::anyhow!(
                         "Invalid conflict range in {}: lines {}-{}",
                         file_path.display(),
                         conflict_start_line + 1,
-                        separator_line
+                        current_end
                     )
                 })?;
 
+            let base_lines = match base_line {
+                Some(base_line) => {
+                    Some(lines.get((base_line + 1)..separator_line).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Invalid conflict range in {}: lines {}-{}",
+                            file_path.display(),
+                            base_line + 1,
+                            separator_line
+                        )
+                    })?)
+                }
+                None => None,
+            };
+
             let incoming_lines = lines.get((separator_line + 1)..end_line).ok_or_else(|| {
                 anyhow::anyhow!(
                     "Invalid conflict range in {}: lines {}-{}",
@@ -95,7 +312,16 @@ pub fn parse_conflicts(file_path: &Path) -> Result<ConflictedFile> {
             let current = current_lines.join("\n");
             let incoming = incoming_lines.join("\n");
 
-            let hunk = ConflictHunk::new(current, incoming, conflict_start_line, end_line);
+            let label = |line: usize| marker_label(lines[line], marker_size);
+            let mut hunk = ConflictHunk::new(current, incoming, conflict_start_line, end_line)
+                .with_labels(
+                    label(conflict_start_line),
+                    base_line.and_then(label),
+                    label(end_line),
+                );
+            if let Some(base_lines) = base_lines {
+                hunk = hunk.with_base(base_lines.join("\n"));
+            }
             conflicts.push(hunk);
 
             // Move past this conflict
@@ -109,19 +335,63 @@ pub fn parse_conflicts(file_path: &Path) -> Result<ConflictedFile> {
         anyhow::bail!("No conflicts found in file: {}", file_path.display());
     }
 
-    Ok(ConflictedFile::new(
-        file_path.to_path_buf(),
-        conflicts,
-        content,
-    ))
+    Ok(
+        ConflictedFile::new(file_path.to_path_buf(), conflicts, content)
+            .with_marker_size(marker_size),
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::conflicted_repo;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
+    #[test]
+    fn test_is_conflict_marker_line() {
+        assert!(is_conflict_marker_line("<<<<<<< HEAD", DEFAULT_MARKER_SIZE));
+        assert!(is_conflict_marker_line("||||||| base", DEFAULT_MARKER_SIZE));
+        assert!(is_conflict_marker_line("=======", DEFAULT_MARKER_SIZE));
+        assert!(is_conflict_marker_line(
+            ">>>>>>> feature",
+            DEFAULT_MARKER_SIZE
+        ));
+        assert!(!is_conflict_marker_line("let a = b;", DEFAULT_MARKER_SIZE));
+        assert!(!is_conflict_marker_line("========", DEFAULT_MARKER_SIZE));
+        assert!(is_conflict_marker_line("========", 8));
+        assert!(!is_conflict_marker_line("=======", 8));
+    }
+
+    #[test]
+    fn test_find_leftover_markers() {
+        let content = "a\n<<<<<<< HEAD\nb\n=======\nc\n>>>>>>> feature\nd\n>>>>>>>\n<<<<<<<\n";
+        assert_eq!(
+            find_leftover_markers(content, DEFAULT_MARKER_SIZE),
+            vec![
+                LeftoverMarker::Block {
+                    start_line: 1,
+                    end_line: 5
+                },
+                LeftoverMarker::Stray { line: 7 },
+                LeftoverMarker::Unterminated { line: 8 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_leftover_markers_respects_size() {
+        let content = "<<<<<<<< HEAD\na\n>>>>>>>> x\n<<<<<<<<<< too long\n<<<<<<<-not a marker\n";
+        assert!(find_leftover_markers(content, DEFAULT_MARKER_SIZE).is_empty());
+        assert_eq!(
+            find_leftover_markers(content, 8),
+            vec![LeftoverMarker::Block {
+                start_line: 0,
+                end_line: 2
+            }]
+        );
+    }
+
     #[test]
     fn test_parse_simple_conflict() {
         let mut temp_file = NamedTempFile::new().unwrap();
@@ -131,7 +401,7 @@ mod tests {
         )
         .unwrap();
 
-        let result = parse_conflicts(temp_file.path());
+        let result = parse_conflicts(temp_file.path(), DEFAULT_MARKER_SIZE, None);
         assert!(result.is_ok());
 
         let conflicted_file = result.unwrap();
@@ -149,19 +419,129 @@ mod tests {
         )
         .unwrap();
 
-        let result = parse_conflicts(temp_file.path());
+        let result = parse_conflicts(temp_file.path(), DEFAULT_MARKER_SIZE, None);
         assert!(result.is_ok());
 
         let conflicted_file = result.unwrap();
         assert_eq!(conflicted_file.conflicts.len(), 2);
     }
 
+    #[test]
+    fn test_parse_diff3_conflict() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(
+            temp_file,
+            "line 1\n<<<<<<< HEAD\ncurrent content\n||||||| merged common ancestors\nbase content\n=======\nincoming content\n>>>>>>> branch\nline 2"
+        )
+        .unwrap();
+
+        let conflicted_file = parse_conflicts(temp_file.path(), DEFAULT_MARKER_SIZE, None).unwrap();
+        assert_eq!(conflicted_file.conflicts.len(), 1);
+
+        let hunk = &conflicted_file.conflicts[0];
+        assert_eq!(hunk.current, "current content");
+        assert_eq!(hunk.base.as_deref(), Some("base content"));
+        assert_eq!(hunk.incoming, "incoming content");
+        assert_eq!(hunk.start_line, 1);
+        assert_eq!(hunk.end_line, 7);
+    }
+
+    #[test]
+    fn test_parse_diff3_conflict_with_empty_base() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(
+            temp_file,
+            "<<<<<<< HEAD\ncurrent\n||||||| base\n=======\nincoming\n>>>>>>> branch"
+        )
+        .unwrap();
+
+        let conflicted_file = parse_conflicts(temp_file.path(), DEFAULT_MARKER_SIZE, None).unwrap();
+        let hunk = &conflicted_file.conflicts[0];
+        assert_eq!(hunk.current, "current");
+        assert_eq!(hunk.base.as_deref(), Some(""));
+        assert_eq!(hunk.incoming, "incoming");
+    }
+
+    #[test]
+    fn test_parse_merge_style_has_no_base() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(
+            temp_file,
+            "<<<<<<< HEAD\ncurrent\n=======\nincoming\n>>>>>>> branch"
+        )
+        .unwrap();
+
+        let conflicted_file = parse_conflicts(temp_file.path(), DEFAULT_MARKER_SIZE, None).unwrap();
+        assert!(conflicted_file.conflicts[0].base.is_none());
+    }
+
+    #[test]
+    fn test_parse_conflicts_from_index_without_markers() {
+        let dir = conflicted_repo("merge", "a\nbase\nc\n", "a\nours\nc\n", "a\ntheirs\nc\n");
+        let file_path = dir.path().join("file.txt");
+
+        // Strip the markers from the worktree file
+        fs::write(&file_path, "a\nhand edited\nc\n").unwrap();
+        assert!(parse_conflicts(&file_path, DEFAULT_MARKER_SIZE, None).is_err());
+
+        let repo = Repository::open(dir.path()).unwrap();
+        let conflicted_file =
+            parse_conflicts_from_index(&repo, &file_path, DEFAULT_MARKER_SIZE, None).unwrap();
+
+        assert_eq!(conflicted_file.conflicts.len(), 1);
+        let hunk = &conflicted_file.conflicts[0];
+        assert_eq!(hunk.current, "ours");
+        assert_eq!(hunk.base.as_deref(), Some("base"));
+        assert_eq!(hunk.incoming, "theirs");
+    }
+
+    #[test]
+    fn test_parse_stores_marker_labels() {
+        let content = "<<<<<<< HEAD\nours\n||||||| parent of 1a2b3c (Add feature)\nbase\n=======\ntheirs\n>>>>>>> 1a2b3c (Add feature)\n";
+        let file = parse_conflict_content(Path::new("f"), content.to_string(), DEFAULT_MARKER_SIZE)
+            .unwrap();
+
+        let hunk = &file.conflicts[0];
+        assert_eq!(hunk.current_label.as_deref(), Some("HEAD"));
+        assert_eq!(
+            hunk.base_label.as_deref(),
+            Some("parent of 1a2b3c (Add feature)")
+        );
+        assert_eq!(hunk.incoming_label.as_deref(), Some("1a2b3c (Add feature)"));
+    }
+
+    #[test]
+    fn test_parse_ignores_longer_marker_runs_inside_hunk() {
+        let content = "<<<<<<< HEAD\nTitle\n========\n=======\ntheirs\n>>>>>>> feature\n";
+        let file = parse_conflict_content(Path::new("f"), content.to_string(), DEFAULT_MARKER_SIZE)
+            .unwrap();
+
+        assert_eq!(file.conflicts[0].current, "Title\n========");
+        assert_eq!(file.conflicts[0].incoming, "theirs");
+    }
+
+    #[test]
+    fn test_parse_custom_marker_size() {
+        let content = "<<<<<<< not a marker\n<<<<<<<<<< HEAD\n=======\nours\n==========\ntheirs\n>>>>>>>>>> feature\n";
+
+        assert!(
+            parse_conflict_content(Path::new("f"), content.to_string(), DEFAULT_MARKER_SIZE)
+                .is_err()
+        );
+        let file = parse_conflict_content(Path::new("f"), content.to_string(), 10).unwrap();
+        let hunk = &file.conflicts[0];
+        assert_eq!(hunk.start_line, 1);
+        assert_eq!(hunk.current, "=======\nours");
+        assert_eq!(hunk.incoming, "theirs");
+        assert_eq!(hunk.incoming_label.as_deref(), Some("feature"));
+    }
+
     #[test]
     fn test_parse_no_conflicts() {
         let mut temp_file = NamedTempFile::new().unwrap();
         writeln!(temp_file, "just normal content\nno conflicts here").unwrap();
 
-        let result = parse_conflicts(temp_file.path());
+        let result = parse_conflicts(temp_file.path(), DEFAULT_MARKER_SIZE, None);
         assert!(result.is_err());
     }
 }
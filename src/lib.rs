@@ -1,11 +1,20 @@
 pub mod app;
+pub mod cli;
+pub mod config;
 pub mod domain;
 pub mod git;
 pub mod tui;
 pub mod version;
 
+#[cfg(test)]
+mod test_support;
+
 pub use app::{AppMode, AppState, ViewMode};
-pub use domain::{ConflictHunk, ConflictedFile, GitOperation, Resolution};
+pub use config::Config;
+pub use domain::{
+    BlobInfo, ConflictHunk, ConflictedFile, FileConflictKind, FileResolution, GitOperation,
+    Resolution,
+};
 pub use git::{detect_git_operation, find_conflicted_files, parse_conflicts};
 pub use tui::run_app;
 pub use version::{check_for_updates, UpdateInfo};
@@ -1,18 +1,312 @@
 use anyhow::{Context, Result};
-use clap::Parser;
-use murasaki_rs::git::get_repository_status;
+use clap::{Parser, Subcommand};
+use murasaki_rs::app::CodeLayout;
+use murasaki_rs::cli::{
+    run_check, run_hooks_install, run_hooks_uninstall, run_list, run_mergetool, run_resolve,
+    CheckOptions, CheckScope, HunkSelector, ResolveOptions,
+};
+use murasaki_rs::git::DEFAULT_MARKER_SIZE;
+use murasaki_rs::git::{get_repository_status, load_conflicted_files};
 use murasaki_rs::{
-    check_for_updates, detect_git_operation, find_conflicted_files, parse_conflicts, run_app,
-    AppState, UpdateInfo,
+    check_for_updates, detect_git_operation, find_conflicted_files, run_app, AppState, Config,
+    GitOperation, Resolution, UpdateInfo,
 };
+use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[command(name = "saki")]
 #[command(about = "A TUI tool for handling git conflicts during merge or rebase", long_about = None)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Specific files to resolve (if not provided, all conflicted files will be shown)
     #[arg(value_name = "FILES")]
     files: Vec<String>,
+
+    /// Color theme: dark, light, high-contrast, a TOML theme file, or the name of
+    /// one in ~/.config/saki/themes/ (defaults to $SAKI_THEME, then the config, then dark)
+    #[arg(long, value_name = "THEME", global = true)]
+    theme: Option<String>,
+
+    /// Layout the code pane opens with
+    #[arg(long, value_name = "unified|three-way", global = true)]
+    layout: Option<CodeLayout>,
+
+    /// Write files as soon as all their conflicts are resolved (default)
+    #[arg(long, overrides_with = "no_auto_save", global = true)]
+    auto_save: bool,
+
+    /// Only write resolutions when pressing `w`
+    #[arg(long, overrides_with = "auto_save", global = true)]
+    no_auto_save: bool,
+
+    /// Stage files when their resolution is written
+    #[arg(long, overrides_with = "no_auto_stage", global = true)]
+    auto_stage: bool,
+
+    /// Leave written files unstaged (default)
+    #[arg(long, overrides_with = "auto_stage", global = true)]
+    no_auto_stage: bool,
+
+    /// Don't check for a newer release at startup
+    #[arg(long, global = true)]
+    no_update_check: bool,
+
+    /// Add a Signed-off-by trailer to commits made from saki
+    #[arg(long, global = true)]
+    signoff: bool,
+
+    /// Skip the commit hooks for commits made from saki
+    #[arg(long, global = true)]
+    no_verify: bool,
+}
+
+impl Args {
+    /// Apply the command line settings, which win over config files and env vars
+    fn apply_to(&self, config: &mut Config) {
+        if let Some(theme) = &self.theme {
+            config.theme = Some(theme.clone());
+        }
+        if let Some(layout) = self.layout {
+            config.layout = layout;
+        }
+        if self.auto_save || self.no_auto_save {
+            config.auto_save = self.auto_save;
+        }
+        if self.auto_stage || self.no_auto_stage {
+            config.auto_stage = self.auto_stage;
+        }
+        if self.no_update_check {
+            config.update_check = false;
+        }
+        if self.signoff {
+            config.commit.sign_off = true;
+        }
+        if self.no_verify {
+            config.commit.no_verify = true;
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Fail if files still contain conflict markers
+    ///
+    /// Scans staged files by default, for use in a pre-commit hook. Paths can be
+    /// excluded with the `-saki-check` attribute in `.gitattributes`, and the
+    /// `conflict-marker-size` attribute overrides --marker-size per path.
+    Check {
+        /// Files to scan in the working tree instead of the staged files
+        #[arg(value_name = "FILES")]
+        files: Vec<String>,
+        /// Scan every tracked file in the working tree
+        #[arg(long, conflicts_with = "files")]
+        all: bool,
+        /// Length of the conflict markers to look for
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = DEFAULT_MARKER_SIZE,
+            value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+        )]
+        marker_size: usize,
+    },
+    /// Manage the git hook that blocks commits with leftover conflict markers
+    Hooks {
+        #[command(subcommand)]
+        action: HooksCommand,
+    },
+    /// List conflicted files and their hunks
+    #[command(visible_alias = "status")]
+    List {
+        /// Print a JSON report instead of a summary
+        #[arg(long)]
+        json: bool,
+    },
+    /// Resolve one file as a `git mergetool` backend
+    ///
+    /// Configure with:
+    /// git config mergetool.saki.cmd 'saki mergetool "$BASE" "$LOCAL" "$REMOTE" "$MERGED"'
+    ///
+    /// Exits non-zero when the file is left unresolved.
+    Mergetool {
+        /// Common ancestor version ($BASE)
+        base: PathBuf,
+        /// Current branch version ($LOCAL)
+        local: PathBuf,
+        /// Version being merged in ($REMOTE)
+        remote: PathBuf,
+        /// File to write the result to ($MERGED)
+        merged: PathBuf,
+    },
+    /// Resolve conflicts without the TUI
+    ///
+    /// Per-conflict --hunk selectors win over --ours/--theirs/--both, which apply
+    /// to every remaining conflict. Files left with unresolved conflicts are not
+    /// written (except with --auto), and the exit status is non-zero.
+    Resolve {
+        /// Conflicted files to resolve (with --auto, defaults to every conflicted file)
+        #[arg(value_name = "FILES", required_unless_present = "auto")]
+        files: Vec<String>,
+        /// Resolve trivial conflicts first: identical sides, trailing-whitespace or
+        /// line-ending differences and (with diff3) a side equal to the base. Other conflicts
+        /// keep their markers unless a side or --hunk picks them.
+        #[arg(long)]
+        auto: bool,
+        /// Keep the current (HEAD) side (deletes a file deleted in HEAD)
+        #[arg(long, group = "side")]
+        ours: bool,
+        /// Keep the incoming side (deletes a file deleted by the incoming side)
+        #[arg(long, group = "side")]
+        theirs: bool,
+        /// Keep both sides, current first
+        #[arg(long, group = "side")]
+        both: bool,
+        /// Resolve a single conflict, numbered from 1 (repeatable)
+        #[arg(long = "hunk", value_name = "N=ours|theirs|both")]
+        hunks: Vec<HunkSelector>,
+        /// Stage the resolved files; without it, whole-file conflicts are only
+        /// settled in the working tree
+        #[arg(long)]
+        stage: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum HooksCommand {
+    /// Install a pre-commit hook running `saki check` (an existing hook keeps running first)
+    Install,
+    /// Remove the hook installed by saki and restore the previous one
+    Uninstall,
+}
+
+/// Settings for the TUI: config files, then SAKI_* env vars, then command line flags
+fn load_config(args: &Args) -> Result<Config> {
+    let workdir = murasaki_rs::git::detector::open_repository()
+        .ok()
+        .and_then(|repo| repo.workdir().map(PathBuf::from));
+    let mut config = Config::load(workdir.as_deref())?;
+    config.apply_env(|name| std::env::var(name).ok())?;
+    args.apply_to(&mut config);
+    Ok(config)
+}
+
+/// Run a subcommand and exit with its status code
+///
+/// Only `mergetool`, which opens the TUI, reads the config: a broken config
+/// file must not break `saki check` in hooks and CI.
+fn run_command(command: Command, args: &Args) -> Result<()> {
+    let code = match command {
+        Command::Check {
+            files,
+            all,
+            marker_size,
+        } => {
+            let repo = murasaki_rs::git::detector::open_repository()
+                .context("Failed to open git repository")?;
+            let scope = if all {
+                CheckScope::All
+            } else if files.is_empty() {
+                CheckScope::Staged
+            } else {
+                CheckScope::Paths(validate_file_args(&repo, files)?)
+            };
+            let options = CheckOptions { scope, marker_size };
+            run_check(&repo, &options)?
+        }
+        Command::Hooks { action } => {
+            let repo = murasaki_rs::git::detector::open_repository()
+                .context("Failed to open git repository")?;
+            match action {
+                HooksCommand::Install => run_hooks_install(&repo)?,
+                HooksCommand::Uninstall => run_hooks_uninstall(&repo)?,
+            }
+        }
+        Command::List { json } => {
+            let repo = murasaki_rs::git::detector::open_repository()
+                .context("Failed to open git repository")?;
+            run_list(&repo, json)?
+        }
+        Command::Mergetool {
+            base,
+            local,
+            remote,
+            merged,
+        } => run_mergetool(&base, &local, &remote, &merged, load_config(args)?)?,
+        Command::Resolve {
+            files,
+            auto,
+            ours,
+            theirs,
+            both,
+            hunks,
+            stage,
+        } => {
+            let repo = murasaki_rs::git::detector::open_repository()
+                .context("Failed to open git repository")?;
+            let paths = if files.is_empty() {
+                find_conflicted_files(&repo).context("Failed to find conflicted files")?
+            } else {
+                validate_file_args(&repo, files)?
+            };
+            if paths.is_empty() {
+                anyhow::bail!("No conflicted files found");
+            }
+            let default = if ours {
+                Some(Resolution::Current)
+            } else if theirs {
+                Some(Resolution::Incoming)
+            } else if both {
+                Some(Resolution::Both)
+            } else {
+                None
+            };
+            let options = ResolveOptions {
+                default,
+                hunks,
+                stage,
+                auto,
+            };
+            run_resolve(&repo, &paths, &options)?
+        }
+    };
+    std::process::exit(code);
+}
+
+/// Validate user-provided file paths, returning them canonicalized
+fn validate_file_args(repo: &git2::Repository, files: Vec<String>) -> Result<Vec<PathBuf>> {
+    let workdir = repo
+        .workdir()
+        .context("Repository has no working directory")?;
+
+    files
+        .into_iter()
+        .map(|file_str| {
+            let path = std::path::PathBuf::from(&file_str);
+
+            // Canonicalize to resolve symlinks and .. components
+            let canonical_path = path
+                .canonicalize()
+                .with_context(|| format!("File not found: {}", file_str))?;
+
+            // Ensure the file is within the repository
+            if !canonical_path.starts_with(workdir) {
+                anyhow::bail!(
+                    "File {} is outside repository: {}",
+                    file_str,
+                    canonical_path.display()
+                );
+            }
+
+            // Ensure it's a file, not a directory
+            if !canonical_path.is_file() {
+                anyhow::bail!("{} is not a file", file_str);
+            }
+
+            Ok(canonical_path)
+        })
+        .collect()
 }
 
 fn print_update_notification(update_info: &UpdateInfo) {
@@ -32,67 +326,45 @@ fn print_update_notification(update_info: &UpdateInfo) {
 }
 
 fn main() -> Result<()> {
-    let args = Args::parse();
+    let mut args = Args::parse();
 
-    // Check for updates at startup (non-blocking with 3s timeout)
-    if let Some(update_info) = check_for_updates() {
-        print_update_notification(&update_info);
+    if let Some(command) = args.command.take() {
+        return run_command(command, &args);
     }
 
+    let config = load_config(&args)?;
+
     // Open git repository
     let repo =
         murasaki_rs::git::detector::open_repository().context("Failed to open git repository")?;
 
+    // Check for updates at startup (non-blocking with 3s timeout)
+    if config.update_check {
+        if let Some(update_info) = check_for_updates() {
+            print_update_notification(&update_info);
+        }
+    }
+
     // Check if there's an ongoing git operation (merge or rebase)
-    let git_operation = detect_git_operation(&repo);
+    let operation = detect_git_operation(&repo)?;
 
     // If there's a git operation, handle conflicts
-    if let Ok(operation) = git_operation {
+    if operation != GitOperation::None {
         // Find conflicted files with validation
         let conflicted_paths = if args.files.is_empty() {
             find_conflicted_files(&repo).context("Failed to find conflicted files")?
         } else {
-            // Validate user-provided file paths
-            let workdir = repo
-                .workdir()
-                .context("Repository has no working directory")?;
-
-            args.files
-                .into_iter()
-                .map(|file_str| {
-                    let path = std::path::PathBuf::from(&file_str);
-
-                    // Canonicalize to resolve symlinks and .. components
-                    let canonical_path = path
-                        .canonicalize()
-                        .with_context(|| format!("File not found: {}", file_str))?;
-
-                    // Ensure the file is within the repository
-                    if !canonical_path.starts_with(workdir) {
-                        anyhow::bail!(
-                            "File {} is outside repository: {}",
-                            file_str,
-                            canonical_path.display()
-                        );
-                    }
-
-                    // Ensure it's a file, not a directory
-                    if !canonical_path.is_file() {
-                        anyhow::bail!("{} is not a file", file_str);
-                    }
-
-                    Ok(canonical_path)
-                })
-                .collect::<Result<Vec<_>>>()?
+            validate_file_args(&repo, args.files)?
         };
 
-        // Parse conflicts from each file
+        // Parse conflicts from each file (falling back to the index stages when the
+        // worktree markers are unusable) and classify whole-file conflicts
         let mut conflicted_files = Vec::new();
-        for path in conflicted_paths {
-            match parse_conflicts(&path) {
+        for result in load_conflicted_files(&repo, &conflicted_paths) {
+            match result {
                 Ok(file) => conflicted_files.push(file),
                 Err(e) => {
-                    eprintln!("Warning: Failed to parse {}: {}", path.display(), e);
+                    eprintln!("Warning: {:#}", e);
                 }
             }
         }
@@ -102,8 +374,11 @@ fn main() -> Result<()> {
             return Ok(());
         }
 
-        // Create app state in conflict mode and run
-        let state = AppState::new(conflicted_files, operation);
+        // Create app state in conflict mode, pre-fill trivial conflicts and run
+        let mut state = AppState::new(conflicted_files, operation);
+        state.set_config(config);
+        state.auto_resolve();
+        state.warn_rebuilt_files();
         run_app(state)?;
     } else {
         // No git operation in progress, run in staging mode
@@ -111,6 +386,7 @@ fn main() -> Result<()> {
 
         // Create app state in staging mode
         let mut state = AppState::new_staging(file_statuses);
+        state.set_config(config);
 
         // Load diff for the first file if there are any files
         if !state.file_statuses.is_empty() {
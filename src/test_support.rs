@@ -0,0 +1,71 @@
+//! Fixtures shared by the tests that need a real git repository
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+/// Run git in `dir` as a test user, returning whether it succeeded
+pub fn try_git(dir: &Path, args: &[&str]) -> bool {
+    Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+        .status
+        .success()
+}
+
+/// Run git in `dir` as a test user; only `merge` may fail (it stops on conflicts)
+pub fn git(dir: &Path, args: &[&str]) {
+    assert!(
+        try_git(dir, args) || args.contains(&"merge"),
+        "git {:?} failed",
+        args
+    );
+}
+
+/// Create a repository with `file.txt` left conflicted by merging a `feature`
+/// branch (`theirs`) into `main` (`ours`), both changed from `base`
+///
+/// `style` is the `merge.conflictStyle` the markers are written with.
+pub fn conflicted_repo(style: &str, base: &str, ours: &str, theirs: &str) -> TempDir {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path();
+    git(path, &["init", "-q", "-b", "main"]);
+    fs::write(path.join("file.txt"), base).unwrap();
+    git(path, &["add", "."]);
+    git(path, &["commit", "-q", "-m", "base"]);
+    git(path, &["checkout", "-q", "-b", "feature"]);
+    fs::write(path.join("file.txt"), theirs).unwrap();
+    git(path, &["commit", "-q", "-am", "theirs"]);
+    git(path, &["checkout", "-q", "main"]);
+    fs::write(path.join("file.txt"), ours).unwrap();
+    git(path, &["commit", "-q", "-am", "ours"]);
+    let style = format!("merge.conflictStyle={}", style);
+    git(path, &["-c", &style, "merge", "-q", "feature"]);
+    dir
+}
+
+/// Create a repository where `del.txt` is deleted by us and `orig.txt` is
+/// renamed on both sides (to `ours.txt` and `theirs.txt`)
+pub fn whole_file_conflict_repo() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path();
+    git(path, &["init", "-q", "-b", "main"]);
+    fs::write(path.join("orig.txt"), "one\ntwo\nthree\n").unwrap();
+    fs::write(path.join("del.txt"), "x\n").unwrap();
+    git(path, &["add", "."]);
+    git(path, &["commit", "-q", "-m", "base"]);
+    git(path, &["checkout", "-q", "-b", "feature"]);
+    git(path, &["mv", "orig.txt", "theirs.txt"]);
+    fs::write(path.join("del.txt"), "x\ny\n").unwrap();
+    git(path, &["commit", "-q", "-am", "theirs"]);
+    git(path, &["checkout", "-q", "main"]);
+    git(path, &["mv", "orig.txt", "ours.txt"]);
+    git(path, &["rm", "-q", "del.txt"]);
+    git(path, &["commit", "-q", "-m", "ours"]);
+    git(path, &["merge", "-q", "feature"]);
+    dir
+}
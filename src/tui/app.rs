@@ -1,5 +1,6 @@
 use anyhow::Result;
 use crossterm::{
+    event::{DisableBracketedPaste, EnableBracketedPaste},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -7,14 +8,27 @@ use ratatui::{backend::CrosstermBackend, Terminal};
 use std::{io, panic};
 
 use crate::app::{AppState, ViewMode};
-use crate::tui::{event::handle_events, views};
+use crate::tui::{
+    event::{auto_save_if_resolved, handle_events},
+    external_editor::edit_current_hunk,
+    theme::{set_theme, Theme},
+    views,
+};
+
+/// Run the TUI application, returning the final state
+///
+/// Draws with the theme named in the state's config, so every entry point
+/// (conflicts, staging, mergetool) gets the same colors.
+pub fn run_app(mut state: AppState) -> Result<AppState> {
+    // Load the theme before taking over the terminal, so a bad one is a plain error
+    if let Some(name) = &state.config.theme {
+        set_theme(Theme::load(name)?);
+    }
 
-/// Run the TUI application
-pub fn run_app(mut state: AppState) -> Result<()> {
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableBracketedPaste)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
@@ -23,7 +37,7 @@ pub fn run_app(mut state: AppState) -> Result<()> {
     panic::set_hook(Box::new(move |panic_info| {
         // Attempt to restore terminal (ignore errors since we're panicking anyway)
         let _ = disable_raw_mode();
-        let _ = execute!(io::stdout(), LeaveAlternateScreen);
+        let _ = execute!(io::stdout(), DisableBracketedPaste, LeaveAlternateScreen);
 
         // Call the original panic hook
         original_hook(panic_info);
@@ -37,10 +51,14 @@ pub fn run_app(mut state: AppState) -> Result<()> {
 
     // Restore terminal (this runs whether result is Ok or Err)
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(
+        terminal.backend_mut(),
+        DisableBracketedPaste,
+        LeaveAlternateScreen
+    )?;
     terminal.show_cursor()?;
 
-    result
+    result.map(|_| state)
 }
 
 fn run_loop(
@@ -59,10 +77,20 @@ fn run_loop(
                     views::render_rebase_actions(frame, state, area);
                 }
             }
+
+            if let Some(notification) = &state.notification {
+                views::render_notification(frame, notification, &state.config.keys, area);
+            }
         })?;
 
         handle_events(state)?;
 
+        if std::mem::take(&mut state.external_edit_requested) {
+            if let Err(e) = suspend_and_edit(terminal, state) {
+                state.set_status_message(format!("Edit failed: {:#}", e));
+            }
+        }
+
         if state.should_quit {
             break;
         }
@@ -70,3 +98,30 @@ fn run_loop(
 
     Ok(())
 }
+
+/// Leave the TUI, edit the current conflict in the user's editor and come back
+fn suspend_and_edit(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    state: &mut AppState,
+) -> Result<()> {
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        DisableBracketedPaste,
+        LeaveAlternateScreen
+    )?;
+    terminal.show_cursor()?;
+
+    let result = edit_current_hunk(state);
+
+    enable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        EnterAlternateScreen,
+        EnableBracketedPaste
+    )?;
+    terminal.clear()?;
+
+    result?;
+    auto_save_if_resolved(state)
+}
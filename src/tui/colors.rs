@@ -1,100 +1,36 @@
 use ratatui::style::Color;
 
-/// Murasaki custom color palette
-/// Gradient: Cyan → Blue → Purple → Magenta
-pub struct MurasakiColors;
-
-impl MurasakiColors {
-    // Cyan shades (top/header elements)
-    pub const CYAN_BRIGHT: Color = Color::Rgb(0, 255, 255); // #00FFFF
-    pub const CYAN_LIGHT: Color = Color::Rgb(64, 224, 255); // #40E0FF
-
-    // Blue shades (primary UI elements)
-    pub const BLUE_MEDIUM: Color = Color::Rgb(0, 128, 255); // #0080FF
-    pub const BLUE_ROYAL: Color = Color::Rgb(65, 105, 225); // #4169E1
-
-    // Purple/Violet shades (secondary elements)
-    pub const PURPLE_BRIGHT: Color = Color::Rgb(128, 0, 255); // #8000FF
-    pub const PURPLE_INDIGO: Color = Color::Rgb(75, 0, 130); // #4B0082
-
-    // Magenta/Pink shades (bottom/accent elements)
-    pub const MAGENTA_BRIGHT: Color = Color::Rgb(255, 0, 255); // #FF00FF
-    pub const PINK_HOT: Color = Color::Rgb(255, 105, 180); // #FF69B4
-
-    // Semantic colors
-    pub const SUCCESS: Color = Color::Rgb(0, 255, 200); // Cyan-green
-    pub const WARNING: Color = Color::Rgb(255, 200, 0); // Yellow
-    pub const ERROR: Color = Color::Rgb(255, 80, 120); // Pink-red
-    pub const INFO: Color = Color::Rgb(100, 180, 255); // Light blue
-
-    // UI state colors
-    pub const FOCUS_BORDER: Color = Self::CYAN_BRIGHT;
-    pub const INACTIVE_BORDER: Color = Color::Rgb(100, 100, 150);
-    pub const SELECTED_BG: Color = Color::Rgb(40, 40, 80);
-    pub const TEXT_NORMAL: Color = Color::Rgb(220, 220, 255);
-    pub const TEXT_DIM: Color = Color::Rgb(120, 120, 160);
-    pub const FOOTER_BG: Color = Color::Rgb(60, 60, 70); // Lighter gray for footer
-
-    // Code conflict colors
-    pub const CONFLICT_CURRENT: Color = Self::CYAN_LIGHT; // Current (HEAD)
-    pub const CONFLICT_INCOMING: Color = Self::MAGENTA_BRIGHT; // Incoming
-    pub const CONFLICT_BOTH: Color = Self::PURPLE_BRIGHT; // Both
-    pub const CONFLICT_MARKER: Color = Self::BLUE_ROYAL; // Markers (<<<, ===, >>>)
-
-    // Conflict backgrounds (semi-transparent effect with darker tones)
-    pub const CONFLICT_CURRENT_BG: Color = Color::Rgb(0, 30, 80); // Dark blue background
-    pub const CONFLICT_INCOMING_BG: Color = Color::Rgb(80, 20, 20); // Dark red background
-    pub const CONFLICT_BOTH_BG: Color = Color::Rgb(60, 20, 80); // Dark purple background
-
-    // Resolved conflict background (light green)
-    pub const RESOLVED_BG: Color = Color::Rgb(20, 60, 30); // Subtle green background
-
-    // Status indicators
-    pub const STATUS_RESOLVED: Color = Self::SUCCESS;
-    pub const STATUS_UNRESOLVED: Color = Self::ERROR;
-}
+use super::theme::theme;
 
 /// Helper to create gradient text effects
 pub fn gradient_char_color(index: usize, total: usize) -> Color {
-    let ratio = index as f32 / total.max(1) as f32;
-
-    // Gradient from cyan → magenta
-    if ratio < 0.33 {
-        // Cyan → Blue
-        let local = ratio / 0.33;
-        interpolate_color(
-            MurasakiColors::CYAN_BRIGHT,
-            MurasakiColors::BLUE_MEDIUM,
-            local,
-        )
-    } else if ratio < 0.66 {
-        // Blue → Purple
-        let local = (ratio - 0.33) / 0.33;
-        interpolate_color(
-            MurasakiColors::BLUE_MEDIUM,
-            MurasakiColors::PURPLE_BRIGHT,
-            local,
-        )
-    } else {
-        // Purple → Magenta
-        let local = (ratio - 0.66) / 0.34;
-        interpolate_color(
-            MurasakiColors::PURPLE_BRIGHT,
-            MurasakiColors::MAGENTA_BRIGHT,
-            local,
-        )
+    // Spread the theme's stops evenly (cyan → blue → purple → magenta by default)
+    let stops = &theme().gradient;
+    match stops.len() {
+        0 => return theme().accent,
+        1 => return stops[0],
+        _ => {}
     }
+
+    let ratio = index as f32 / total.max(1) as f32;
+    let position = ratio.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+    let segment = (position as usize).min(stops.len() - 2);
+    interpolate_color(
+        stops[segment],
+        stops[segment + 1],
+        position - segment as f32,
+    )
 }
 
 fn interpolate_color(from: Color, to: Color, ratio: f32) -> Color {
-    let (r1, g1, b1) = match from {
-        Color::Rgb(r, g, b) => (r as f32, g as f32, b as f32),
-        _ => (0.0, 0.0, 0.0),
-    };
-
-    let (r2, g2, b2) = match to {
-        Color::Rgb(r, g, b) => (r as f32, g as f32, b as f32),
-        _ => (0.0, 0.0, 0.0),
+    // Named terminal colors have no RGB value to blend: take the closer stop
+    let ((r1, g1, b1), (r2, g2, b2)) = match (from, to) {
+        (Color::Rgb(r1, g1, b1), Color::Rgb(r2, g2, b2)) => (
+            (r1 as f32, g1 as f32, b1 as f32),
+            (r2 as f32, g2 as f32, b2 as f32),
+        ),
+        _ if ratio < 0.5 => return from,
+        _ => return to,
     };
 
     let r = (r1 + (r2 - r1) * ratio) as u8;
@@ -103,3 +39,20 @@ fn interpolate_color(from: Color, to: Color, ratio: f32) -> Color {
 
     Color::Rgb(r, g, b)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gradient_follows_theme_stops() {
+        let stops = &theme().gradient;
+        assert_eq!(gradient_char_color(0, 10), stops[0]);
+        assert_eq!(gradient_char_color(10, 10), stops[stops.len() - 1]);
+        assert_eq!(
+            interpolate_color(Color::Rgb(0, 0, 0), Color::Rgb(200, 100, 50), 0.5),
+            Color::Rgb(100, 50, 25)
+        );
+        assert_eq!(interpolate_color(Color::Red, Color::Blue, 0.7), Color::Blue);
+    }
+}
@@ -2,31 +2,59 @@ use anyhow::Result;
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use std::time::Duration;
 
-use crate::app::{AppState, PaneFocus, ViewMode};
-use crate::domain::Resolution;
+use crate::app::{AppState, GitAction, Notification, PaneFocus, ViewMode};
+use crate::domain::{FileResolution, Resolution};
 use crate::git::{
-    abort_rebase, apply_resolutions, commit_changes, continue_rebase, get_file_diff,
-    get_repository_status, restore_file, skip_rebase, stage_file, unstage_file,
+    apply_file_resolution, apply_resolutions, commit_changes, get_file_diff, get_repository_status,
+    mark_resolved, restore_file_conflict, restore_original_content, snapshot_file_conflict,
 };
+use crate::tui::keymap::{Action, KeyMode};
 
 /// Handle keyboard events
 pub fn handle_events(state: &mut AppState) -> Result<()> {
     if event::poll(Duration::from_millis(100))? {
-        if let Event::Key(key) = event::read()? {
-            if key.kind == KeyEventKind::Press {
-                handle_key_event(state, key)?;
+        match event::read()? {
+            // A failing action shouldn't end the session: report it and carry on
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                if let Err(e) = handle_key_event(state, key) {
+                    state.notify(Notification::error("Action failed".to_string(), &e, None));
+                }
+            }
+            // Bracketed paste delivers the whole text at once
+            Event::Paste(text) => {
+                if let Some(editor) = state.editor.as_mut() {
+                    editor.insert_str(&text);
+                }
             }
+            _ => {}
         }
     }
     Ok(())
 }
 
 fn handle_key_event(state: &mut AppState, key: KeyEvent) -> Result<()> {
+    state.status_message = None;
+
+    // An error notification takes the keys until it is dismissed
+    if state.notification.is_some() {
+        return handle_notification_keys(state, key);
+    }
+
     // Handle commit modal first (highest priority)
     if state.show_commit_modal {
         return handle_commit_modal_keys(state, key);
     }
 
+    // The inline hunk editor captures all keys while open
+    if state.is_editing() {
+        return handle_hunk_editor_keys(state, key);
+    }
+
+    // So does the line picker
+    if state.is_picking_lines() {
+        return handle_line_picker_keys(state, key);
+    }
+
     match state.view_mode {
         ViewMode::SplitPane { .. } => {
             if state.is_staging_mode() {
@@ -39,20 +67,51 @@ fn handle_key_event(state: &mut AppState, key: KeyEvent) -> Result<()> {
     }
 }
 
+fn handle_notification_keys(state: &mut AppState, key: KeyEvent) -> Result<()> {
+    match state.config.keys.action(KeyMode::Notification, &key) {
+        Some(Action::Retry) => {
+            if let Some(action) = state.dismiss_notification().and_then(|n| n.retry) {
+                run_git_action(state, action)?;
+            }
+        }
+        Some(Action::Dismiss) => {
+            state.dismiss_notification();
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Run a git command, reporting a failure in a notification that offers a retry
+fn run_git_action(state: &mut AppState, action: GitAction) -> Result<()> {
+    if let Err(e) = (state.git_runner)(&action) {
+        state.notify(Notification::git_failure(action, &e));
+        return Ok(());
+    }
+
+    match action {
+        GitAction::ContinueRebase | GitAction::AbortRebase | GitAction::SkipRebase => {
+            state.quit();
+        }
+        GitAction::Stage(_) | GitAction::Unstage(_) | GitAction::Restore(_) => {
+            refresh_file_statuses(state)?;
+        }
+    }
+    Ok(())
+}
+
 fn handle_commit_modal_keys(state: &mut AppState, key: KeyEvent) -> Result<()> {
     match key.code {
-        KeyCode::Enter => {
-            // Submit commit
-            if !state.commit_message.is_empty() {
-                match commit_changes(&state.commit_message) {
-                    Ok(_) => {
-                        state.close_commit_modal();
-                        // Refresh file statuses after commit
-                        refresh_file_statuses(state)?;
-                    }
-                    Err(e) => {
-                        state.set_commit_error(format!("Commit failed: {}", e));
-                    }
+        // Submit commit
+        KeyCode::Enter if !state.commit_message.is_empty() => {
+            match commit_changes(&state.commit_message, &state.config.commit) {
+                Ok(_) => {
+                    state.close_commit_modal();
+                    // Refresh file statuses after commit
+                    refresh_file_statuses(state)?;
+                }
+                Err(e) => {
+                    state.set_commit_error(format!("Commit failed: {}", e));
                 }
             }
         }
@@ -70,61 +129,92 @@ fn handle_commit_modal_keys(state: &mut AppState, key: KeyEvent) -> Result<()> {
     Ok(())
 }
 
-fn handle_staging_keys(state: &mut AppState, key: KeyEvent) -> Result<()> {
-    // If help dialog is open, only handle Esc or ? to close it
-    if state.show_help {
-        match key.code {
-            KeyCode::Esc | KeyCode::Char('?') | KeyCode::Char('q') => {
-                state.toggle_help();
-            }
-            _ => {}
+fn handle_hunk_editor_keys(state: &mut AppState, key: KeyEvent) -> Result<()> {
+    // Save with Ctrl+s
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        if key.code == KeyCode::Char('s') {
+            state.save_hunk_editor();
+            auto_save_if_resolved(state)?;
         }
         return Ok(());
     }
 
-    // Global keys
+    let editor = match state.editor.as_mut() {
+        Some(editor) => editor,
+        None => return Ok(()),
+    };
+
     match key.code {
-        KeyCode::Char('q') => {
-            state.quit();
-            return Ok(());
-        }
-        KeyCode::Char('?') => {
-            state.toggle_help();
-            return Ok(());
+        KeyCode::Esc => state.cancel_hunk_editor(),
+        KeyCode::Enter => editor.insert_newline(),
+        KeyCode::Backspace => editor.backspace(),
+        KeyCode::Delete => editor.delete(),
+        KeyCode::Left => editor.move_left(),
+        KeyCode::Right => editor.move_right(),
+        KeyCode::Up => editor.move_up(),
+        KeyCode::Down => editor.move_down(),
+        KeyCode::Home => editor.move_home(),
+        KeyCode::End => editor.move_end(),
+        KeyCode::Tab => editor.insert_char('\t'),
+        KeyCode::Char(c) => editor.insert_char(c),
+        _ => {}
+    }
+    Ok(())
+}
+
+fn handle_line_picker_keys(state: &mut AppState, key: KeyEvent) -> Result<()> {
+    let picker = match state.line_picker.as_mut() {
+        Some(picker) => picker,
+        None => return Ok(()),
+    };
+
+    match key.code {
+        KeyCode::Esc => state.cancel_line_picker(),
+        KeyCode::Enter => {
+            state.save_line_picker();
+            auto_save_if_resolved(state)?;
         }
-        KeyCode::Tab => {
+        KeyCode::Char(' ') => picker.toggle(),
+        KeyCode::Char('j') | KeyCode::Down => picker.move_down(),
+        KeyCode::Char('k') | KeyCode::Up => picker.move_up(),
+        _ => {}
+    }
+    Ok(())
+}
+
+fn handle_staging_keys(state: &mut AppState, key: KeyEvent) -> Result<()> {
+    // If help dialog is open, only handle the keys that close it
+    if state.show_help {
+        handle_help_keys(state, KeyMode::Staging, key);
+        return Ok(());
+    }
+
+    let action = match state.config.keys.action(KeyMode::Staging, &key) {
+        Some(action) => action,
+        None => return Ok(()),
+    };
+
+    match action {
+        Action::Quit => state.quit(),
+        Action::Help => state.toggle_help(),
+        Action::ToggleFocus => {
             state.toggle_focus();
             // Load diff when focusing on code view
             if state.focus == PaneFocus::CodeView {
                 load_current_file_diff(state)?;
             }
-            return Ok(());
         }
-        _ => {}
-    }
-
-    // Handle Ctrl+d and Ctrl+u for scrolling
-    if key.modifiers.contains(KeyModifiers::CONTROL) {
-        match key.code {
-            KeyCode::Char('d') => {
-                for _ in 0..10 {
-                    state.scroll_down();
-                }
-                return Ok(());
+        Action::HalfPageDown => {
+            for _ in 0..10 {
+                state.scroll_down();
             }
-            KeyCode::Char('u') => {
-                for _ in 0..10 {
-                    state.scroll_up();
-                }
-                return Ok(());
+        }
+        Action::HalfPageUp => {
+            for _ in 0..10 {
+                state.scroll_up();
             }
-            _ => {}
         }
-    }
-
-    // Staging-specific keys
-    match key.code {
-        KeyCode::Char('j') | KeyCode::Down => {
+        Action::Down => {
             if state.focus == PaneFocus::FileList {
                 state.move_selection_down_unified();
                 load_current_file_diff(state)?;
@@ -132,7 +222,7 @@ fn handle_staging_keys(state: &mut AppState, key: KeyEvent) -> Result<()> {
                 state.scroll_down();
             }
         }
-        KeyCode::Char('k') | KeyCode::Up => {
+        Action::Up => {
             if state.focus == PaneFocus::FileList {
                 state.move_selection_up_unified();
                 load_current_file_diff(state)?;
@@ -140,44 +230,28 @@ fn handle_staging_keys(state: &mut AppState, key: KeyEvent) -> Result<()> {
                 state.scroll_up();
             }
         }
-        KeyCode::Char('a') => {
-            // Stage file
+        Action::Stage => {
             if let Some(file_status) = state.current_file_status() {
                 let path = file_status.path.to_string_lossy().to_string();
-                if let Err(e) = stage_file(&path) {
-                    eprintln!("Failed to stage file: {}", e);
-                } else {
-                    refresh_file_statuses(state)?;
-                }
+                run_git_action(state, GitAction::Stage(path))?;
             }
         }
-        KeyCode::Char('s') => {
-            // Unstage file
+        Action::Unstage => {
             if let Some(file_status) = state.current_file_status() {
                 let path = file_status.path.to_string_lossy().to_string();
-                if let Err(e) = unstage_file(&path) {
-                    eprintln!("Failed to unstage file: {}", e);
-                } else {
-                    refresh_file_statuses(state)?;
-                }
+                run_git_action(state, GitAction::Unstage(path))?;
             }
         }
-        KeyCode::Char('r') => {
-            // Restore file (discard changes)
+        Action::Restore => {
+            // Discard the file's changes
             if let Some(file_status) = state.current_file_status() {
                 let path = file_status.path.to_string_lossy().to_string();
-                if let Err(e) = restore_file(&path) {
-                    eprintln!("Failed to restore file: {}", e);
-                } else {
-                    refresh_file_statuses(state)?;
-                }
+                run_git_action(state, GitAction::Restore(path))?;
             }
         }
-        KeyCode::Char('c') => {
-            // Open commit modal (only if there are staged files)
-            if state.has_staged_files() {
-                state.open_commit_modal();
-            }
+        // Open commit modal (only if there are staged files)
+        Action::Commit if state.has_staged_files() => {
+            state.open_commit_modal();
         }
         _ => {}
     }
@@ -186,150 +260,274 @@ fn handle_staging_keys(state: &mut AppState, key: KeyEvent) -> Result<()> {
 }
 
 fn handle_split_pane_keys(state: &mut AppState, key: KeyEvent) -> Result<()> {
-    // If help dialog is open, only handle Esc or ? to close it
+    // If help dialog is open, only handle the keys that close it
     if state.show_help {
-        match key.code {
-            KeyCode::Esc | KeyCode::Char('?') | KeyCode::Char('q') => {
-                state.toggle_help();
-            }
-            _ => {}
-        }
+        handle_help_keys(state, KeyMode::Conflict, key);
         return Ok(());
     }
 
-    // Global keys that work regardless of focus
-    match key.code {
-        KeyCode::Char('q') => {
+    let action = match state.config.keys.action(KeyMode::Conflict, &key) {
+        Some(action) => action,
+        None => return Ok(()),
+    };
+
+    // Actions that work regardless of focus
+    match action {
+        Action::Quit => {
             state.quit();
             return Ok(());
         }
-        KeyCode::Char('?') => {
+        Action::Help => {
             state.toggle_help();
             return Ok(());
         }
-        KeyCode::Tab => {
+        Action::ToggleFocus => {
             state.toggle_focus();
             return Ok(());
         }
+        // Redo the last undone resolution
+        Action::Redo => {
+            if let Some(file_index) = state.redo() {
+                auto_save(state, file_index)?;
+            }
+            return Ok(());
+        }
+        // Undo the last resolution (and the file write it caused)
+        Action::Undo => {
+            if let Some(file_index) = state.undo() {
+                auto_save(state, file_index)?;
+            }
+            return Ok(());
+        }
+        // Write every file's resolutions now (the only way with auto-save off)
+        Action::WriteFiles => {
+            for file_index in 0..state.files.len() {
+                sync_file_to_disk(state, file_index)?;
+            }
+            state.status_message = Some("Wrote resolved files".to_string());
+            return Ok(());
+        }
         _ => {}
     }
 
-    // Keys specific to current focus
+    // Actions specific to current focus
     match state.focus {
-        PaneFocus::FileList => handle_file_list_focus_keys(state, key),
-        PaneFocus::CodeView => handle_code_view_focus_keys(state, key),
+        PaneFocus::FileList => handle_file_list_focus_action(state, action),
+        PaneFocus::CodeView => handle_code_view_focus_action(state, action),
     }
 }
 
-fn handle_file_list_focus_keys(state: &mut AppState, key: KeyEvent) -> Result<()> {
-    match key.code {
-        KeyCode::Char('j') | KeyCode::Down => {
-            state.move_selection_down();
-        }
-        KeyCode::Char('k') | KeyCode::Up => {
-            state.move_selection_up();
-        }
+/// Close the help dialog on Esc or the help/quit keys of `mode`
+fn handle_help_keys(state: &mut AppState, mode: KeyMode, key: KeyEvent) {
+    let action = state.config.keys.action(mode, &key);
+    if key.code == KeyCode::Esc || matches!(action, Some(Action::Help | Action::Quit)) {
+        state.toggle_help();
+    }
+}
+
+fn handle_file_list_focus_action(state: &mut AppState, action: Action) -> Result<()> {
+    match action {
+        Action::Down => state.move_selection_down(),
+        Action::Up => state.move_selection_up(),
         _ => {}
     }
     Ok(())
 }
 
-fn handle_code_view_focus_keys(state: &mut AppState, key: KeyEvent) -> Result<()> {
-    // Handle Ctrl+d and Ctrl+u for scrolling (half page)
-    if key.modifiers.contains(KeyModifiers::CONTROL) {
-        match key.code {
-            KeyCode::Char('d') => {
-                // Scroll down (half page)
-                for _ in 0..10 {
-                    state.scroll_down();
-                }
-                return Ok(());
+fn handle_code_view_focus_action(state: &mut AppState, action: Action) -> Result<()> {
+    match action {
+        Action::HalfPageDown => {
+            for _ in 0..10 {
+                state.scroll_down();
             }
-            KeyCode::Char('u') => {
-                // Scroll up (half page)
-                for _ in 0..10 {
-                    state.scroll_up();
-                }
-                return Ok(());
+        }
+        Action::HalfPageUp => {
+            for _ in 0..10 {
+                state.scroll_up();
             }
-            _ => {}
         }
-    }
-
-    match key.code {
-        KeyCode::Char('j') | KeyCode::Down => {
+        Action::Down => {
             // Scroll down one line
             state.scroll_down();
         }
-        KeyCode::Char('k') | KeyCode::Up => {
+        Action::Up => {
             // Scroll up one line
             state.scroll_up();
         }
-        KeyCode::Char('n') => {
+        Action::NextConflict => {
             // Navigate to next conflict in current file
             state.next_conflict();
         }
-        KeyCode::Char('p') => {
+        Action::PreviousConflict => {
             // Navigate to previous conflict in current file
             state.previous_conflict();
         }
-        KeyCode::Char('c') => {
-            // Set resolution for current conflict
-            state.set_current_resolution(Resolution::Current);
+        Action::TakeCurrent => {
+            // Set resolution for current conflict (or keep our version of the file)
+            if state.current_file_is_file_conflict() {
+                state.set_current_file_resolution(FileResolution::KeepOurs);
+            } else {
+                state.set_current_resolution(Resolution::Current);
+            }
             // Auto-save after resolution
             auto_save_if_resolved(state)?;
         }
-        KeyCode::Char('i') => {
-            // Set resolution for current conflict
-            state.set_current_resolution(Resolution::Incoming);
+        Action::TakeIncoming => {
+            // Set resolution for current conflict (or keep their version of the file)
+            if state.current_file_is_file_conflict() {
+                state.set_current_file_resolution(FileResolution::KeepTheirs);
+            } else {
+                state.set_current_resolution(Resolution::Incoming);
+            }
             // Auto-save after resolution
             auto_save_if_resolved(state)?;
         }
-        KeyCode::Char('b') => {
+        // Delete the file (whole-file conflicts only)
+        Action::DeleteFile if state.current_file_is_file_conflict() => {
+            state.set_current_file_resolution(FileResolution::Delete);
+            auto_save_if_resolved(state)?;
+        }
+        Action::TakeBoth => {
             // Set resolution for current conflict
             state.set_current_resolution(Resolution::Both);
             // Auto-save after resolution
             auto_save_if_resolved(state)?;
         }
-        KeyCode::Char('u') => {
-            // Clear resolution for current conflict (undo)
+        // Hand-merge the current conflict, starting from the current side
+        // (or from the previous hand-merged text)
+        Action::EditHunk if !state.current_file_is_file_conflict() => {
+            let seed = match state.current_resolution() {
+                Some(Resolution::Custom(text)) => Resolution::Custom(text.clone()),
+                _ => Resolution::Current,
+            };
+            state.open_hunk_editor(seed);
+        }
+        // Hand-merge the current conflict, starting from the incoming side
+        Action::EditHunkIncoming if !state.current_file_is_file_conflict() => {
+            state.open_hunk_editor(Resolution::Incoming);
+        }
+        // Pick single lines of each side of the current conflict
+        Action::PickLines if !state.current_file_is_file_conflict() => {
+            state.open_line_picker();
+        }
+        // Hand-merge the current conflict in $VISUAL/$EDITOR
+        Action::ExternalEdit => {
+            state.request_external_edit();
+        }
+        Action::ToggleLayout => {
+            // Switch between unified and ours | base | theirs layouts
+            state.toggle_code_layout();
+        }
+        Action::TogglePreview => {
+            // Show the file as it would be written
+            state.toggle_result_preview();
+        }
+        // Confirm the automatic resolutions of this file (and save it if that completes it)
+        Action::AcceptAuto if !state.current_file_is_file_conflict() => {
+            let count = state.accept_auto_resolutions();
+            if count > 0 {
+                state.set_status_message(format!(
+                    "Accepted {} automatic resolution{}",
+                    count,
+                    if count == 1 { "" } else { "s" }
+                ));
+                auto_save_if_resolved(state)?;
+            }
+        }
+        Action::ClearResolution => {
+            // Clear resolution for current conflict
             state.clear_current_resolution();
+            auto_save_if_resolved(state)?;
         }
         _ => {}
     }
     Ok(())
 }
 
-fn auto_save_if_resolved(state: &mut AppState) -> Result<()> {
-    if let Some(file) = state.current_file() {
-        if file.is_fully_resolved() {
-            apply_resolutions(file)?;
+pub(crate) fn auto_save_if_resolved(state: &mut AppState) -> Result<()> {
+    auto_save(state, state.selected_file)
+}
+
+/// Sync a file to disk after a resolution change, unless auto-save is off
+fn auto_save(state: &mut AppState, file_index: usize) -> Result<()> {
+    if !state.config.auto_save {
+        return Ok(());
+    }
+    sync_file_to_disk(state, file_index)
+}
 
-            // If all files are resolved and it's a rebase, show rebase actions
-            if state.all_files_resolved() && state.git_operation.is_rebase() {
-                state.go_to_rebase_actions();
+/// Make the file on disk match its resolutions
+///
+/// Fully resolved files get their resolutions written (and staged with
+/// auto-stage on); files saki already wrote go back to their conflicted state,
+/// index stages included, when a resolution is undone or cleared.
+fn sync_file_to_disk(state: &mut AppState, file_index: usize) -> Result<()> {
+    let file = match state.files.get(file_index) {
+        Some(file) => file,
+        None => return Ok(()),
+    };
+
+    if file.is_file_conflict() {
+        let repo = crate::git::detector::open_repository()?;
+        if let Some(snapshot) = state.conflict_snapshots.remove(&file.path) {
+            restore_file_conflict(&repo, &snapshot)?;
+        }
+        if file.is_fully_resolved() {
+            let snapshot = snapshot_file_conflict(&repo, file)?;
+            apply_file_resolution(&repo, file, true)?;
+            state.conflict_snapshots.insert(file.path.clone(), snapshot);
+        }
+    } else if file.is_fully_resolved() {
+        if state.config.auto_stage && !state.conflict_snapshots.contains_key(&file.path) {
+            // Keep the conflicted index stages so undo can bring them back
+            let repo = crate::git::detector::open_repository()?;
+            let snapshot = snapshot_file_conflict(&repo, file)?;
+            state.conflict_snapshots.insert(file.path.clone(), snapshot);
+        }
+        apply_resolutions(file)?;
+        state.written_files.insert(file.path.clone());
+        if state.config.auto_stage {
+            let repo = crate::git::detector::open_repository()?;
+            mark_resolved(&repo, &file.path)?;
+        }
+    } else if state.written_files.remove(&file.path) {
+        match state.conflict_snapshots.remove(&file.path) {
+            Some(snapshot) => {
+                let repo = crate::git::detector::open_repository()?;
+                restore_file_conflict(&repo, &snapshot)?;
             }
+            None => restore_original_content(file)?,
         }
     }
+
+    // If all files are resolved and it's a rebase, show rebase actions
+    if state.all_files_resolved() && state.git_operation.is_rebase() {
+        state.go_to_rebase_actions();
+    }
     Ok(())
 }
 
 fn handle_rebase_actions_keys(state: &mut AppState, key: KeyEvent) -> Result<()> {
-    match key.code {
-        KeyCode::Char('q') | KeyCode::Esc => {
+    if state.show_help {
+        handle_help_keys(state, KeyMode::Rebase, key);
+        return Ok(());
+    }
+
+    match state.config.keys.action(KeyMode::Rebase, &key) {
+        Some(Action::Quit) => {
             state.quit();
         }
-        KeyCode::Char('c') => {
-            continue_rebase()?;
-            state.quit();
+        Some(Action::Help) => {
+            state.toggle_help();
         }
-        KeyCode::Char('a') => {
-            abort_rebase()?;
-            state.quit();
+        Some(Action::ContinueRebase) => {
+            run_git_action(state, GitAction::ContinueRebase)?;
         }
-        KeyCode::Char('s') => {
-            skip_rebase()?;
-            state.quit();
+        Some(Action::AbortRebase) => {
+            run_git_action(state, GitAction::AbortRebase)?;
+        }
+        Some(Action::SkipCommit) => {
+            run_git_action(state, GitAction::SkipRebase)?;
         }
         _ => {}
     }
@@ -377,3 +575,64 @@ fn refresh_file_statuses(state: &mut AppState) -> Result<()> {
     load_current_file_diff(state)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    /// Stand-in for git that fails every command the way a missing path does
+    fn failing_git(action: &GitAction) -> Result<()> {
+        anyhow::bail!(
+            "git add failed: pathspec did not match any files ({:?})",
+            action
+        )
+    }
+
+    #[test]
+    fn test_failed_git_action_can_be_retried() {
+        let mut state = AppState::new_staging(Vec::new());
+        state.git_runner = failing_git;
+        let action = GitAction::Stage("no/such/file.txt".to_string());
+
+        run_git_action(&mut state, action.clone()).unwrap();
+        let notification = state.notification.clone().unwrap();
+        assert_eq!(notification.title, "Stage no/such/file.txt failed");
+        assert!(notification.message.contains("git add failed"));
+        assert_eq!(notification.retry, Some(action.clone()));
+
+        // Other keys are swallowed while the notification is up
+        handle_key_event(&mut state, press('a')).unwrap();
+        assert_eq!(state.notification.as_ref(), Some(&notification));
+
+        // Retrying runs the command again, which fails the same way
+        state.notification.as_mut().unwrap().message = "stale".to_string();
+        handle_key_event(&mut state, press('r')).unwrap();
+        assert_eq!(state.notification.as_ref(), Some(&notification));
+
+        handle_key_event(&mut state, KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)).unwrap();
+        assert_eq!(state.notification, None);
+    }
+
+    #[test]
+    fn test_retry_key_follows_keymap() {
+        let mut state = AppState::new_staging(Vec::new());
+        state.git_runner = failing_git;
+        state.config.keys = toml::from_str("[notification]\nretry = \"R\"").unwrap();
+        let notification = Notification::git_failure(
+            GitAction::Stage("no/such/file.txt".to_string()),
+            &anyhow::anyhow!("stale"),
+        );
+        state.notify(notification.clone());
+
+        handle_key_event(&mut state, press('r')).unwrap();
+        assert_eq!(state.notification.as_ref(), Some(&notification));
+
+        handle_key_event(&mut state, press('R')).unwrap();
+        let retried = state.notification.unwrap();
+        assert!(retried.message.contains("git add failed"));
+    }
+}
@@ -0,0 +1,189 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::process::Command;
+
+use crate::app::AppState;
+use crate::domain::{ConflictHunk, Resolution};
+use crate::git::is_conflict_marker_line;
+
+/// Get the user's editor command ($VISUAL, then $EDITOR, then vi)
+pub fn editor_command() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+/// Build the text handed to the editor: the previous hand-merged result, or both sides with markers
+///
+/// Markers are `marker_size` long like the file's, so marker-like content in the
+/// sides isn't mistaken for them.
+pub fn hunk_template(
+    conflict: &ConflictHunk,
+    resolution: Option<&Resolution>,
+    marker_size: usize,
+) -> String {
+    if let Some(Resolution::Custom(text)) = resolution {
+        return format!("{}\n", text);
+    }
+
+    let marker = |c: &str, label: &str| format!("{}{}\n", c.repeat(marker_size), label);
+    let mut template = marker("<", " CURRENT (HEAD)");
+    push_lines(&mut template, &conflict.current);
+    if let Some(base) = &conflict.base {
+        template.push_str(&marker("|", " BASE"));
+        push_lines(&mut template, base);
+    }
+    template.push_str(&marker("=", ""));
+    push_lines(&mut template, &conflict.incoming);
+    template.push_str(&marker(">", " INCOMING"));
+    template
+}
+
+fn push_lines(out: &mut String, text: &str) {
+    // Saved text gets the file's line endings back when it is applied
+    for line in text.lines() {
+        out.push_str(line.strip_suffix('\r').unwrap_or(line));
+        out.push('\n');
+    }
+}
+
+/// Turn the edited text into a resolution, rejecting leftover `marker_size` conflict markers
+pub fn parse_edited_hunk(edited: &str, marker_size: usize) -> Result<String> {
+    if let Some((number, _)) = edited
+        .lines()
+        .enumerate()
+        .find(|(_, line)| is_conflict_marker_line(line, marker_size))
+    {
+        anyhow::bail!(
+            "Edited hunk still contains conflict markers (line {})",
+            number + 1
+        );
+    }
+
+    let text = edited.strip_suffix('\n').unwrap_or(edited);
+    Ok(text.strip_suffix('\r').unwrap_or(text).to_string())
+}
+
+/// Open `text` in an editor and return the saved result
+///
+/// The command runs through `sh` like git does, so `$EDITOR` may contain arguments.
+pub fn edit_text(editor: &str, text: &str, extension: Option<&str>) -> Result<String> {
+    let suffix = extension.map(|ext| format!(".{}", ext)).unwrap_or_default();
+    let file = tempfile::Builder::new()
+        .prefix("saki-hunk-")
+        .suffix(&suffix)
+        .tempfile()
+        .context("Failed to create temporary file for editor")?;
+    fs::write(file.path(), text)
+        .with_context(|| format!("Failed to write {}", file.path().display()))?;
+
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$@\"", editor))
+        .arg(editor)
+        .arg(file.path())
+        .status()
+        .with_context(|| format!("Failed to run editor: {}", editor))?;
+
+    if !status.success() {
+        anyhow::bail!("Editor exited with {}", status);
+    }
+
+    fs::read_to_string(file.path())
+        .with_context(|| format!("Failed to read {}", file.path().display()))
+}
+
+/// Edit the current conflict in the user's editor and store the result as a custom resolution
+pub fn edit_current_hunk(state: &mut AppState) -> Result<()> {
+    let conflict_index = match state.current_conflict_index() {
+        Some(index) => index,
+        None => return Ok(()),
+    };
+    let file = match state.current_file() {
+        Some(file) => file,
+        None => return Ok(()),
+    };
+    let conflict = match file.conflicts.get(conflict_index) {
+        Some(conflict) => conflict,
+        None => return Ok(()),
+    };
+
+    let template = hunk_template(conflict, state.current_resolution(), file.marker_size);
+    let extension = file.path.extension().and_then(|e| e.to_str());
+    let edited = edit_text(&editor_command(), &template, extension)?;
+    let text = parse_edited_hunk(&edited, file.marker_size)?;
+
+    state.set_hunk_resolution(conflict_index, Resolution::Custom(text));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::DEFAULT_MARKER_SIZE;
+
+    #[test]
+    fn test_hunk_template_includes_both_sides_and_base() {
+        let hunk = ConflictHunk::new("ours".to_string(), "theirs".to_string(), 0, 6)
+            .with_base("base".to_string());
+        assert_eq!(
+            hunk_template(&hunk, None, DEFAULT_MARKER_SIZE),
+            "<<<<<<< CURRENT (HEAD)\nours\n||||||| BASE\nbase\n=======\ntheirs\n>>>>>>> INCOMING\n"
+        );
+    }
+
+    #[test]
+    fn test_hunk_template_uses_file_marker_size() {
+        let hunk = ConflictHunk::new("Title\n=======".to_string(), "theirs".to_string(), 0, 6);
+        let template = hunk_template(&hunk, None, 10);
+        assert!(template.starts_with("<<<<<<<<<< CURRENT (HEAD)\nTitle\n=======\n==========\n"));
+
+        // The heading underline is content, the template's own markers are not
+        assert_eq!(
+            parse_edited_hunk("Title\n=======\n", 10).unwrap(),
+            "Title\n======="
+        );
+        assert!(parse_edited_hunk(&template, 10).is_err());
+    }
+
+    #[test]
+    fn test_hunk_template_reuses_custom_text() {
+        let hunk = ConflictHunk::new("ours".to_string(), "theirs".to_string(), 0, 4);
+        let custom = Resolution::Custom("merged".to_string());
+        assert_eq!(
+            hunk_template(&hunk, Some(&custom), DEFAULT_MARKER_SIZE),
+            "merged\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_edited_hunk_strips_final_newline() {
+        assert_eq!(
+            parse_edited_hunk("a\nb\n", DEFAULT_MARKER_SIZE).unwrap(),
+            "a\nb"
+        );
+        assert_eq!(
+            parse_edited_hunk("a\r\n", DEFAULT_MARKER_SIZE).unwrap(),
+            "a"
+        );
+    }
+
+    #[test]
+    fn test_parse_edited_hunk_rejects_markers() {
+        let err = parse_edited_hunk("ours\n=======\ntheirs\n", DEFAULT_MARKER_SIZE).unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+    }
+
+    #[test]
+    fn test_edit_text_runs_editor_command() {
+        let edited = edit_text("printf 'merged\\n' >", "original\n", Some("rs")).unwrap();
+        assert_eq!(edited, "merged\n");
+    }
+
+    #[test]
+    fn test_edit_text_fails_when_editor_fails() {
+        assert!(edit_text("false", "original\n", None).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+use ratatui::text::Line;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::domain::{AutoResolution, ConflictedFile, Resolution};
+
+/// What a view's lines were built from; when it changes they are built again
+#[derive(Debug, Clone, PartialEq)]
+struct CacheKey {
+    conflict_index: usize,
+    resolutions: Vec<Option<Resolution>>,
+    auto_resolved: Vec<Option<AutoResolution>>,
+}
+
+impl CacheKey {
+    fn new(file: &ConflictedFile, conflict_index: usize) -> Self {
+        Self {
+            conflict_index,
+            resolutions: file.resolutions.clone(),
+            auto_resolved: file.auto_resolved.clone(),
+        }
+    }
+}
+
+/// What one view built for each file, kept until the file's resolutions or
+/// its current conflict change
+#[derive(Debug)]
+pub struct ViewCache<T> {
+    entries: RefCell<HashMap<PathBuf, (CacheKey, T)>>,
+}
+
+impl<T> Default for ViewCache<T> {
+    fn default() -> Self {
+        Self {
+            entries: RefCell::new(HashMap::new()),
+        }
+    }
+}
+
+impl<T: Clone> ViewCache<T> {
+    /// The value built for `file` at `conflict_index`, calling `build` only if
+    /// nothing is cached for that state of the file
+    pub fn get_or_build(
+        &self,
+        file: &ConflictedFile,
+        conflict_index: usize,
+        build: impl FnOnce() -> T,
+    ) -> T {
+        let key = CacheKey::new(file, conflict_index);
+        let mut entries = self.entries.borrow_mut();
+        if let Some((cached_key, value)) = entries.get(&file.path) {
+            if *cached_key == key {
+                return value.clone();
+            }
+        }
+
+        let value = build();
+        entries.insert(file.path.clone(), (key, value.clone()));
+        value
+    }
+}
+
+/// Highlighted lines of the code views
+///
+/// syntect is too slow to run over a whole file on every frame, so each view
+/// highlights a file once per change of its resolutions.
+#[derive(Debug, Default)]
+pub struct HighlightCache {
+    /// The unified view of the file
+    pub file_content: ViewCache<Vec<Line<'static>>>,
+    /// The file as it would be written
+    pub result_preview: ViewCache<Vec<Line<'static>>>,
+    /// The ours | base | theirs columns, and the row of the current conflict
+    pub three_way: ViewCache<([Vec<Line<'static>>; 3], usize)>,
+    /// The result of the current conflict below the three columns
+    pub three_way_result: ViewCache<Vec<Line<'static>>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ConflictHunk;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_view_cache_rebuilds_on_resolution_change() {
+        let mut file = ConflictedFile::new(
+            PathBuf::from("f.txt"),
+            vec![ConflictHunk::new("a".to_string(), "b".to_string(), 0, 4)],
+            String::new(),
+        );
+        let cache = ViewCache::default();
+        let builds = Cell::new(0);
+        let build = || {
+            builds.set(builds.get() + 1);
+            builds.get()
+        };
+
+        assert_eq!(cache.get_or_build(&file, 0, build), 1);
+        assert_eq!(cache.get_or_build(&file, 0, build), 1);
+
+        file.set_resolution(0, Resolution::Incoming);
+        assert_eq!(cache.get_or_build(&file, 0, build), 2);
+        assert_eq!(cache.get_or_build(&file, 0, build), 2);
+
+        file.clear_resolution(0);
+        assert_eq!(cache.get_or_build(&file, 0, build), 3);
+        assert_eq!(builds.get(), 3);
+    }
+}
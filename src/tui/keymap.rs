@@ -0,0 +1,762 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Something a key can do, named as in the `[keys.*]` config tables
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Action {
+    Quit,
+    Help,
+    ToggleFocus,
+    Down,
+    Up,
+    HalfPageDown,
+    HalfPageUp,
+    NextConflict,
+    PreviousConflict,
+    TakeCurrent,
+    TakeIncoming,
+    TakeBoth,
+    DeleteFile,
+    EditHunk,
+    EditHunkIncoming,
+    PickLines,
+    ExternalEdit,
+    ToggleLayout,
+    TogglePreview,
+    AcceptAuto,
+    ClearResolution,
+    Undo,
+    Redo,
+    WriteFiles,
+    Stage,
+    Unstage,
+    Restore,
+    Commit,
+    ContinueRebase,
+    AbortRebase,
+    SkipCommit,
+    Retry,
+    Dismiss,
+}
+
+impl fmt::Display for Action {
+    /// Name used in config files, e.g. `take-current`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, c) in format!("{:?}", self).chars().enumerate() {
+            if c.is_ascii_uppercase() && i > 0 {
+                write!(f, "-")?;
+            }
+            write!(f, "{}", c.to_ascii_lowercase())?;
+        }
+        Ok(())
+    }
+}
+
+/// Set of bindings in effect, following the screen saki is showing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyMode {
+    /// Resolving conflicts in the split pane
+    Conflict,
+    /// Staging files in the split pane
+    Staging,
+    /// Choosing what to do with a rebase once everything is resolved
+    Rebase,
+    /// An error notification is shown over the view
+    Notification,
+}
+
+/// A key with its Ctrl/Alt modifiers, e.g. `ctrl+r`, `E`, `down`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+
+    /// Whether a key press triggers this binding
+    ///
+    /// Shift is part of the character for letters (`E`), so it's only compared
+    /// for keys without one.
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        let mut modifiers =
+            key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        if matches!(key.code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        key.code == self.code && modifiers == self.modifiers
+    }
+}
+
+/// Named keys, as written in config files
+const KEY_NAMES: &[(&str, KeyCode)] = &[
+    ("space", KeyCode::Char(' ')),
+    ("tab", KeyCode::Tab),
+    ("backtab", KeyCode::BackTab),
+    ("enter", KeyCode::Enter),
+    ("esc", KeyCode::Esc),
+    ("backspace", KeyCode::Backspace),
+    ("delete", KeyCode::Delete),
+    ("insert", KeyCode::Insert),
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+    ("pageup", KeyCode::PageUp),
+    ("pagedown", KeyCode::PageDown),
+];
+
+impl FromStr for KeyBinding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            if let Some(key) = rest.strip_prefix('^').filter(|key| !key.is_empty()) {
+                modifiers |= KeyModifiers::CONTROL;
+                rest = key;
+            } else if lower.starts_with("ctrl+") || lower.starts_with("ctrl-") {
+                modifiers |= KeyModifiers::CONTROL;
+                rest = &rest[5..];
+            } else if lower.starts_with("alt+") || lower.starts_with("alt-") {
+                modifiers |= KeyModifiers::ALT;
+                rest = &rest[4..];
+            } else if lower.starts_with("shift+") || lower.starts_with("shift-") {
+                modifiers |= KeyModifiers::SHIFT;
+                rest = &rest[6..];
+            } else {
+                break;
+            }
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => {
+                let lower = rest.to_ascii_lowercase();
+                let named = KEY_NAMES.iter().find(|(name, _)| *name == lower);
+                match named {
+                    Some((_, code)) => *code,
+                    None => match lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                        Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                        _ => return Err(format!("unknown key '{}'", s)),
+                    },
+                }
+            }
+        };
+
+        // Shifted letters are written as the capital letter
+        if let KeyCode::Char(c) = code {
+            if modifiers.contains(KeyModifiers::SHIFT) {
+                modifiers.remove(KeyModifiers::SHIFT);
+                return Ok(Self::new(KeyCode::Char(c.to_ascii_uppercase()), modifiers));
+            }
+        }
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "^")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "M-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "S-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::BackTab => write!(f, "S-Tab"),
+            KeyCode::Delete => write!(f, "Del"),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// Bindings of one mode, in help order
+type Bindings = Vec<(Action, Vec<KeyBinding>)>;
+
+/// Keys of every mode, the defaults with the user's `[keys.*]` tables applied
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    conflict: Bindings,
+    staging: Bindings,
+    rebase: Bindings,
+    notification: Bindings,
+}
+
+/// Keys for one action in a config file: a single key or a list
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Keys {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// The `[keys]` config table
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct KeymapConfig {
+    conflict: BTreeMap<Action, Keys>,
+    staging: BTreeMap<Action, Keys>,
+    rebase: BTreeMap<Action, Keys>,
+    notification: BTreeMap<Action, Keys>,
+}
+
+impl<'de> Deserialize<'de> for Keymap {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let config = KeymapConfig::deserialize(deserializer)?;
+        let mut keymap = Self::default();
+        for (mode, overrides) in [
+            (KeyMode::Conflict, config.conflict),
+            (KeyMode::Staging, config.staging),
+            (KeyMode::Rebase, config.rebase),
+            (KeyMode::Notification, config.notification),
+        ] {
+            keymap
+                .rebind(mode, overrides)
+                .map_err(serde::de::Error::custom)?;
+        }
+        Ok(keymap)
+    }
+}
+
+fn keys(specs: &[&str]) -> Vec<KeyBinding> {
+    specs
+        .iter()
+        .map(|spec| spec.parse().expect("valid default key"))
+        .collect()
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        use Action::*;
+
+        let navigation = || {
+            vec![
+                (Down, keys(&["j", "down"])),
+                (Up, keys(&["k", "up"])),
+                (HalfPageDown, keys(&["ctrl+d"])),
+                (HalfPageUp, keys(&["ctrl+u"])),
+                (ToggleFocus, keys(&["tab"])),
+            ]
+        };
+        let actions = || vec![(Help, keys(&["?"])), (Quit, keys(&["q"]))];
+
+        let mut conflict = navigation();
+        conflict.extend([
+            (NextConflict, keys(&["n"])),
+            (PreviousConflict, keys(&["p"])),
+            (TakeCurrent, keys(&["c"])),
+            (TakeIncoming, keys(&["i"])),
+            (TakeBoth, keys(&["b"])),
+            (EditHunk, keys(&["e"])),
+            (EditHunkIncoming, keys(&["E"])),
+            (PickLines, keys(&["l"])),
+            (ExternalEdit, keys(&["o"])),
+            (DeleteFile, keys(&["d"])),
+            (ToggleLayout, keys(&["t"])),
+            (TogglePreview, keys(&["r"])),
+            (AcceptAuto, keys(&["a"])),
+            (ClearResolution, keys(&["x"])),
+            (Undo, keys(&["u"])),
+            (Redo, keys(&["ctrl+r"])),
+            (WriteFiles, keys(&["w"])),
+        ]);
+        conflict.extend(actions());
+
+        let mut staging = navigation();
+        staging.extend([
+            (Stage, keys(&["a"])),
+            (Unstage, keys(&["s"])),
+            (Restore, keys(&["r"])),
+            (Commit, keys(&["c"])),
+        ]);
+        staging.extend(actions());
+
+        let rebase = vec![
+            (ContinueRebase, keys(&["c"])),
+            (AbortRebase, keys(&["a"])),
+            (SkipCommit, keys(&["s"])),
+            (Help, keys(&["?"])),
+            (Quit, keys(&["q", "esc"])),
+        ];
+
+        let notification = vec![(Retry, keys(&["r"])), (Dismiss, keys(&["esc", "enter"]))];
+
+        Self {
+            conflict,
+            staging,
+            rebase,
+            notification,
+        }
+    }
+}
+
+impl Keymap {
+    fn bindings(&self, mode: KeyMode) -> &Bindings {
+        match mode {
+            KeyMode::Conflict => &self.conflict,
+            KeyMode::Staging => &self.staging,
+            KeyMode::Rebase => &self.rebase,
+            KeyMode::Notification => &self.notification,
+        }
+    }
+
+    /// Action a key press triggers in `mode`, if any
+    pub fn action(&self, mode: KeyMode, key: &KeyEvent) -> Option<Action> {
+        self.bindings(mode)
+            .iter()
+            .find(|(_, keys)| keys.iter().any(|binding| binding.matches(key)))
+            .map(|(action, _)| *action)
+    }
+
+    /// Keys bound to an action in `mode`
+    pub fn keys(&self, mode: KeyMode, action: Action) -> &[KeyBinding] {
+        self.bindings(mode)
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, keys)| keys.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Main key of an action, for hints (`None` if it's unbound)
+    pub fn label(&self, mode: KeyMode, action: Action) -> Option<String> {
+        self.keys(mode, action).first().map(|key| key.to_string())
+    }
+
+    /// Hint for accepting automatic resolutions, e.g. `a to accept` (`None` if unbound)
+    pub fn accept_auto_hint(&self) -> Option<String> {
+        self.label(KeyMode::Conflict, Action::AcceptAuto)
+            .map(|key| format!("{} to accept", key))
+    }
+
+    /// Hint for reviewing an automatic resolution, e.g. `a to accept, c/i/b to override`
+    ///
+    /// Unbound actions are left out; `None` if none of them have a key.
+    pub fn auto_resolution_hint(&self) -> Option<String> {
+        let overrides: Vec<String> = [Action::TakeCurrent, Action::TakeIncoming, Action::TakeBoth]
+            .iter()
+            .filter_map(|action| self.label(KeyMode::Conflict, *action))
+            .collect();
+        let overrides = if overrides.is_empty() {
+            None
+        } else {
+            Some(format!("{} to override", overrides.join("/")))
+        };
+        let parts: Vec<String> = self
+            .accept_auto_hint()
+            .into_iter()
+            .chain(overrides)
+            .collect();
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(", "))
+        }
+    }
+
+    /// Replace the keys of some actions, taking their keys away from other actions
+    fn rebind(&mut self, mode: KeyMode, overrides: BTreeMap<Action, Keys>) -> Result<(), String> {
+        let mode_name = format!("{:?}", mode).to_lowercase();
+        let mut claimed: Vec<(KeyBinding, Action)> = Vec::new();
+        let mut parsed = Vec::new();
+        for (action, spec) in overrides {
+            if !self.bindings(mode).iter().any(|(a, _)| *a == action) {
+                return Err(format!(
+                    "{} is not an action of [keys.{}]",
+                    action, mode_name
+                ));
+            }
+            let specs = match spec {
+                Keys::One(key) => vec![key],
+                Keys::Many(keys) => keys,
+            };
+            let mut bindings = Vec::new();
+            for spec in specs {
+                let binding: KeyBinding = spec.parse()?;
+                if let Some((_, other)) = claimed.iter().find(|(key, _)| *key == binding) {
+                    return Err(format!(
+                        "'{}' is bound to both {} and {} in [keys.{}]",
+                        spec, other, action, mode_name
+                    ));
+                }
+                claimed.push((binding, action));
+                bindings.push(binding);
+            }
+            parsed.push((action, bindings));
+        }
+
+        let table = match mode {
+            KeyMode::Conflict => &mut self.conflict,
+            KeyMode::Staging => &mut self.staging,
+            KeyMode::Rebase => &mut self.rebase,
+            KeyMode::Notification => &mut self.notification,
+        };
+        for (action, keys) in table.iter_mut() {
+            match parsed.iter().position(|(a, _)| a == action) {
+                Some(index) => *keys = parsed.swap_remove(index).1,
+                None => keys.retain(|key| !claimed.iter().any(|(claimed, _)| claimed == key)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Help modal contents for `mode`: titled sections of (keys, description)
+    ///
+    /// Ends with the keys of the editors and modals opened from the mode,
+    /// which can't be remapped.
+    pub fn help(&self, mode: KeyMode) -> Vec<(&'static str, Vec<(String, &'static str)>)> {
+        let (sections, fixed) = match mode {
+            KeyMode::Conflict => (CONFLICT_HELP, CONFLICT_FIXED_HELP),
+            KeyMode::Staging => (STAGING_HELP, STAGING_FIXED_HELP),
+            KeyMode::Rebase => (REBASE_HELP, &[][..]),
+            KeyMode::Notification => (&[][..], &[][..]),
+        };
+        let fixed = fixed.iter().map(|(title, entries)| {
+            let lines = entries
+                .iter()
+                .map(|(keys, description)| (keys.to_string(), *description))
+                .collect();
+            (*title, lines)
+        });
+        sections
+            .iter()
+            .map(|(title, entries)| {
+                let lines = entries
+                    .iter()
+                    .filter_map(|(actions, description)| {
+                        let labels: Vec<String> = actions
+                            .iter()
+                            .filter_map(|action| self.label(mode, *action))
+                            .collect();
+                        if labels.is_empty() {
+                            None
+                        } else {
+                            Some((labels.join("/"), *description))
+                        }
+                    })
+                    .collect();
+                (*title, lines)
+            })
+            .chain(fixed)
+            .collect()
+    }
+}
+
+type HelpSections = &'static [(&'static str, &'static [(&'static [Action], &'static str)])];
+
+const CONFLICT_HELP: HelpSections = &[
+    (
+        "Navigation",
+        &[
+            (&[Action::Down, Action::Up], "Move down/up"),
+            (
+                &[Action::HalfPageDown, Action::HalfPageUp],
+                "Half page down/up",
+            ),
+            (&[Action::ToggleFocus], "Switch focus"),
+            (
+                &[Action::NextConflict, Action::PreviousConflict],
+                "Next/previous conflict",
+            ),
+        ],
+    ),
+    (
+        "Conflict Resolution",
+        &[
+            (&[Action::TakeCurrent], "Choose current (HEAD)"),
+            (&[Action::TakeIncoming], "Choose incoming"),
+            (&[Action::TakeBoth], "Choose both"),
+            (
+                &[Action::EditHunk, Action::EditHunkIncoming],
+                "Edit hunk (from current/incoming)",
+            ),
+            (&[Action::PickLines], "Pick single lines of each side"),
+            (&[Action::ExternalEdit], "Edit hunk in $EDITOR"),
+            (&[Action::DeleteFile], "Delete file (whole-file conflicts)"),
+            (&[Action::ToggleLayout], "Toggle three-way layout"),
+            (&[Action::TogglePreview], "Toggle result preview"),
+            (&[Action::AcceptAuto], "Accept automatic resolutions"),
+            (&[Action::ClearResolution], "Clear resolution"),
+            (&[Action::Undo, Action::Redo], "Undo/redo"),
+            (&[Action::WriteFiles], "Write resolved files"),
+        ],
+    ),
+    (
+        "Actions",
+        &[
+            (&[Action::Help], "Toggle this help"),
+            (&[Action::Quit], "Quit"),
+        ],
+    ),
+];
+
+/// Help for keys that aren't in the keymap: (keys, description)
+type FixedHelpSections = &'static [(&'static str, &'static [(&'static str, &'static str)])];
+
+const CONFLICT_FIXED_HELP: FixedHelpSections = &[
+    (
+        "Hunk Editor (fixed keys)",
+        &[("^s", "Save as the resolution"), ("Esc", "Cancel")],
+    ),
+    (
+        "Line Picker (fixed keys)",
+        &[
+            ("Space", "Toggle line"),
+            ("Enter", "Save picked lines"),
+            ("Esc", "Cancel"),
+        ],
+    ),
+];
+
+const STAGING_FIXED_HELP: FixedHelpSections = &[(
+    "Commit Message (fixed keys)",
+    &[("Enter", "Commit"), ("Esc", "Cancel")],
+)];
+
+const REBASE_HELP: HelpSections = &[
+    (
+        "Rebase",
+        &[
+            (&[Action::ContinueRebase], "Continue rebase"),
+            (&[Action::AbortRebase], "Abort rebase"),
+            (&[Action::SkipCommit], "Skip current commit"),
+        ],
+    ),
+    (
+        "Actions",
+        &[
+            (&[Action::Help], "Toggle this help"),
+            (&[Action::Quit], "Quit"),
+        ],
+    ),
+];
+
+const STAGING_HELP: HelpSections = &[
+    (
+        "Navigation",
+        &[
+            (&[Action::Down, Action::Up], "Move down/up"),
+            (
+                &[Action::HalfPageDown, Action::HalfPageUp],
+                "Half page down/up",
+            ),
+            (&[Action::ToggleFocus], "Switch focus"),
+        ],
+    ),
+    (
+        "Staging",
+        &[
+            (&[Action::Stage], "Stage file"),
+            (&[Action::Unstage], "Unstage file"),
+            (&[Action::Restore], "Restore file (discard changes)"),
+            (&[Action::Commit], "Commit staged changes"),
+        ],
+    ),
+    (
+        "Actions",
+        &[
+            (&[Action::Help], "Toggle this help"),
+            (&[Action::Quit], "Quit"),
+        ],
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    fn parse_keymap(toml: &str) -> Result<Keymap, toml::de::Error> {
+        toml::from_str(toml)
+    }
+
+    #[test]
+    fn test_parse_key_bindings() {
+        let parse = |s: &str| s.parse::<KeyBinding>().unwrap();
+        assert_eq!(
+            parse("j"),
+            KeyBinding::new(KeyCode::Char('j'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            parse("ctrl+r"),
+            KeyBinding::new(KeyCode::Char('r'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(parse("^r"), parse("Ctrl-r"));
+        assert_eq!(parse("shift+e"), parse("E"));
+        assert_eq!(parse("PageDown").code, KeyCode::PageDown);
+        assert_eq!(parse("f5").code, KeyCode::F(5));
+        assert_eq!(parse("^").code, KeyCode::Char('^'));
+        assert!("hyper+x".parse::<KeyBinding>().is_err());
+        assert!("f13".parse::<KeyBinding>().is_err());
+
+        assert_eq!(parse("ctrl+r").to_string(), "^r");
+        assert_eq!(parse("space").to_string(), "Space");
+        assert_eq!(parse("down").to_string(), "Down");
+    }
+
+    #[test]
+    fn test_default_bindings() {
+        let keymap = Keymap::default();
+        let conflict = |code, modifiers| keymap.action(KeyMode::Conflict, &press(code, modifiers));
+
+        assert_eq!(
+            conflict(KeyCode::Char('u'), KeyModifiers::NONE),
+            Some(Action::Undo)
+        );
+        assert_eq!(
+            conflict(KeyCode::Char('u'), KeyModifiers::CONTROL),
+            Some(Action::HalfPageUp)
+        );
+        assert_eq!(
+            conflict(KeyCode::Char('E'), KeyModifiers::SHIFT),
+            Some(Action::EditHunkIncoming)
+        );
+        assert_eq!(
+            conflict(KeyCode::Down, KeyModifiers::NONE),
+            Some(Action::Down)
+        );
+        assert_eq!(conflict(KeyCode::Char('s'), KeyModifiers::NONE), None);
+        assert_eq!(
+            keymap.action(
+                KeyMode::Staging,
+                &press(KeyCode::Char('s'), KeyModifiers::NONE)
+            ),
+            Some(Action::Unstage)
+        );
+        assert_eq!(
+            keymap.action(KeyMode::Rebase, &press(KeyCode::Esc, KeyModifiers::NONE)),
+            Some(Action::Quit)
+        );
+        assert_eq!(
+            keymap.action(
+                KeyMode::Notification,
+                &press(KeyCode::Enter, KeyModifiers::NONE)
+            ),
+            Some(Action::Dismiss)
+        );
+    }
+
+    #[test]
+    fn test_rebind_takes_keys_from_other_actions() {
+        let keymap = parse_keymap(
+            r#"
+            [staging]
+            stage = "space"
+            unstage = ["u", "a"]
+            "#,
+        )
+        .unwrap();
+        let staging = |c| {
+            keymap.action(
+                KeyMode::Staging,
+                &press(KeyCode::Char(c), KeyModifiers::NONE),
+            )
+        };
+
+        assert_eq!(staging(' '), Some(Action::Stage));
+        assert_eq!(staging('a'), Some(Action::Unstage));
+        assert_eq!(staging('s'), None);
+        assert_eq!(
+            keymap.label(KeyMode::Staging, Action::Unstage).as_deref(),
+            Some("u")
+        );
+        // Other modes keep their defaults
+        assert_eq!(
+            keymap
+                .label(KeyMode::Conflict, Action::AcceptAuto)
+                .as_deref(),
+            Some("a")
+        );
+    }
+
+    #[test]
+    fn test_help_follows_keymap() {
+        let keymap = parse_keymap("[conflict]\nundo = \"z\"\nnext-conflict = []").unwrap();
+        let help = keymap.help(KeyMode::Conflict);
+
+        assert_eq!(help[0].0, "Navigation");
+        assert_eq!(help[0].1[0], ("j/k".to_string(), "Move down/up"));
+        assert!(help[0]
+            .1
+            .contains(&("p".to_string(), "Next/previous conflict")));
+        assert!(help[1].1.contains(&("z/^r".to_string(), "Undo/redo")));
+        assert!(keymap.help(KeyMode::Staging)[1]
+            .1
+            .contains(&("a".to_string(), "Stage file")));
+        assert_eq!(help.last().unwrap().0, "Line Picker (fixed keys)");
+    }
+
+    #[test]
+    fn test_rebase_help() {
+        let keymap = parse_keymap("[rebase]\nskip-commit = \"S\"").unwrap();
+        let help = keymap.help(KeyMode::Rebase);
+
+        assert_eq!(help[0].0, "Rebase");
+        assert_eq!(
+            help[0].1,
+            vec![
+                ("c".to_string(), "Continue rebase"),
+                ("a".to_string(), "Abort rebase"),
+                ("S".to_string(), "Skip current commit"),
+            ]
+        );
+        assert!(help[1].1.contains(&("q".to_string(), "Quit")));
+    }
+
+    #[test]
+    fn test_auto_resolution_hints_follow_keymap() {
+        let keymap = Keymap::default();
+        assert_eq!(
+            keymap.auto_resolution_hint().as_deref(),
+            Some("a to accept, c/i/b to override")
+        );
+
+        let keymap =
+            parse_keymap("[conflict]\naccept-auto = \"A\"\ntake-both = []\ntake-current = \"o\"")
+                .unwrap();
+        assert_eq!(keymap.accept_auto_hint().as_deref(), Some("A to accept"));
+        assert_eq!(
+            keymap.auto_resolution_hint().as_deref(),
+            Some("A to accept, o/i to override")
+        );
+
+        let keymap = parse_keymap(
+            "[conflict]\naccept-auto = []\ntake-current = []\ntake-incoming = []\ntake-both = []",
+        )
+        .unwrap();
+        assert_eq!(keymap.accept_auto_hint(), None);
+        assert_eq!(keymap.auto_resolution_hint(), None);
+    }
+
+    #[test]
+    fn test_invalid_keymaps() {
+        assert!(parse_keymap("[conflict]\nstage = \"s\"").is_err());
+        assert!(parse_keymap("[conflict]\nfly = \"f\"").is_err());
+        assert!(parse_keymap("[conflict]\nundo = \"hyper+z\"").is_err());
+        assert!(parse_keymap("[conflict]\nundo = \"z\"\nredo = \"z\"").is_err());
+        assert!(parse_keymap("[merge]\nundo = \"z\"").is_err());
+    }
+}
@@ -1,7 +1,11 @@
 pub mod app;
 pub mod colors;
 pub mod event;
+pub mod external_editor;
+pub mod highlight_cache;
+pub mod keymap;
 pub mod syntax;
+pub mod theme;
 pub mod views;
 
 pub use app::run_app;
@@ -1,18 +1,50 @@
-use ratatui::style::{Color, Style};
+use ratatui::style::{Color, Modifier, Style};
+use std::ops::Range;
 use std::path::Path;
-use syntect::parsing::{SyntaxReference, SyntaxSet};
+use std::sync::OnceLock;
+use syntect::highlighting::{
+    self, FontStyle, HighlightIterator, HighlightState, Highlighter, Theme, ThemeSet,
+};
+use syntect::parsing::{ParseState, ScopeStack, SyntaxReference, SyntaxSet};
 
-// Background color for code view
-pub const CODE_BG: Color = Color::Rgb(40, 40, 45); // Dark gray background
+use super::theme::theme;
+
+/// syntect theme used for code when the configured one is not bundled
+pub const DEFAULT_THEME: &str = "base16-ocean.dark";
+
+/// Grammars and themes are decoded once; views build a highlighter every frame
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn theme_set() -> &'static ThemeSet {
+    static THEME_SET: OnceLock<ThemeSet> = OnceLock::new();
+    THEME_SET.get_or_init(ThemeSet::load_defaults)
+}
 
 pub struct SyntaxHighlighter {
-    syntax_set: SyntaxSet,
+    syntax_set: &'static SyntaxSet,
+    theme: &'static Theme,
 }
 
 impl SyntaxHighlighter {
+    /// Highlighter using the syntax theme of the TUI theme
     pub fn new() -> Self {
-        let syntax_set = SyntaxSet::load_defaults_newlines();
-        Self { syntax_set }
+        Self::with_theme(&theme().syntax)
+    }
+
+    /// Highlighter using one of syntect's bundled themes (the default one if unknown)
+    pub fn with_theme(name: &str) -> Self {
+        let themes = &theme_set().themes;
+        let theme = themes
+            .get(name)
+            .or_else(|| themes.get(DEFAULT_THEME))
+            .expect("syntect bundles its default theme");
+        Self {
+            syntax_set: syntax_set(),
+            theme,
+        }
     }
 
     pub fn detect_syntax(&self, file_path: &Path) -> &SyntaxReference {
@@ -23,117 +55,207 @@ impl SyntaxHighlighter {
             .unwrap_or_else(|| self.syntax_set.find_syntax_plain_text())
     }
 
-    pub fn highlight_line(&self, line: &str, _syntax: &SyntaxReference) -> Vec<(Style, String)> {
-        // Use simple token-based highlighting with custom colors
-        let mut result = Vec::new();
+    /// Start highlighting a file; its lines must then be highlighted in order
+    pub fn highlight_lines<'a>(&'a self, syntax: &SyntaxReference) -> LineHighlighter<'a> {
+        let highlighter = Highlighter::new(self.theme);
+        let highlight_state = HighlightState::new(&highlighter, ScopeStack::new());
+        LineHighlighter {
+            syntax_set: self.syntax_set,
+            theme: self.theme,
+            highlighter,
+            parse_state: ParseState::new(syntax),
+            highlight_state,
+        }
+    }
+}
 
-        // Keywords for common languages
-        let keywords = [
-            "function", "const", "let", "var", "return", "if", "else", "for", "while", "fn", "pub",
-            "impl", "struct", "enum", "trait", "use", "mod", "def", "class", "import", "from",
-            "async", "await", "int", "string", "bool", "void", "char", "float", "double",
-        ];
-
-        let mut current_pos = 0;
-        let chars: Vec<char> = line.chars().collect();
-
-        while current_pos < chars.len() {
-            let remaining = &chars[current_pos..];
-
-            // Check for strings
-            if remaining[0] == '"' || remaining[0] == '\'' {
-                let quote = remaining[0];
-                let mut end_pos = 1;
-                while end_pos < remaining.len() && remaining[end_pos] != quote {
-                    if remaining[end_pos] == '\\' && end_pos + 1 < remaining.len() {
-                        end_pos += 2;
-                    } else {
-                        end_pos += 1;
-                    }
-                }
-                if end_pos < remaining.len() {
-                    end_pos += 1;
-                }
-                let string_text: String = remaining[..end_pos].iter().collect();
-                result.push((
-                    Style::default().fg(Color::Rgb(152, 195, 121)), // Green for strings
-                    string_text,
-                ));
-                current_pos += end_pos;
-                continue;
-            }
+/// Highlights consecutive lines, carrying the parse state across them so
+/// multi-line strings and comments are colored throughout
+///
+/// Cloning keeps the state, so the sides of a conflict can each be
+/// highlighted from where the file was before it.
+pub struct LineHighlighter<'a> {
+    syntax_set: &'a SyntaxSet,
+    theme: &'a Theme,
+    highlighter: Highlighter<'a>,
+    parse_state: ParseState,
+    highlight_state: HighlightState,
+}
 
-            // Check for comments
-            if current_pos + 1 < chars.len() && remaining[0] == '/' && remaining[1] == '/' {
-                let comment: String = remaining.iter().collect();
-                result.push((
-                    Style::default().fg(Color::Rgb(120, 120, 130)), // Gray for comments
-                    comment,
-                ));
-                break;
-            }
+impl Clone for LineHighlighter<'_> {
+    fn clone(&self) -> Self {
+        Self {
+            syntax_set: self.syntax_set,
+            theme: self.theme,
+            highlighter: Highlighter::new(self.theme),
+            parse_state: self.parse_state.clone(),
+            highlight_state: self.highlight_state.clone(),
+        }
+    }
+}
 
-            // Check for numbers
-            if remaining[0].is_ascii_digit() {
-                let mut end_pos = 0;
-                while end_pos < remaining.len()
-                    && (remaining[end_pos].is_ascii_digit() || remaining[end_pos] == '.')
-                {
-                    end_pos += 1;
-                }
-                let number: String = remaining[..end_pos].iter().collect();
-                result.push((
-                    Style::default().fg(Color::Rgb(209, 154, 102)), // Orange for numbers
-                    number,
-                ));
-                current_pos += end_pos;
-                continue;
-            }
+impl LineHighlighter<'_> {
+    /// Highlight the next line (without its line ending)
+    ///
+    /// The segments' text adds up to the line, minus a trailing `\r`.
+    pub fn highlight_line(&mut self, line: &str) -> Vec<(Style, String)> {
+        // CRLF files keep their `\r` in the model; it's never drawn
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        // The grammars expect lines with their newline
+        let with_newline = format!("{}\n", line);
 
-            // Check for keywords/identifiers
-            if remaining[0].is_alphabetic() || remaining[0] == '_' {
-                let mut end_pos = 0;
-                while end_pos < remaining.len()
-                    && (remaining[end_pos].is_alphanumeric() || remaining[end_pos] == '_')
-                {
-                    end_pos += 1;
-                }
-                let word: String = remaining[..end_pos].iter().collect();
-
-                let is_keyword = keywords.contains(&word.as_str());
-                let color = if is_keyword {
-                    Color::Rgb(198, 120, 221) // Purple for keywords
-                } else {
-                    Color::Rgb(220, 220, 255) // White for identifiers
-                };
-
-                result.push((Style::default().fg(color), word));
-                current_pos += end_pos;
-                continue;
-            }
+        let ops = match self.parse_state.parse_line(&with_newline, self.syntax_set) {
+            Ok(ops) => ops,
+            Err(_) => return vec![(Style::default(), line.to_string())],
+        };
+        let ranges: Vec<_> = HighlightIterator::new(
+            &mut self.highlight_state,
+            &ops,
+            &with_newline,
+            &self.highlighter,
+        )
+        .collect();
 
-            // Operators and punctuation
-            let ch = remaining[0];
-            let color = match ch {
-                '+' | '-' | '*' | '/' | '=' | '<' | '>' | '!' | '&' | '|' | '^' | '%' => {
-                    Color::Rgb(86, 182, 194) // Cyan for operators
-                }
-                '(' | ')' | '[' | ']' | '{' | '}' => {
-                    Color::Rgb(255, 198, 109) // Yellow for brackets
-                }
-                _ => Color::Rgb(220, 220, 255), // White for others
-            };
-
-            result.push((Style::default().fg(color), ch.to_string()));
-            current_pos += 1;
+        let mut result = Vec::new();
+        let mut offset = 0;
+        for (style, text) in ranges {
+            let end = (offset + text.len()).min(line.len());
+            if end > offset {
+                result.push((to_ratatui_style(style), line[offset..end].to_string()));
+            }
+            offset += text.len();
         }
-
         result
     }
 }
 
+/// Foreground and font style of a syntect style; backgrounds are left to the views
+fn to_ratatui_style(style: highlighting::Style) -> Style {
+    let foreground = style.foreground;
+    let mut result = Style::default().fg(Color::Rgb(foreground.r, foreground.g, foreground.b));
+    if style.font_style.contains(FontStyle::BOLD) {
+        result = result.add_modifier(Modifier::BOLD);
+    }
+    if style.font_style.contains(FontStyle::ITALIC) {
+        result = result.add_modifier(Modifier::ITALIC);
+    }
+    if style.font_style.contains(FontStyle::UNDERLINE) {
+        result = result.add_modifier(Modifier::UNDERLINED);
+    }
+    result
+}
+
+/// Give the parts of highlighted segments inside `changes` (byte ranges of the line) a background
+pub fn emphasize_changes(
+    segments: Vec<(Style, String)>,
+    changes: &[Range<usize>],
+    bg: Color,
+) -> Vec<(Style, String)> {
+    if changes.is_empty() {
+        return segments;
+    }
+
+    let changed = |offset: usize| changes.iter().any(|range| range.contains(&offset));
+    let mut result = Vec::new();
+    let mut offset = 0;
+
+    for (style, text) in segments {
+        let mut piece = String::new();
+        let mut piece_changed = changed(offset);
+        for c in text.chars() {
+            let is_changed = changed(offset);
+            if is_changed != piece_changed && !piece.is_empty() {
+                let piece_style = if piece_changed { style.bg(bg) } else { style };
+                result.push((piece_style, std::mem::take(&mut piece)));
+            }
+            piece_changed = is_changed;
+            piece.push(c);
+            offset += c.len_utf8();
+        }
+        if !piece.is_empty() {
+            let piece_style = if piece_changed { style.bg(bg) } else { style };
+            result.push((piece_style, piece));
+        }
+    }
+
+    result
+}
+
 impl Default for SyntaxHighlighter {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(segments: &[(Style, String)]) -> String {
+        segments.iter().map(|(_, text)| text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_highlight_keeps_line_text() {
+        let highlighter = SyntaxHighlighter::new();
+        let syntax = highlighter.detect_syntax(Path::new("main.py"));
+        assert_eq!(syntax.name, "Python");
+
+        let mut code = highlighter.highlight_lines(syntax);
+        let segments = code.highlight_line("def greet(name):\r");
+        assert_eq!(text(&segments), "def greet(name):");
+        assert!(segments.len() > 1);
+        assert!(code.highlight_line("").is_empty());
+    }
+
+    #[test]
+    fn test_highlight_carries_state_across_lines() {
+        let highlighter = SyntaxHighlighter::new();
+        let syntax = highlighter.detect_syntax(Path::new("lib.rs"));
+        let mut code = highlighter.highlight_lines(syntax);
+
+        let opening = code.highlight_line("/* a comment");
+        let inside = code.highlight_line("let x = 1;");
+        let after = code.highlight_line("*/ let x = 1;");
+
+        // Still inside the block comment: one comment-colored segment
+        assert_eq!(inside.len(), 1);
+        assert_eq!(inside[0].0, opening[0].0);
+        assert_ne!(after.last().unwrap().0, opening[0].0);
+    }
+
+    #[test]
+    fn test_cloned_highlighter_keeps_its_own_state() {
+        let highlighter = SyntaxHighlighter::new();
+        let syntax = highlighter.detect_syntax(Path::new("lib.rs"));
+        let mut code = highlighter.highlight_lines(syntax);
+        let plain = code.clone().highlight_line("let x = 1;");
+
+        let mut other = code.clone();
+        other.highlight_line("/* a comment");
+        // The comment opened in the clone doesn't leak into the original
+        assert_eq!(code.highlight_line("let x = 1;"), plain);
+        assert_ne!(other.highlight_line("let x = 1;"), plain);
+    }
+
+    #[test]
+    fn test_emphasize_changes_splits_segments() {
+        let plain = Style::default();
+        let keyword = Style::default().fg(Color::Magenta);
+        let segments = vec![(keyword, "let".to_string()), (plain, " é = 1;".to_string())];
+
+        let result = emphasize_changes(segments, &[2..6, 9..10], Color::Blue);
+
+        assert_eq!(
+            result,
+            vec![
+                (keyword, "le".to_string()),
+                (keyword.bg(Color::Blue), "t".to_string()),
+                (plain.bg(Color::Blue), " é".to_string()),
+                (plain, " = ".to_string()),
+                (plain.bg(Color::Blue), "1".to_string()),
+                (plain, ";".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,395 @@
+use anyhow::{Context, Result};
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::config::config_dir;
+
+/// Names of the built-in themes
+pub const BUILTIN_THEMES: [&str; 3] = ["dark", "light", "high-contrast"];
+
+/// Colors the TUI is drawn with
+///
+/// Theme files are TOML with the same keys, on top of a built-in theme:
+///
+/// ```toml
+/// base = "light"            # built-in theme to start from (default: dark)
+/// syntax = "InspiredGitHub" # syntect theme for code
+/// code_bg = "#fdf6e3"
+/// conflict_current_bg = "#dbe9ff"
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Theme {
+    /// syntect theme used to highlight code
+    pub syntax: String,
+    /// Stops of the title gradient, first to last
+    pub gradient: Vec<Color>,
+
+    // Accents
+    pub accent: Color,
+    pub accent_alt: Color,
+    pub heading: Color,
+
+    // Semantic colors
+    pub success: Color,
+    pub warning: Color,
+    pub error: Color,
+    pub info: Color,
+
+    // Panes and text
+    pub inactive_border: Color,
+    pub selected_bg: Color,
+    pub selected_fg: Color,
+    pub text: Color,
+    pub text_dim: Color,
+    pub text_bright: Color,
+    pub footer_bg: Color,
+    pub code_bg: Color,
+
+    // Conflict sides
+    pub conflict_current: Color,
+    pub conflict_incoming: Color,
+    pub conflict_both: Color,
+    pub conflict_base: Color,
+    pub conflict_marker: Color,
+    pub conflict_current_bg: Color,
+    pub conflict_incoming_bg: Color,
+    pub conflict_both_bg: Color,
+    pub conflict_base_bg: Color,
+
+    // Changed words inside a conflict side
+    pub conflict_current_changed_bg: Color,
+    pub conflict_incoming_changed_bg: Color,
+    pub conflict_base_changed_bg: Color,
+
+    // Diffs
+    pub diff_added: Color,
+    pub diff_removed: Color,
+    pub diff_hunk_header: Color,
+    pub diff_file_header: Color,
+    pub diff_context: Color,
+    pub diff_added_bg: Color,
+    pub diff_added_changed_bg: Color,
+    pub diff_removed_bg: Color,
+    pub diff_removed_changed_bg: Color,
+
+    // Resolutions
+    pub resolved_bg: Color,
+    pub auto_resolved: Color,
+    pub auto_resolved_bg: Color,
+    pub status_resolved: Color,
+    pub status_unresolved: Color,
+}
+
+impl Theme {
+    /// Murasaki's palette for dark terminals: cyan → blue → purple → magenta
+    pub fn dark() -> Self {
+        let cyan_bright = Color::Rgb(0, 255, 255);
+        let purple_bright = Color::Rgb(128, 0, 255);
+        let magenta_bright = Color::Rgb(255, 0, 255);
+        let success = Color::Rgb(0, 255, 200);
+        let error = Color::Rgb(255, 80, 120);
+        let info = Color::Rgb(100, 180, 255);
+        let text_dim = Color::Rgb(120, 120, 160);
+
+        Self {
+            syntax: "base16-ocean.dark".to_string(),
+            gradient: vec![
+                cyan_bright,
+                Color::Rgb(0, 128, 255),
+                purple_bright,
+                magenta_bright,
+            ],
+            accent: cyan_bright,
+            accent_alt: Color::Rgb(255, 105, 180),
+            heading: purple_bright,
+            success,
+            warning: Color::Rgb(255, 200, 0),
+            error,
+            info,
+            inactive_border: Color::Rgb(100, 100, 150),
+            selected_bg: Color::Rgb(40, 40, 80),
+            selected_fg: Color::Black,
+            text: Color::Rgb(220, 220, 255),
+            text_dim,
+            text_bright: Color::White,
+            footer_bg: Color::Rgb(60, 60, 70),
+            code_bg: Color::Rgb(40, 40, 45),
+            conflict_current: Color::Rgb(64, 224, 255),
+            conflict_incoming: magenta_bright,
+            conflict_both: purple_bright,
+            conflict_base: text_dim,
+            conflict_marker: Color::Rgb(65, 105, 225),
+            conflict_current_bg: Color::Rgb(0, 30, 80),
+            conflict_incoming_bg: Color::Rgb(80, 20, 20),
+            conflict_both_bg: Color::Rgb(60, 20, 80),
+            conflict_base_bg: Color::Rgb(50, 50, 60),
+            conflict_current_changed_bg: Color::Rgb(0, 70, 160),
+            conflict_incoming_changed_bg: Color::Rgb(150, 40, 40),
+            conflict_base_changed_bg: Color::Rgb(85, 85, 105),
+            diff_added: Color::Green,
+            diff_removed: Color::Red,
+            diff_hunk_header: Color::Cyan,
+            diff_file_header: Color::Yellow,
+            diff_context: Color::Gray,
+            diff_added_bg: Color::Rgb(20, 40, 20),
+            diff_added_changed_bg: Color::Rgb(40, 100, 40),
+            diff_removed_bg: Color::Rgb(50, 20, 20),
+            diff_removed_changed_bg: Color::Rgb(120, 40, 40),
+            resolved_bg: Color::Rgb(20, 60, 30),
+            auto_resolved: info,
+            auto_resolved_bg: Color::Rgb(20, 45, 70),
+            status_resolved: success,
+            status_unresolved: error,
+        }
+    }
+
+    /// Pale backgrounds and dark text for light terminals
+    pub fn light() -> Self {
+        let teal = Color::Rgb(0, 124, 145);
+        let blue = Color::Rgb(0, 87, 183);
+        let purple = Color::Rgb(106, 27, 154);
+        let magenta = Color::Rgb(173, 20, 87);
+        let success = Color::Rgb(27, 127, 59);
+        let warning = Color::Rgb(154, 103, 0);
+        let error = Color::Rgb(198, 40, 40);
+        let info = Color::Rgb(21, 101, 192);
+        let text_dim = Color::Rgb(110, 110, 135);
+
+        Self {
+            syntax: "InspiredGitHub".to_string(),
+            gradient: vec![teal, blue, purple, magenta],
+            accent: teal,
+            accent_alt: Color::Rgb(194, 24, 91),
+            heading: purple,
+            success,
+            warning,
+            error,
+            info,
+            inactive_border: Color::Rgb(160, 160, 184),
+            selected_bg: Color::Rgb(216, 216, 240),
+            selected_fg: Color::White,
+            text: Color::Rgb(36, 36, 58),
+            text_dim,
+            text_bright: Color::Black,
+            footer_bg: Color::Rgb(228, 228, 236),
+            code_bg: Color::Rgb(250, 250, 252),
+            conflict_current: blue,
+            conflict_incoming: magenta,
+            conflict_both: purple,
+            conflict_base: text_dim,
+            conflict_marker: Color::Rgb(57, 73, 171),
+            conflict_current_bg: Color::Rgb(220, 235, 255),
+            conflict_incoming_bg: Color::Rgb(255, 224, 230),
+            conflict_both_bg: Color::Rgb(238, 224, 250),
+            conflict_base_bg: Color::Rgb(236, 236, 242),
+            conflict_current_changed_bg: Color::Rgb(168, 204, 255),
+            conflict_incoming_changed_bg: Color::Rgb(255, 179, 193),
+            conflict_base_changed_bg: Color::Rgb(208, 208, 220),
+            diff_added: success,
+            diff_removed: error,
+            diff_hunk_header: teal,
+            diff_file_header: warning,
+            diff_context: Color::Rgb(85, 85, 106),
+            diff_added_bg: Color::Rgb(227, 246, 232),
+            diff_added_changed_bg: Color::Rgb(181, 230, 195),
+            diff_removed_bg: Color::Rgb(252, 228, 228),
+            diff_removed_changed_bg: Color::Rgb(245, 181, 181),
+            resolved_bg: Color::Rgb(224, 244, 228),
+            auto_resolved: info,
+            auto_resolved_bg: Color::Rgb(221, 235, 250),
+            status_resolved: success,
+            status_unresolved: error,
+        }
+    }
+
+    /// Pure colors on black, for low-vision use and poor displays
+    pub fn high_contrast() -> Self {
+        let cyan = Color::Rgb(0, 255, 255);
+        let magenta = Color::Rgb(255, 0, 255);
+        let yellow = Color::Rgb(255, 255, 0);
+        let green = Color::Rgb(0, 255, 0);
+        let red = Color::Rgb(255, 64, 64);
+        let white = Color::Rgb(255, 255, 255);
+        let silver = Color::Rgb(192, 192, 192);
+
+        Self {
+            syntax: "base16-eighties.dark".to_string(),
+            gradient: vec![cyan, yellow, magenta],
+            accent: yellow,
+            accent_alt: magenta,
+            heading: white,
+            success: green,
+            warning: yellow,
+            error: red,
+            info: cyan,
+            inactive_border: silver,
+            selected_bg: Color::Rgb(0, 0, 175),
+            selected_fg: Color::Black,
+            text: white,
+            text_dim: silver,
+            text_bright: white,
+            footer_bg: Color::Black,
+            code_bg: Color::Black,
+            conflict_current: cyan,
+            conflict_incoming: magenta,
+            conflict_both: yellow,
+            conflict_base: silver,
+            conflict_marker: yellow,
+            conflict_current_bg: Color::Rgb(0, 0, 95),
+            conflict_incoming_bg: Color::Rgb(95, 0, 0),
+            conflict_both_bg: Color::Rgb(63, 0, 95),
+            conflict_base_bg: Color::Rgb(38, 38, 38),
+            conflict_current_changed_bg: Color::Rgb(0, 0, 215),
+            conflict_incoming_changed_bg: Color::Rgb(175, 0, 0),
+            conflict_base_changed_bg: Color::Rgb(88, 88, 88),
+            diff_added: green,
+            diff_removed: red,
+            diff_hunk_header: cyan,
+            diff_file_header: yellow,
+            diff_context: white,
+            diff_added_bg: Color::Rgb(0, 51, 0),
+            diff_added_changed_bg: Color::Rgb(0, 135, 0),
+            diff_removed_bg: Color::Rgb(51, 0, 0),
+            diff_removed_changed_bg: Color::Rgb(175, 0, 0),
+            resolved_bg: Color::Rgb(0, 64, 0),
+            auto_resolved: cyan,
+            auto_resolved_bg: Color::Rgb(0, 0, 95),
+            status_resolved: green,
+            status_unresolved: red,
+        }
+    }
+
+    /// Look up a built-in theme by name
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            _ => None,
+        }
+    }
+
+    /// Load a theme given as a built-in name, a theme file path, or the name of
+    /// a file in `~/.config/saki/themes/` (without `.toml`)
+    pub fn load(spec: &str) -> Result<Self> {
+        if let Some(theme) = Self::builtin(spec) {
+            return Ok(theme);
+        }
+
+        let path = Path::new(spec);
+        if path.is_file() {
+            return Self::from_file(path);
+        }
+        if let Some(path) = themes_dir().map(|dir| dir.join(format!("{}.toml", spec))) {
+            if path.is_file() {
+                return Self::from_file(&path);
+            }
+        }
+
+        anyhow::bail!(
+            "Unknown theme '{}' (built-in themes: {}, or a path to a TOML theme file)",
+            spec,
+            BUILTIN_THEMES.join(", ")
+        )
+    }
+
+    /// Load a TOML theme file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read theme {}", path.display()))?;
+        Self::from_toml(&content).with_context(|| format!("Invalid theme {}", path.display()))
+    }
+
+    /// Parse a theme: the keys given override those of its `base` theme
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let mut overrides: toml::Table = content.parse()?;
+
+        let base = match overrides.remove("base") {
+            Some(toml::Value::String(name)) => Self::builtin(&name).with_context(|| {
+                format!(
+                    "Unknown base theme '{}' (expected one of: {})",
+                    name,
+                    BUILTIN_THEMES.join(", ")
+                )
+            })?,
+            Some(_) => anyhow::bail!("`base` must be the name of a built-in theme"),
+            None => Self::dark(),
+        };
+
+        let mut theme = toml::Table::try_from(base)?;
+        theme.extend(overrides);
+        Ok(toml::Value::Table(theme).try_into()?)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+/// Directory searched for theme files given by name
+fn themes_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("themes"))
+}
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// Draw the TUI with this theme (only the first call has an effect)
+pub fn set_theme(theme: Theme) {
+    let _ = THEME.set(theme);
+}
+
+/// Theme the TUI is drawn with: the one set at startup, or the dark one
+pub fn theme() -> &'static Theme {
+    THEME.get_or_init(Theme::dark)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_themes() {
+        for name in BUILTIN_THEMES {
+            assert!(Theme::builtin(name).is_some(), "{}", name);
+        }
+        assert_eq!(Theme::load("light").unwrap(), Theme::light());
+        assert!(Theme::load("no-such-theme").is_err());
+    }
+
+    #[test]
+    fn test_theme_file_overrides_base() {
+        let theme = Theme::from_toml(
+            r##"
+            base = "light"
+            syntax = "Solarized (light)"
+            code_bg = "#fdf6e3"
+            diff_added = "green"
+            gradient = ["#000000", "#ffffff"]
+            "##,
+        )
+        .unwrap();
+
+        assert_eq!(theme.code_bg, Color::Rgb(0xfd, 0xf6, 0xe3));
+        assert_eq!(theme.diff_added, Color::Green);
+        assert_eq!(theme.syntax, "Solarized (light)");
+        assert_eq!(theme.gradient.len(), 2);
+        assert_eq!(theme.text, Theme::light().text);
+    }
+
+    #[test]
+    fn test_theme_file_defaults_to_dark() {
+        assert_eq!(Theme::from_toml("").unwrap(), Theme::dark());
+    }
+
+    #[test]
+    fn test_theme_file_errors() {
+        assert!(Theme::from_toml("code_bgg = \"#000000\"").is_err());
+        assert!(Theme::from_toml("code_bg = \"not a color\"").is_err());
+        assert!(Theme::from_toml("base = \"sepia\"").is_err());
+    }
+}
@@ -1,6 +1,6 @@
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph, Wrap},
     Frame,
@@ -8,7 +8,8 @@ use ratatui::{
 
 use crate::app::AppState;
 use crate::domain::Resolution;
-use crate::tui::colors::MurasakiColors;
+use crate::tui::keymap::{Action, KeyMode};
+use crate::tui::theme::theme;
 
 pub fn render_conflict_view(frame: &mut Frame, state: &AppState, area: Rect) {
     let chunks = Layout::default()
@@ -31,7 +32,7 @@ pub fn render_conflict_view(frame: &mut Frame, state: &AppState, area: Rect) {
     }
 
     let conflict = &file.conflicts[conflict_index];
-    let resolution = file.resolutions[conflict_index];
+    let resolution = file.resolutions[conflict_index].as_ref();
 
     // Header
     let header = Paragraph::new(vec![
@@ -65,16 +66,25 @@ pub fn render_conflict_view(frame: &mut Frame, state: &AppState, area: Rect) {
     let current_selected = matches!(resolution, Some(Resolution::Current));
     let current_style = if current_selected {
         Style::default()
-            .fg(Color::Green)
+            .fg(theme().success)
             .add_modifier(Modifier::BOLD)
     } else {
         Style::default()
     };
 
+    let select_hint = |action| match state.config.keys.label(KeyMode::Conflict, action) {
+        Some(key) => format!(" - Press '{}' to select", key),
+        None => String::new(),
+    };
+
     let current_title = if current_selected {
-        "CURRENT (HEAD) ✓ [selected]"
+        format!("{} ✓ [selected]", conflict.current_title())
     } else {
-        "CURRENT (HEAD) - Press 'c' to select"
+        format!(
+            "{}{}",
+            conflict.current_title(),
+            select_hint(Action::TakeCurrent)
+        )
     };
 
     let current = Paragraph::new(conflict.current.as_str())
@@ -92,16 +102,20 @@ pub fn render_conflict_view(frame: &mut Frame, state: &AppState, area: Rect) {
     let incoming_selected = matches!(resolution, Some(Resolution::Incoming));
     let incoming_style = if incoming_selected {
         Style::default()
-            .fg(Color::Cyan)
+            .fg(theme().accent)
             .add_modifier(Modifier::BOLD)
     } else {
         Style::default()
     };
 
     let incoming_title = if incoming_selected {
-        "INCOMING ✓ [selected]"
+        format!("{} ✓ [selected]", conflict.incoming_title())
     } else {
-        "INCOMING - Press 'i' to select"
+        format!(
+            "{}{}",
+            conflict.incoming_title(),
+            select_hint(Action::TakeIncoming)
+        )
     };
 
     let incoming = Paragraph::new(conflict.incoming.as_str())
@@ -125,9 +139,9 @@ pub fn render_conflict_view(frame: &mut Frame, state: &AppState, area: Rect) {
 
     let can_save = file.is_fully_resolved();
     let save_style = if can_save {
-        Style::default().fg(Color::Green)
+        Style::default().fg(theme().success)
     } else {
-        Style::default().fg(Color::DarkGray)
+        Style::default().fg(theme().text_dim)
     };
 
     let save_text = format!(
@@ -137,49 +151,49 @@ pub fn render_conflict_view(frame: &mut Frame, state: &AppState, area: Rect) {
     );
 
     let footer_line = Line::from(vec![
-        Span::styled("keys: ", Style::default().fg(Color::White)),
+        Span::styled("keys: ", Style::default().fg(theme().text_bright)),
         Span::styled(
             "c",
             Style::default()
-                .fg(Color::Green)
+                .fg(theme().success)
                 .add_modifier(Modifier::BOLD),
         ),
-        Span::styled("=current ", Style::default().fg(Color::White)),
+        Span::styled("=current ", Style::default().fg(theme().text_bright)),
         Span::styled(
             "i",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme().accent)
                 .add_modifier(Modifier::BOLD),
         ),
-        Span::styled("=incoming ", Style::default().fg(Color::White)),
+        Span::styled("=incoming ", Style::default().fg(theme().text_bright)),
         Span::styled(
             "b",
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme().warning)
                 .add_modifier(Modifier::BOLD),
         ),
-        Span::styled("=both ", Style::default().fg(Color::White)),
+        Span::styled("=both ", Style::default().fg(theme().text_bright)),
         Span::styled(
             "j/k",
             Style::default()
-                .fg(Color::White)
+                .fg(theme().text_bright)
                 .add_modifier(Modifier::BOLD),
         ),
-        Span::styled("=conflicts ", Style::default().fg(Color::White)),
+        Span::styled("=conflicts ", Style::default().fg(theme().text_bright)),
         Span::styled("s", save_style.add_modifier(Modifier::BOLD)),
-        Span::styled("=save ", Style::default().fg(Color::White)),
+        Span::styled("=save ", Style::default().fg(theme().text_bright)),
         Span::styled(
             save_text,
-            Style::default().fg(save_style.fg.unwrap_or(Color::White)),
+            Style::default().fg(save_style.fg.unwrap_or(theme().text_bright)),
         ),
         Span::styled("  Esc", Style::default().add_modifier(Modifier::BOLD)),
-        Span::styled("=back ", Style::default().fg(Color::White)),
+        Span::styled("=back ", Style::default().fg(theme().text_bright)),
         Span::styled("q", Style::default().add_modifier(Modifier::BOLD)),
-        Span::styled("=quit", Style::default().fg(Color::White)),
+        Span::styled("=quit", Style::default().fg(theme().text_bright)),
     ]);
 
     let footer = Paragraph::new(footer_line)
-        .style(Style::default().bg(MurasakiColors::FOOTER_BG))
+        .style(Style::default().bg(theme().footer_bg))
         .alignment(Alignment::Left);
 
     frame.render_widget(footer, chunks[2]);
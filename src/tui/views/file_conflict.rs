@@ -0,0 +1,114 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Wrap},
+    Frame,
+};
+
+use crate::domain::{BlobInfo, ConflictedFile, FileConflictKind, FileResolution};
+use crate::tui::keymap::{Action, KeyMode, Keymap};
+use crate::tui::theme::theme;
+
+/// Key that selects a whole-file resolution in the code view
+fn resolution_key(keymap: &Keymap, resolution: FileResolution) -> String {
+    let action = match resolution {
+        FileResolution::KeepOurs => Action::TakeCurrent,
+        FileResolution::KeepTheirs => Action::TakeIncoming,
+        FileResolution::Delete => Action::DeleteFile,
+    };
+    keymap
+        .label(KeyMode::Conflict, action)
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Build the metadata lines for one side of a binary conflict
+fn blob_lines(title: &'static str, color: Color, blob: &BlobInfo) -> Vec<Line<'static>> {
+    let dim = Style::default().fg(theme().text_dim);
+    let short_id: String = blob.id.chars().take(10).collect();
+    vec![
+        Line::from(Span::styled(
+            title,
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            format!("    Size:   {} bytes", blob.size),
+            dim,
+        )),
+        Line::from(Span::styled(format!("    Blob:   {}", short_id), dim)),
+        Line::from(Span::styled(
+            format!(
+                "    Commit: {}",
+                blob.last_commit.as_deref().unwrap_or("unknown")
+            ),
+            dim,
+        )),
+        Line::from(""),
+    ]
+}
+
+/// Render a whole-file conflict (deleted/added/renamed/binary) with its resolution options
+pub fn render_file_conflict(frame: &mut Frame, file: &ConflictedFile, keymap: &Keymap, area: Rect) {
+    let kind = match &file.file_conflict {
+        Some(kind) => kind,
+        None => return,
+    };
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "Whole-file conflict",
+            Style::default()
+                .fg(theme().conflict_marker)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            kind.description(),
+            Style::default().fg(theme().text),
+        )),
+        Line::from(""),
+    ];
+
+    if let FileConflictKind::Binary { ours, theirs } = kind {
+        lines.extend(blob_lines("Current (HEAD)", theme().conflict_current, ours));
+        lines.extend(blob_lines("Incoming", theme().conflict_incoming, theirs));
+    }
+
+    lines.push(Line::from(Span::styled(
+        "Options",
+        Style::default().fg(theme().heading),
+    )));
+
+    for &resolution in kind.available_resolutions() {
+        let is_selected = file.file_resolution == Some(resolution);
+        let marker = if is_selected { " ✓" } else { "" };
+
+        let label_style = if is_selected {
+            Style::default()
+                .fg(theme().status_resolved)
+                .bg(theme().resolved_bg)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme().text_dim)
+        };
+
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("    {}  ", resolution_key(keymap, resolution)),
+                Style::default()
+                    .fg(theme().text_bright)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("{}{}", kind.resolution_label(resolution), marker),
+                label_style,
+            ),
+        ]));
+    }
+
+    let content = Paragraph::new(lines)
+        .style(Style::default().bg(theme().code_bg))
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(content, area);
+}
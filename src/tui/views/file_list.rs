@@ -1,12 +1,13 @@
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, Paragraph},
     Frame,
 };
 
 use crate::app::AppState;
+use crate::tui::theme::theme;
 
 pub fn render_file_list(frame: &mut Frame, state: &AppState, area: Rect) {
     let chunks = Layout::default()
@@ -24,7 +25,7 @@ pub fn render_file_list(frame: &mut Frame, state: &AppState, area: Rect) {
             Span::styled(
                 "Murasaki",
                 Style::default()
-                    .fg(Color::Cyan)
+                    .fg(theme().accent)
                     .add_modifier(Modifier::BOLD),
             ),
             Span::raw(" - Git Conflict Resolution Tool"),
@@ -49,9 +50,9 @@ pub fn render_file_list(frame: &mut Frame, state: &AppState, area: Rect) {
             };
 
             let status_color = if file.is_fully_resolved() {
-                Color::Green
+                theme().status_resolved
             } else {
-                Color::Red
+                theme().status_unresolved
             };
 
             let conflict_info = format!(
@@ -71,13 +72,13 @@ pub fn render_file_list(frame: &mut Frame, state: &AppState, area: Rect) {
                 Span::raw("  "),
                 Span::styled(
                     format!("({})", conflict_info),
-                    Style::default().fg(Color::DarkGray),
+                    Style::default().fg(theme().text_dim),
                 ),
             ]);
 
             let style = if i == state.selected_file {
                 Style::default()
-                    .bg(Color::DarkGray)
+                    .bg(theme().selected_bg)
                     .add_modifier(Modifier::BOLD)
             } else {
                 Style::default()
@@ -112,9 +113,9 @@ pub fn render_file_list(frame: &mut Frame, state: &AppState, area: Rect) {
         .style(
             Style::default()
                 .fg(if state.all_files_resolved() {
-                    Color::Green
+                    theme().success
                 } else {
-                    Color::Yellow
+                    theme().warning
                 })
                 .add_modifier(Modifier::BOLD),
         );
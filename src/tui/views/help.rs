@@ -0,0 +1,66 @@
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::tui::keymap::{KeyMode, Keymap};
+use crate::tui::theme::theme;
+
+/// Render the key bindings of `mode` in a centered modal
+pub fn render_help_modal(frame: &mut Frame, keymap: &Keymap, mode: KeyMode, area: Rect) {
+    // Build help content from the keymap of the mode
+    let mut help_lines = vec![Line::from(vec![Span::styled(
+        "Keyboard Shortcuts",
+        Style::default()
+            .fg(theme().accent)
+            .add_modifier(Modifier::BOLD),
+    )])];
+    for (title, entries) in keymap.help(mode) {
+        help_lines.push(Line::from(""));
+        help_lines.push(Line::from(vec![Span::styled(
+            format!("  {}", title),
+            Style::default().fg(theme().heading),
+        )]));
+        for (keys, description) in entries {
+            help_lines.push(Line::from(vec![
+                Span::styled(
+                    format!("    {:<7}", keys),
+                    Style::default()
+                        .fg(theme().text_bright)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(description, Style::default().fg(theme().text_dim)),
+            ]));
+        }
+    }
+
+    // Calculate modal size and position (centered)
+    let modal_width = 52u16;
+    let modal_height = help_lines.len() as u16 + 2;
+    let x = (area.width.saturating_sub(modal_width)) / 2;
+    let y = (area.height.saturating_sub(modal_height)) / 2;
+
+    let modal_area = Rect::new(
+        x,
+        y,
+        modal_width.min(area.width),
+        modal_height.min(area.height),
+    );
+
+    // Clear the area behind the modal
+    frame.render_widget(Clear, modal_area);
+
+    let help_block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme().accent))
+        .style(Style::default().bg(theme().footer_bg));
+
+    let help_content = Paragraph::new(help_lines)
+        .block(help_block)
+        .alignment(Alignment::Left);
+
+    frame.render_widget(help_content, modal_area);
+}
@@ -0,0 +1,75 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::app::HunkEditor;
+use crate::tui::theme::theme;
+
+/// Width tabs are expanded to in the editor buffer
+const TAB_WIDTH: usize = 4;
+
+/// Render the inline hunk editor with its cursor
+pub fn render_hunk_editor(frame: &mut Frame, editor: &HunkEditor, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // Title
+            Constraint::Min(0),    // Buffer
+        ])
+        .split(area);
+
+    let key_style = Style::default()
+        .fg(theme().text_bright)
+        .add_modifier(Modifier::BOLD);
+    let dim = Style::default().fg(theme().text_dim);
+    let title = Line::from(vec![
+        Span::styled(
+            format!("Editing conflict {}  ", editor.conflict_index + 1),
+            Style::default()
+                .fg(theme().heading)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled("Ctrl-s", key_style),
+        Span::styled(" save  ", dim),
+        Span::styled("Esc", key_style),
+        Span::styled(" cancel", dim),
+    ]);
+    frame.render_widget(
+        Paragraph::new(title).style(Style::default().bg(theme().code_bg)),
+        chunks[0],
+    );
+
+    // Keep the cursor line visible
+    let height = chunks[1].height as usize;
+    let top = (editor.cursor_row + 1).saturating_sub(height.max(1));
+
+    let lines: Vec<Line> = editor
+        .lines
+        .iter()
+        .map(|line| {
+            Line::from(Span::styled(
+                line.replace('\t', &" ".repeat(TAB_WIDTH)),
+                Style::default().fg(theme().text).bg(theme().resolved_bg),
+            ))
+        })
+        .collect();
+
+    let buffer = Paragraph::new(lines)
+        .style(Style::default().bg(theme().code_bg))
+        .scroll((top as u16, 0));
+    frame.render_widget(buffer, chunks[1]);
+
+    // Place the terminal cursor, expanding tabs the same way as the buffer
+    let cursor_x: usize = editor.lines[editor.cursor_row]
+        .chars()
+        .take(editor.cursor_col)
+        .map(|c| if c == '\t' { TAB_WIDTH } else { 1 })
+        .sum();
+    let x = chunks[1].x + (cursor_x as u16).min(chunks[1].width.saturating_sub(1));
+    let y = chunks[1].y + (editor.cursor_row - top) as u16;
+    frame.set_cursor(x, y);
+}
@@ -0,0 +1,92 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::app::LinePicker;
+use crate::domain::Side;
+use crate::tui::theme::theme;
+
+/// Render the line picker: every line of both sides, numbered in pick order
+pub fn render_line_picker(frame: &mut Frame, picker: &LinePicker, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // Title
+            Constraint::Min(0),    // Lines
+        ])
+        .split(area);
+
+    let key_style = Style::default()
+        .fg(theme().text_bright)
+        .add_modifier(Modifier::BOLD);
+    let dim = Style::default().fg(theme().text_dim);
+    let title = Line::from(vec![
+        Span::styled(
+            format!("Picking lines of conflict {}  ", picker.conflict_index + 1),
+            Style::default()
+                .fg(theme().heading)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled("Space", key_style),
+        Span::styled(" toggle  ", dim),
+        Span::styled("Enter", key_style),
+        Span::styled(" apply  ", dim),
+        Span::styled("Esc", key_style),
+        Span::styled(" cancel", dim),
+    ]);
+    frame.render_widget(
+        Paragraph::new(title).style(Style::default().bg(theme().code_bg)),
+        chunks[0],
+    );
+
+    let mut lines: Vec<Line> = Vec::new();
+    let mut cursor_line = 0;
+    for row in 0..picker.len() {
+        let (side, index) = picker.row(row);
+        // Side headers, like the conflict markers
+        if index == 0 {
+            let (label, color) = match side {
+                Side::Current => ("current", theme().conflict_current),
+                Side::Incoming => ("incoming", theme().conflict_incoming),
+            };
+            lines.push(Line::from(Span::styled(
+                format!("── {} ──", label),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            )));
+        }
+        if row == picker.cursor {
+            cursor_line = lines.len();
+        }
+
+        let text = match side {
+            Side::Current => &picker.current[index],
+            Side::Incoming => &picker.incoming[index],
+        };
+        let (checkbox, bg) = match (picker.pick_position(row), side) {
+            (Some(position), _) => (format!("[{}] ", position + 1), theme().resolved_bg),
+            (None, Side::Current) => ("[ ] ".to_string(), theme().conflict_current_bg),
+            (None, Side::Incoming) => ("[ ] ".to_string(), theme().conflict_incoming_bg),
+        };
+        let mut style = Style::default().fg(theme().text).bg(bg);
+        if row == picker.cursor {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        lines.push(Line::from(vec![
+            Span::styled(checkbox, style.add_modifier(Modifier::BOLD)),
+            Span::styled(text.clone(), style),
+        ]));
+    }
+
+    // Keep the cursor line visible
+    let height = chunks[1].height as usize;
+    let top = (cursor_line + 1).saturating_sub(height.max(1));
+
+    let paragraph = Paragraph::new(lines)
+        .style(Style::default().bg(theme().code_bg))
+        .scroll((top as u16, 0));
+    frame.render_widget(paragraph, chunks[1]);
+}
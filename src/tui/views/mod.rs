@@ -1,9 +1,23 @@
 pub mod conflict_view;
+pub mod file_conflict;
 pub mod file_list;
+pub mod help;
+pub mod hunk_editor;
+pub mod line_picker;
+pub mod notification;
 pub mod rebase_actions;
+pub mod result_preview;
 pub mod split_pane;
+pub mod three_way;
 
 pub use conflict_view::render_conflict_view;
+pub use file_conflict::render_file_conflict;
 pub use file_list::render_file_list;
+pub use help::render_help_modal;
+pub use hunk_editor::render_hunk_editor;
+pub use line_picker::render_line_picker;
+pub use notification::render_notification;
 pub use rebase_actions::render_rebase_actions;
+pub use result_preview::render_result_preview;
 pub use split_pane::render_split_pane;
+pub use three_way::render_three_way;
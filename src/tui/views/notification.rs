@@ -0,0 +1,87 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::Notification;
+use crate::tui::keymap::{Action, KeyMode, Keymap};
+use crate::tui::theme::theme;
+
+/// Most lines of the error shown; long hook output is cut off
+const MAX_MESSAGE_LINES: usize = 12;
+
+/// Render an error notification near the bottom of the screen, over the view
+pub fn render_notification(
+    frame: &mut Frame,
+    notification: &Notification,
+    keymap: &Keymap,
+    area: Rect,
+) {
+    let width = 72u16.min(area.width);
+    let inner_width = width.saturating_sub(2).max(1) as usize;
+
+    let text = Style::default().fg(theme().text);
+    let mut lines: Vec<Line> = notification
+        .message
+        .lines()
+        .take(MAX_MESSAGE_LINES)
+        .map(|line| Line::from(Span::styled(line.to_string(), text)))
+        .collect();
+    if notification.message.lines().count() > MAX_MESSAGE_LINES {
+        lines.push(Line::from(Span::styled(
+            "...",
+            Style::default().fg(theme().text_dim),
+        )));
+    }
+
+    let key_style = Style::default()
+        .fg(theme().text_bright)
+        .add_modifier(Modifier::BOLD);
+    let dim = Style::default().fg(theme().text_dim);
+    let mut hint = Vec::new();
+    let retry = keymap.label(KeyMode::Notification, Action::Retry);
+    if let (Some(key), Some(_)) = (retry, &notification.retry) {
+        hint.push(Span::styled(key, key_style));
+        hint.push(Span::styled(" retry  ", dim));
+    }
+    if let Some(key) = keymap.label(KeyMode::Notification, Action::Dismiss) {
+        hint.push(Span::styled(key, key_style));
+        hint.push(Span::styled(" dismiss", dim));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(hint));
+
+    // Height of the wrapped text plus the borders
+    let rows: usize = lines
+        .iter()
+        .map(|line| line.width().max(1).div_ceil(inner_width))
+        .sum();
+    let height = (rows as u16 + 2).min(area.height);
+
+    let notification_area = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + area.height.saturating_sub(height + 1),
+        width,
+        height,
+    );
+    frame.render_widget(Clear, notification_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme().error))
+        .title(Span::styled(
+            format!(" {} ", notification.title),
+            Style::default()
+                .fg(theme().error)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .style(Style::default().bg(theme().footer_bg));
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, notification_area);
+}
@@ -7,9 +7,11 @@ use ratatui::{
 };
 
 use crate::app::AppState;
-use crate::tui::colors::MurasakiColors;
+use crate::tui::keymap::{Action, KeyMode};
+use crate::tui::theme::theme;
+use crate::tui::views::help::render_help_modal;
 
-pub fn render_rebase_actions(frame: &mut Frame, _state: &AppState, area: Rect) {
+pub fn render_rebase_actions(frame: &mut Frame, state: &AppState, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -24,58 +26,50 @@ pub fn render_rebase_actions(frame: &mut Frame, _state: &AppState, area: Rect) {
         Line::from(vec![Span::styled(
             "All Conflicts Resolved!",
             Style::default()
-                .fg(MurasakiColors::SUCCESS)
+                .fg(theme().success)
                 .add_modifier(Modifier::BOLD),
         )]),
         Line::from(""),
         Line::from(Span::styled(
             "What would you like to do with the rebase?",
-            Style::default().fg(MurasakiColors::TEXT_NORMAL),
+            Style::default().fg(theme().text),
         )),
     ])
     .alignment(Alignment::Center);
 
     frame.render_widget(header, chunks[0]);
 
-    // Actions
-    let actions = vec![
-        ListItem::new(Line::from(vec![
-            Span::styled(
-                "c",
-                Style::default()
-                    .fg(MurasakiColors::CONFLICT_CURRENT)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                " - Continue rebase (git rebase --continue)",
-                Style::default().fg(MurasakiColors::TEXT_NORMAL),
-            ),
-        ])),
-        ListItem::new(Line::from(vec![
-            Span::styled(
-                "a",
-                Style::default()
-                    .fg(MurasakiColors::ERROR)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                " - Abort rebase (git rebase --abort)",
-                Style::default().fg(MurasakiColors::TEXT_NORMAL),
-            ),
-        ])),
-        ListItem::new(Line::from(vec![
-            Span::styled(
-                "s",
-                Style::default()
-                    .fg(MurasakiColors::WARNING)
-                    .add_modifier(Modifier::BOLD),
-            ),
+    // Actions, with their keys in the rebase keymap
+    let keymap = &state.config.keys;
+    let actions: Vec<ListItem> = [
+        (
+            Action::ContinueRebase,
+            theme().conflict_current,
+            "Continue rebase (git rebase --continue)",
+        ),
+        (
+            Action::AbortRebase,
+            theme().error,
+            "Abort rebase (git rebase --abort)",
+        ),
+        (
+            Action::SkipCommit,
+            theme().warning,
+            "Skip current commit (git rebase --skip)",
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(action, color, description)| {
+        let key = keymap.label(KeyMode::Rebase, action)?;
+        Some(ListItem::new(Line::from(vec![
+            Span::styled(key, Style::default().fg(color).add_modifier(Modifier::BOLD)),
             Span::styled(
-                " - Skip current commit (git rebase --skip)",
-                Style::default().fg(MurasakiColors::TEXT_NORMAL),
+                format!(" - {}", description),
+                Style::default().fg(theme().text),
             ),
-        ])),
-    ];
+        ])))
+    })
+    .collect();
 
     // No borders on list
     let list = List::new(actions);
@@ -83,23 +77,28 @@ pub fn render_rebase_actions(frame: &mut Frame, _state: &AppState, area: Rect) {
     frame.render_widget(list, chunks[1]);
 
     // Footer - no borders
-    let footer = Paragraph::new(Line::from(vec![
-        Span::styled(
-            "q",
-            Style::default()
-                .fg(MurasakiColors::PINK_HOT)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::styled("=Exit  ", Style::default().fg(MurasakiColors::TEXT_DIM)),
-        Span::styled(
-            "Esc",
-            Style::default()
-                .fg(MurasakiColors::PINK_HOT)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::styled("=Cancel", Style::default().fg(MurasakiColors::TEXT_DIM)),
-    ]))
-    .alignment(Alignment::Center);
+    let quit_keys: Vec<String> = keymap
+        .keys(KeyMode::Rebase, Action::Quit)
+        .iter()
+        .map(|key| key.to_string())
+        .collect();
+    let key_style = Style::default()
+        .fg(theme().accent_alt)
+        .add_modifier(Modifier::BOLD);
+    let mut hints = vec![
+        Span::styled(quit_keys.join("/"), key_style),
+        Span::styled("=Exit", Style::default().fg(theme().text_dim)),
+    ];
+    if let Some(key) = keymap.label(KeyMode::Rebase, Action::Help) {
+        hints.push(Span::styled("  ", Style::default()));
+        hints.push(Span::styled(key, key_style));
+        hints.push(Span::styled("=Help", Style::default().fg(theme().text_dim)));
+    }
+    let footer = Paragraph::new(Line::from(hints)).alignment(Alignment::Center);
 
     frame.render_widget(footer, chunks[2]);
+
+    if state.show_help {
+        render_help_modal(frame, keymap, KeyMode::Rebase, area);
+    }
 }
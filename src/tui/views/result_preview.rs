@@ -0,0 +1,72 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Wrap},
+    Frame,
+};
+
+use crate::domain::{ConflictedFile, LineOrigin};
+use crate::git::is_conflict_marker_line;
+use crate::tui::highlight_cache::HighlightCache;
+use crate::tui::syntax::SyntaxHighlighter;
+use crate::tui::theme::theme;
+
+/// Render the file as it would be written with the current resolutions
+pub fn render_result_preview(
+    frame: &mut Frame,
+    file: &ConflictedFile,
+    highlights: &HighlightCache,
+    scroll_offset: u16,
+    area: Rect,
+) {
+    // The preview doesn't depend on the current conflict
+    let lines = highlights
+        .result_preview
+        .get_or_build(file, 0, || preview_lines(file));
+    let content = Paragraph::new(lines)
+        .style(Style::default().bg(theme().code_bg))
+        .wrap(Wrap { trim: false })
+        .scroll((scroll_offset, 0));
+
+    frame.render_widget(content, area);
+}
+
+/// The merged file, highlighted, with unresolved conflicts left in
+fn preview_lines(file: &ConflictedFile) -> Vec<Line<'static>> {
+    let highlighter = SyntaxHighlighter::new();
+    let syntax = highlighter.detect_syntax(&file.path);
+    let mut code = highlighter.highlight_lines(syntax);
+
+    file.merged_lines()
+        .into_iter()
+        .map(|line| {
+            // Unresolved conflicts keep their markers, so they stand out
+            if line.origin == LineOrigin::Unresolved
+                && is_conflict_marker_line(&line.text, file.marker_size)
+            {
+                return Line::from(Span::styled(
+                    line.text.trim_end_matches('\r').to_string(),
+                    Style::default()
+                        .fg(theme().conflict_marker)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+
+            let background = match line.origin {
+                LineOrigin::Context => None,
+                LineOrigin::Resolved => Some(theme().resolved_bg),
+                LineOrigin::Unresolved => Some(theme().conflict_base_bg),
+            };
+            let spans: Vec<Span> = code
+                .highlight_line(&line.text)
+                .into_iter()
+                .map(|(style, text)| match background {
+                    Some(bg) => Span::styled(text, style.bg(bg)),
+                    None => Span::styled(text, style),
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect()
+}
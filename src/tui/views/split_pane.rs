@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -6,11 +8,18 @@ use ratatui::{
     Frame,
 };
 
-use crate::app::{AppState, PaneFocus};
-use crate::domain::Resolution;
+use crate::app::{AppState, CodeLayout, PaneFocus};
+use crate::domain::{inline_diff, HunkChanges, Resolution};
 use crate::git::FileStatus;
-use crate::tui::colors::MurasakiColors;
-use crate::tui::syntax::{SyntaxHighlighter, CODE_BG};
+use crate::tui::keymap::{Action, KeyMode, Keymap};
+use crate::tui::syntax::{emphasize_changes, SyntaxHighlighter};
+use crate::tui::theme::theme;
+use crate::tui::views::file_conflict::render_file_conflict;
+use crate::tui::views::help::render_help_modal;
+use crate::tui::views::hunk_editor::render_hunk_editor;
+use crate::tui::views::line_picker::render_line_picker;
+use crate::tui::views::result_preview::render_result_preview;
+use crate::tui::views::three_way::render_three_way;
 
 pub fn render_split_pane(frame: &mut Frame, state: &AppState, area: Rect) {
     // Split into left (file list) and right (code view)
@@ -29,7 +38,12 @@ pub fn render_split_pane(frame: &mut Frame, state: &AppState, area: Rect) {
     if state.show_commit_modal {
         render_commit_modal(frame, state, area);
     } else if state.show_help {
-        render_help_modal(frame, state, area);
+        let mode = if state.is_staging_mode() {
+            KeyMode::Staging
+        } else {
+            KeyMode::Conflict
+        };
+        render_help_modal(frame, &state.config.keys, mode, area);
     }
 }
 
@@ -48,9 +62,9 @@ fn render_file_list_pane(frame: &mut Frame, state: &AppState, area: Rect) {
     // Render focus indicator
     let focus_text = if is_focused { "▎FILES" } else { " Files" };
     let focus_color = if is_focused {
-        MurasakiColors::CYAN_BRIGHT
+        theme().accent
     } else {
-        MurasakiColors::TEXT_DIM
+        theme().text_dim
     };
     let focus_line = Line::from(vec![Span::styled(
         focus_text,
@@ -84,10 +98,13 @@ fn render_conflict_file_list(frame: &mut Frame, state: &AppState, area: Rect) {
                 "○ "
             };
 
-            let status_color = if file.is_fully_resolved() {
-                MurasakiColors::STATUS_RESOLVED
+            // Automatic resolutions still need a look before the file counts as done
+            let status_color = if file.auto_resolved_count() > 0 {
+                theme().auto_resolved
+            } else if file.is_fully_resolved() {
+                theme().status_resolved
             } else {
-                MurasakiColors::TEXT_DIM
+                theme().text_dim
             };
 
             let is_selected = i == state.selected_file;
@@ -107,15 +124,15 @@ fn render_conflict_file_list(frame: &mut Frame, state: &AppState, area: Rect) {
                     Span::styled(status_icon, Style::default().fg(status_color)),
                     Span::styled(
                         format!(" {}", file.file_name()),
-                        Style::default().fg(MurasakiColors::TEXT_DIM),
+                        Style::default().fg(theme().text_dim),
                     ),
                 ])
             };
 
             let style = if is_selected {
                 Style::default()
-                    .bg(MurasakiColors::CYAN_BRIGHT)
-                    .fg(Color::Black)
+                    .bg(theme().accent)
+                    .fg(theme().selected_fg)
                     .add_modifier(Modifier::BOLD)
             } else {
                 Style::default()
@@ -158,7 +175,7 @@ fn render_staging_file_list(frame: &mut Frame, state: &AppState, area: Rect) {
         items.push(ListItem::new(Line::from(vec![Span::styled(
             "▎STAGED",
             Style::default()
-                .fg(MurasakiColors::SUCCESS)
+                .fg(theme().success)
                 .add_modifier(Modifier::BOLD),
         )])));
         for (idx, file) in &staged {
@@ -166,7 +183,7 @@ fn render_staging_file_list(frame: &mut Frame, state: &AppState, area: Rect) {
                 *idx,
                 file,
                 state.selected_file,
-                MurasakiColors::SUCCESS,
+                theme().success,
             ));
         }
     }
@@ -176,7 +193,7 @@ fn render_staging_file_list(frame: &mut Frame, state: &AppState, area: Rect) {
         items.push(ListItem::new(Line::from(vec![Span::styled(
             "▎BOTH",
             Style::default()
-                .fg(MurasakiColors::WARNING)
+                .fg(theme().warning)
                 .add_modifier(Modifier::BOLD),
         )])));
         for (idx, file) in &both {
@@ -184,7 +201,7 @@ fn render_staging_file_list(frame: &mut Frame, state: &AppState, area: Rect) {
                 *idx,
                 file,
                 state.selected_file,
-                MurasakiColors::WARNING,
+                theme().warning,
             ));
         }
     }
@@ -194,7 +211,7 @@ fn render_staging_file_list(frame: &mut Frame, state: &AppState, area: Rect) {
         items.push(ListItem::new(Line::from(vec![Span::styled(
             "▎UNSTAGED",
             Style::default()
-                .fg(MurasakiColors::ERROR)
+                .fg(theme().error)
                 .add_modifier(Modifier::BOLD),
         )])));
         for (idx, file) in &unstaged {
@@ -202,14 +219,14 @@ fn render_staging_file_list(frame: &mut Frame, state: &AppState, area: Rect) {
                 *idx,
                 file,
                 state.selected_file,
-                MurasakiColors::ERROR,
+                theme().error,
             ));
         }
     }
 
     if items.is_empty() {
         let empty = Paragraph::new("No changes")
-            .style(Style::default().fg(MurasakiColors::TEXT_DIM))
+            .style(Style::default().fg(theme().text_dim))
             .alignment(Alignment::Center);
         frame.render_widget(empty, area);
     } else {
@@ -230,7 +247,7 @@ fn create_staging_list_item(
 
     let style = if is_selected {
         Style::default()
-            .fg(Color::Black)
+            .fg(theme().selected_fg)
             .bg(color)
             .add_modifier(Modifier::BOLD)
     } else {
@@ -275,9 +292,9 @@ fn render_diff_pane(frame: &mut Frame, state: &AppState, area: Rect) {
     };
 
     let header_color = if is_focused {
-        MurasakiColors::CYAN_BRIGHT
+        theme().accent
     } else {
-        MurasakiColors::TEXT_DIM
+        theme().text_dim
     };
 
     let header = Paragraph::new(Line::from(vec![Span::styled(
@@ -297,63 +314,134 @@ fn render_diff_pane(frame: &mut Frame, state: &AppState, area: Rect) {
         render_diff_content(frame, diff, state.scroll_offset, chunks[1]);
     } else {
         let empty = Paragraph::new("No changes to display")
-            .style(Style::default().fg(MurasakiColors::TEXT_DIM).bg(CODE_BG));
+            .style(Style::default().fg(theme().text_dim).bg(theme().code_bg));
         frame.render_widget(empty, chunks[1]);
     }
 
     // Footer
-    render_footer(frame, chunks[2]);
+    render_footer(frame, state, chunks[2]);
 }
 
 fn render_diff_content(frame: &mut Frame, diff: &str, scroll_offset: u16, area: Rect) {
-    let lines: Vec<Line> = diff
-        .lines()
-        .map(|line| {
+    let diff_lines: Vec<&str> = diff.lines().collect();
+    let changes = diff_line_changes(&diff_lines);
+
+    let lines: Vec<Line> = diff_lines
+        .iter()
+        .zip(changes)
+        .map(|(line, changes)| {
             if line.starts_with('+') && !line.starts_with("+++") {
-                Line::from(Span::styled(
+                let segments = vec![(
+                    Style::default()
+                        .fg(theme().diff_added)
+                        .bg(theme().diff_added_bg),
                     line.to_string(),
-                    Style::default().fg(Color::Green).bg(Color::Rgb(20, 40, 20)),
+                )];
+                styled_line(emphasize_changes(
+                    segments,
+                    &changes,
+                    theme().diff_added_changed_bg,
                 ))
             } else if line.starts_with('-') && !line.starts_with("---") {
-                Line::from(Span::styled(
+                let segments = vec![(
+                    Style::default()
+                        .fg(theme().diff_removed)
+                        .bg(theme().diff_removed_bg),
                     line.to_string(),
-                    Style::default().fg(Color::Red).bg(Color::Rgb(50, 20, 20)),
+                )];
+                styled_line(emphasize_changes(
+                    segments,
+                    &changes,
+                    theme().diff_removed_changed_bg,
                 ))
             } else if line.starts_with("@@") {
                 Line::from(Span::styled(
                     line.to_string(),
                     Style::default()
-                        .fg(Color::Cyan)
+                        .fg(theme().diff_hunk_header)
                         .add_modifier(Modifier::BOLD),
                 ))
             } else if line.starts_with("diff ") || line.starts_with("index ") {
                 Line::from(Span::styled(
                     line.to_string(),
-                    Style::default().fg(Color::Yellow),
+                    Style::default().fg(theme().diff_file_header),
                 ))
             } else if line.starts_with("---") || line.starts_with("+++") {
                 Line::from(Span::styled(
                     line.to_string(),
                     Style::default()
-                        .fg(Color::White)
+                        .fg(theme().text_bright)
                         .add_modifier(Modifier::BOLD),
                 ))
             } else {
                 Line::from(Span::styled(
                     line.to_string(),
-                    Style::default().fg(Color::Gray),
+                    Style::default().fg(theme().diff_context),
                 ))
             }
         })
         .collect();
 
     let paragraph = Paragraph::new(lines)
-        .style(Style::default().bg(CODE_BG))
+        .style(Style::default().bg(theme().code_bg))
         .scroll((scroll_offset, 0));
 
     frame.render_widget(paragraph, area);
 }
 
+fn styled_line(segments: Vec<(Style, String)>) -> Line<'static> {
+    Line::from(
+        segments
+            .into_iter()
+            .map(|(style, text)| Span::styled(text, style))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Changed spans of every line of a unified diff
+///
+/// A run of `-` lines directly followed by `+` lines is diffed word by word;
+/// other lines get no spans, so they keep their plain added/removed color.
+fn diff_line_changes(lines: &[&str]) -> Vec<Vec<Range<usize>>> {
+    let is_removed = |line: &str| line.starts_with('-') && !line.starts_with("---");
+    let is_added = |line: &str| line.starts_with('+') && !line.starts_with("+++");
+
+    let mut changes = vec![Vec::new(); lines.len()];
+    let mut i = 0;
+    while i < lines.len() {
+        let removed_start = i;
+        while i < lines.len() && is_removed(lines[i]) {
+            i += 1;
+        }
+        let added_start = i;
+        while i < lines.len() && is_added(lines[i]) {
+            i += 1;
+        }
+        if removed_start == added_start || added_start == i {
+            i = i.max(removed_start + 1);
+            continue;
+        }
+
+        // Compare without the leading +/-
+        let removed: Vec<&str> = lines[removed_start..added_start]
+            .iter()
+            .map(|line| &line[1..])
+            .collect();
+        let added: Vec<&str> = lines[added_start..i]
+            .iter()
+            .map(|line| &line[1..])
+            .collect();
+        let (removed_changes, added_changes) = inline_diff::diff_lines(&removed, &added);
+        for (offset, spans) in removed_changes.into_iter().chain(added_changes).enumerate() {
+            changes[removed_start + offset] = spans
+                .into_iter()
+                .map(|range| range.start + 1..range.end + 1)
+                .collect();
+        }
+    }
+    changes
+}
+
 fn render_conflict_code_pane(frame: &mut Frame, state: &AppState, area: Rect) {
     let is_focused = state.focus == PaneFocus::CodeView;
 
@@ -361,8 +449,8 @@ fn render_conflict_code_pane(frame: &mut Frame, state: &AppState, area: Rect) {
     let file = match state.current_file() {
         Some(f) => f,
         None => {
-            let empty = Paragraph::new("No file selected")
-                .style(Style::default().fg(MurasakiColors::TEXT_DIM));
+            let empty =
+                Paragraph::new("No file selected").style(Style::default().fg(theme().text_dim));
             frame.render_widget(empty, area);
             return;
         }
@@ -380,31 +468,45 @@ fn render_conflict_code_pane(frame: &mut Frame, state: &AppState, area: Rect) {
         ])
         .split(area);
 
-    // Header with focus indicator
+    // Header with focus indicator (the title shows which view the pane is in)
+    let title = match (state.show_result_preview, is_focused) {
+        (true, true) => "PREVIEW",
+        (true, false) => "Preview",
+        (false, true) => "CODE",
+        (false, false) => "Code",
+    };
+    let auto_text = match file.auto_resolved_count() {
+        0 => String::new(),
+        count => format!(" ({} auto)", count),
+    };
     let header_text = if is_focused {
         format!(
-            "▎CODE  •  {}  •  Conflict {}/{}  •  Resolved {}/{}",
+            "▎{}  •  {}  •  Conflict {}/{}  •  Resolved {}/{}{}",
+            title,
             file.file_name(),
             conflict_index + 1,
             file.total_conflicts(),
             file.resolved_count(),
-            file.total_conflicts()
+            file.total_conflicts(),
+            auto_text
         )
     } else {
         format!(
-            " Code  •  {}  •  Conflict {}/{}  •  Resolved {}/{}",
+            " {}  •  {}  •  Conflict {}/{}  •  Resolved {}/{}{}",
+            title,
             file.file_name(),
             conflict_index + 1,
             file.total_conflicts(),
             file.resolved_count(),
-            file.total_conflicts()
+            file.total_conflicts(),
+            auto_text
         )
     };
 
     let header_color = if is_focused {
-        MurasakiColors::CYAN_BRIGHT
+        theme().accent
     } else {
-        MurasakiColors::TEXT_DIM
+        theme().text_dim
     };
 
     let header = Paragraph::new(Line::from(vec![Span::styled(
@@ -419,11 +521,38 @@ fn render_conflict_code_pane(frame: &mut Frame, state: &AppState, area: Rect) {
     )]));
     frame.render_widget(header, chunks[0]);
 
-    // Content - show full file with conflict highlighted
-    render_file_content(frame, state, file, conflict_index, chunks[1]);
+    // Content - whole-file conflicts get their options, others the full file
+    // with the conflict highlighted
+    if file.is_file_conflict() {
+        render_file_conflict(frame, file, &state.config.keys, chunks[1]);
+    } else if let Some(editor) = &state.editor {
+        render_hunk_editor(frame, editor, chunks[1]);
+    } else if let Some(picker) = &state.line_picker {
+        render_line_picker(frame, picker, chunks[1]);
+    } else if state.show_result_preview {
+        render_result_preview(
+            frame,
+            file,
+            &state.highlights,
+            state.scroll_offset,
+            chunks[1],
+        );
+    } else if state.code_layout == CodeLayout::ThreeWay {
+        render_three_way(
+            frame,
+            file,
+            &state.highlights,
+            &state.config.keys,
+            conflict_index,
+            state.scroll_offset,
+            chunks[1],
+        );
+    } else {
+        render_file_content(frame, state, file, conflict_index, chunks[1]);
+    }
 
     // Footer
-    render_footer(frame, chunks[2]);
+    render_footer(frame, state, chunks[2]);
 }
 
 fn render_file_content(
@@ -433,19 +562,41 @@ fn render_file_content(
     conflict_index: usize,
     area: Rect,
 ) {
-    let lines: Vec<&str> = file.original_content.lines().collect();
-    let mut display_lines: Vec<Line> = Vec::new();
-
     if conflict_index >= file.conflicts.len() {
         let content = Paragraph::new("No conflicts in this file")
-            .style(Style::default().fg(MurasakiColors::TEXT_DIM));
+            .style(Style::default().fg(theme().text_dim));
         frame.render_widget(content, area);
         return;
     }
 
-    // Initialize syntax highlighter
+    let display_lines = state
+        .highlights
+        .file_content
+        .get_or_build(file, conflict_index, || {
+            file_content_lines(file, conflict_index, &state.config.keys)
+        });
+    let content = Paragraph::new(display_lines)
+        .style(Style::default().bg(theme().code_bg))
+        .wrap(Wrap { trim: false })
+        .scroll((state.scroll_offset, 0));
+
+    frame.render_widget(content, area);
+}
+
+/// The whole file with its conflicts as resolved so far, highlighted
+fn file_content_lines(
+    file: &crate::domain::ConflictedFile,
+    conflict_index: usize,
+    keymap: &Keymap,
+) -> Vec<Line<'static>> {
+    let lines: Vec<&str> = file.original_content.lines().collect();
+    let mut display_lines: Vec<Line<'static>> = Vec::new();
+
+    // Initialize syntax highlighter; lines are fed in display order
     let highlighter = SyntaxHighlighter::new();
     let syntax = highlighter.detect_syntax(&file.path);
+    let mut code = highlighter.highlight_lines(syntax);
+    let no_changes = HunkChanges::default();
 
     let mut line_idx = 0;
 
@@ -459,7 +610,7 @@ fn render_file_content(
 
         if let Some((idx, conflict)) = conflict_at_line {
             // This is a conflict
-            let resolution = file.resolutions[idx];
+            let resolution = file.resolutions[idx].as_ref();
             let is_current_conflict = idx == conflict_index;
             let both_selected = matches!(resolution, Some(Resolution::Both));
             let current_selected = matches!(
@@ -471,75 +622,176 @@ fn render_file_content(
                 Some(Resolution::Incoming) | Some(Resolution::Both)
             );
             let is_resolved = resolution.is_some();
+            let auto_resolution = file.auto_resolution(idx);
+            let resolved_bg = if auto_resolution.is_some() {
+                theme().auto_resolved_bg
+            } else {
+                theme().resolved_bg
+            };
+
+            if let Some(reason) = auto_resolution {
+                let marker = if is_current_conflict { " ◀" } else { "" };
+                let hint = match keymap.auto_resolution_hint() {
+                    Some(hint) => format!(" ({})", hint),
+                    None => String::new(),
+                };
+                display_lines.push(Line::from(Span::styled(
+                    format!("┄┄ auto: {}{}{}", reason.as_str(), hint, marker),
+                    Style::default()
+                        .fg(theme().auto_resolved)
+                        .add_modifier(Modifier::ITALIC),
+                )));
+            }
 
             if !is_resolved {
                 let marker_text = if is_current_conflict {
-                    "<<<<<<< CURRENT (HEAD) ◀"
+                    format!("<<<<<<< {} ◀", conflict.current_title())
                 } else {
-                    "<<<<<<< CURRENT (HEAD)"
+                    format!("<<<<<<< {}", conflict.current_title())
                 };
                 display_lines.push(Line::from(Span::styled(
                     marker_text,
                     Style::default()
-                        .fg(MurasakiColors::CONFLICT_MARKER)
+                        .fg(theme().conflict_marker)
                         .add_modifier(Modifier::BOLD),
                 )));
             }
 
             let show_current = resolution.is_none() || current_selected;
 
+            // Only what actually differs is emphasized while the conflict is open
+            let changes = match file.hunk_changes(idx) {
+                Some(changes) if !is_resolved => changes,
+                _ => &no_changes,
+            };
+
+            // Each side starts from the state before the conflict, so a comment
+            // or string opened on one side doesn't bleed into the other
+            let mut current_code = code.clone();
             if show_current {
-                for line in conflict.current.lines() {
-                    let highlighted = highlighter.highlight_line(line, syntax);
-                    let spans: Vec<Span> = highlighted
+                for (i, line) in conflict.current.lines().enumerate() {
+                    let highlighted: Vec<(Style, String)> = current_code
+                        .highlight_line(line)
                         .into_iter()
                         .map(|(style, text)| {
                             let final_style = if is_resolved {
                                 if both_selected {
-                                    style.bg(MurasakiColors::CONFLICT_BOTH_BG)
+                                    style.bg(theme().conflict_both_bg)
                                 } else {
-                                    style.bg(MurasakiColors::RESOLVED_BG)
+                                    style.bg(resolved_bg)
                                 }
                             } else {
-                                style.bg(MurasakiColors::CONFLICT_CURRENT_BG)
+                                style.bg(theme().conflict_current_bg)
                             };
-                            Span::styled(text, final_style)
+                            (final_style, text)
                         })
                         .collect();
+                    let spans: Vec<Span> = emphasize_changes(
+                        highlighted,
+                        changes.current.get(i).map_or(&[], Vec::as_slice),
+                        theme().conflict_current_changed_bg,
+                    )
+                    .into_iter()
+                    .map(|(style, text)| Span::styled(text, style))
+                    .collect();
 
                     display_lines.push(Line::from(spans));
                 }
             }
 
+            // Common ancestor (diff3/zdiff3) so both sides' changes are visible
+            if !is_resolved {
+                if let Some(base) = &conflict.base {
+                    display_lines.push(Line::from(Span::styled(
+                        format!("||||||| {}", conflict.base_title()),
+                        Style::default()
+                            .fg(theme().conflict_marker)
+                            .add_modifier(Modifier::BOLD),
+                    )));
+
+                    let mut base_code = code.clone();
+                    for (i, line) in base.lines().enumerate() {
+                        let highlighted: Vec<(Style, String)> = base_code
+                            .highlight_line(line)
+                            .into_iter()
+                            .map(|(style, text)| {
+                                (
+                                    style.fg(theme().conflict_base).bg(theme().conflict_base_bg),
+                                    text,
+                                )
+                            })
+                            .collect();
+                        let spans: Vec<Span> = emphasize_changes(
+                            highlighted,
+                            changes.base.get(i).map_or(&[], Vec::as_slice),
+                            theme().conflict_base_changed_bg,
+                        )
+                        .into_iter()
+                        .map(|(style, text)| Span::styled(text, style))
+                        .collect();
+
+                        display_lines.push(Line::from(spans));
+                    }
+                }
+            }
+
             if !is_resolved {
                 display_lines.push(Line::from(Span::styled(
                     "=======",
                     Style::default()
-                        .fg(MurasakiColors::CONFLICT_MARKER)
+                        .fg(theme().conflict_marker)
                         .add_modifier(Modifier::BOLD),
                 )));
             }
 
             let show_incoming = resolution.is_none() || incoming_selected;
 
+            // With both kept, incoming follows current in the result
+            let mut incoming_code = if both_selected {
+                current_code.clone()
+            } else {
+                code.clone()
+            };
             if show_incoming {
-                for line in conflict.incoming.lines() {
-                    let highlighted = highlighter.highlight_line(line, syntax);
-                    let spans: Vec<Span> = highlighted
+                for (i, line) in conflict.incoming.lines().enumerate() {
+                    let highlighted: Vec<(Style, String)> = incoming_code
+                        .highlight_line(line)
                         .into_iter()
                         .map(|(style, text)| {
                             let final_style = if is_resolved {
                                 if both_selected {
-                                    style.bg(MurasakiColors::CONFLICT_BOTH_BG)
+                                    style.bg(theme().conflict_both_bg)
                                 } else {
-                                    style.bg(MurasakiColors::RESOLVED_BG)
+                                    style.bg(resolved_bg)
                                 }
                             } else {
-                                style.bg(MurasakiColors::CONFLICT_INCOMING_BG)
+                                style.bg(theme().conflict_incoming_bg)
                             };
-                            Span::styled(text, final_style)
+                            (final_style, text)
                         })
                         .collect();
+                    let spans: Vec<Span> = emphasize_changes(
+                        highlighted,
+                        changes.incoming.get(i).map_or(&[], Vec::as_slice),
+                        theme().conflict_incoming_changed_bg,
+                    )
+                    .into_iter()
+                    .map(|(style, text)| Span::styled(text, style))
+                    .collect();
+
+                    display_lines.push(Line::from(spans));
+                }
+            }
+
+            // Hand-merged content and picked lines replace both sides
+            let mut resolved_code = code.clone();
+            if let Some(resolution @ (Resolution::Custom(_) | Resolution::Lines(_))) = resolution {
+                for line in conflict.resolve(resolution.clone()).lines() {
+                    let highlighted = resolved_code.highlight_line(line);
+                    let spans: Vec<Span> = highlighted
+                        .into_iter()
+                        .map(|(style, text)| Span::styled(text, style.bg(resolved_bg)))
+                        .collect();
 
                     display_lines.push(Line::from(spans));
                 }
@@ -547,21 +799,28 @@ fn render_file_content(
 
             if !is_resolved {
                 let marker_text = if is_current_conflict {
-                    ">>>>>>> INCOMING ◀"
+                    format!(">>>>>>> {} ◀", conflict.incoming_title())
                 } else {
-                    ">>>>>>> INCOMING"
+                    format!(">>>>>>> {}", conflict.incoming_title())
                 };
                 display_lines.push(Line::from(Span::styled(
                     marker_text,
                     Style::default()
-                        .fg(MurasakiColors::CONFLICT_MARKER)
+                        .fg(theme().conflict_marker)
                         .add_modifier(Modifier::BOLD),
                 )));
             }
 
+            // The rest of the file follows the version shown (current while unresolved)
+            code = match resolution {
+                None | Some(Resolution::Current) => current_code,
+                Some(Resolution::Incoming) | Some(Resolution::Both) => incoming_code,
+                Some(_) => resolved_code,
+            };
+
             line_idx = conflict.end_line + 1;
         } else {
-            let highlighted = highlighter.highlight_line(lines[line_idx], syntax);
+            let highlighted = code.highlight_line(lines[line_idx]);
             let spans: Vec<Span> = highlighted
                 .into_iter()
                 .map(|(style, text)| Span::styled(text, style))
@@ -572,28 +831,43 @@ fn render_file_content(
         }
     }
 
-    let content = Paragraph::new(display_lines)
-        .style(Style::default().bg(CODE_BG))
-        .wrap(Wrap { trim: false })
-        .scroll((state.scroll_offset, 0));
-
-    frame.render_widget(content, area);
+    display_lines
 }
 
-fn render_footer(frame: &mut Frame, area: Rect) {
+fn render_footer(frame: &mut Frame, state: &AppState, area: Rect) {
+    if let Some(message) = &state.status_message {
+        let footer = Paragraph::new(Line::from(Span::styled(
+            message.as_str(),
+            Style::default().fg(theme().warning),
+        )))
+        .style(Style::default().bg(theme().footer_bg))
+        .alignment(Alignment::Center);
+        frame.render_widget(footer, area);
+        return;
+    }
+
+    let mode = if state.is_staging_mode() {
+        KeyMode::Staging
+    } else {
+        KeyMode::Conflict
+    };
+    let help_key = match state.config.keys.label(mode, Action::Help) {
+        Some(key) => key,
+        None => return,
+    };
     let hint = Line::from(vec![
-        Span::styled("Press ", Style::default().fg(MurasakiColors::TEXT_DIM)),
+        Span::styled("Press ", Style::default().fg(theme().text_dim)),
         Span::styled(
-            "?",
+            help_key,
             Style::default()
-                .fg(Color::White)
+                .fg(theme().text_bright)
                 .add_modifier(Modifier::BOLD),
         ),
-        Span::styled(" for help", Style::default().fg(MurasakiColors::TEXT_DIM)),
+        Span::styled(" for help", Style::default().fg(theme().text_dim)),
     ]);
 
     let footer = Paragraph::new(hint)
-        .style(Style::default().bg(MurasakiColors::FOOTER_BG))
+        .style(Style::default().bg(theme().footer_bg))
         .alignment(Alignment::Center);
 
     frame.render_widget(footer, area);
@@ -632,15 +906,15 @@ fn render_commit_modal(frame: &mut Frame, state: &AppState, area: Rect) {
     // Background
     let bg = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(MurasakiColors::CYAN_BRIGHT))
-        .style(Style::default().bg(MurasakiColors::FOOTER_BG));
+        .border_style(Style::default().fg(theme().accent))
+        .style(Style::default().bg(theme().footer_bg));
     frame.render_widget(bg, modal_area);
 
     // Header
     let header = Paragraph::new(Line::from(vec![Span::styled(
         " Commit Changes",
         Style::default()
-            .fg(MurasakiColors::CYAN_BRIGHT)
+            .fg(theme().accent)
             .add_modifier(Modifier::BOLD),
     )]));
     frame.render_widget(header, chunks[0]);
@@ -648,7 +922,7 @@ fn render_commit_modal(frame: &mut Frame, state: &AppState, area: Rect) {
     // Input prompt
     let prompt = Paragraph::new(Line::from(vec![Span::styled(
         "  Enter commit message:",
-        Style::default().fg(Color::White),
+        Style::default().fg(theme().text_bright),
     )]));
     frame.render_widget(prompt, chunks[1]);
 
@@ -656,7 +930,7 @@ fn render_commit_modal(frame: &mut Frame, state: &AppState, area: Rect) {
     let input_text = format!("  > {}_", state.commit_message);
     let input = Paragraph::new(Line::from(vec![Span::styled(
         input_text,
-        Style::default().fg(MurasakiColors::CYAN_BRIGHT),
+        Style::default().fg(theme().accent),
     )]));
     frame.render_widget(input, chunks[2]);
 
@@ -664,7 +938,7 @@ fn render_commit_modal(frame: &mut Frame, state: &AppState, area: Rect) {
     if let Some(ref error) = state.commit_error {
         let error_line = Paragraph::new(Line::from(vec![Span::styled(
             format!("  {}", error),
-            Style::default().fg(MurasakiColors::ERROR),
+            Style::default().fg(theme().error),
         )]));
         frame.render_widget(error_line, chunks[3]);
     }
@@ -674,219 +948,48 @@ fn render_commit_modal(frame: &mut Frame, state: &AppState, area: Rect) {
         Span::styled(
             "  Enter",
             Style::default()
-                .fg(MurasakiColors::CYAN_BRIGHT)
+                .fg(theme().accent)
                 .add_modifier(Modifier::BOLD),
         ),
-        Span::styled("=Commit  ", Style::default().fg(MurasakiColors::TEXT_DIM)),
+        Span::styled("=Commit  ", Style::default().fg(theme().text_dim)),
         Span::styled(
             "Esc",
             Style::default()
-                .fg(MurasakiColors::CYAN_BRIGHT)
+                .fg(theme().accent)
                 .add_modifier(Modifier::BOLD),
         ),
-        Span::styled("=Cancel", Style::default().fg(MurasakiColors::TEXT_DIM)),
+        Span::styled("=Cancel", Style::default().fg(theme().text_dim)),
     ]));
     frame.render_widget(footer, chunks[5]);
 }
 
-fn render_help_modal(frame: &mut Frame, state: &AppState, area: Rect) {
-    // Calculate modal size and position (centered)
-    let modal_width = 52u16;
-    let modal_height = 24u16;
-    let x = (area.width.saturating_sub(modal_width)) / 2;
-    let y = (area.height.saturating_sub(modal_height)) / 2;
-
-    let modal_area = Rect::new(
-        x,
-        y,
-        modal_width.min(area.width),
-        modal_height.min(area.height),
-    );
-
-    // Clear the area behind the modal
-    frame.render_widget(Clear, modal_area);
-
-    // Build help content based on mode
-    let mut help_lines = vec![
-        Line::from(vec![Span::styled(
-            "Keyboard Shortcuts",
-            Style::default()
-                .fg(MurasakiColors::CYAN_BRIGHT)
-                .add_modifier(Modifier::BOLD),
-        )]),
-        Line::from(""),
-        Line::from(vec![Span::styled(
-            "  Navigation",
-            Style::default().fg(MurasakiColors::PURPLE_BRIGHT),
-        )]),
-        Line::from(vec![
-            Span::styled(
-                "    j/k    ",
-                Style::default()
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                "Scroll up/down",
-                Style::default().fg(MurasakiColors::TEXT_DIM),
-            ),
-        ]),
-        Line::from(vec![
-            Span::styled(
-                "    Tab    ",
-                Style::default()
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                "Switch focus",
-                Style::default().fg(MurasakiColors::TEXT_DIM),
-            ),
-        ]),
-    ];
-
-    if state.is_staging_mode() {
-        // Staging mode help
-        help_lines.push(Line::from(""));
-        help_lines.push(Line::from(vec![Span::styled(
-            "  Staging",
-            Style::default().fg(MurasakiColors::PURPLE_BRIGHT),
-        )]));
-        help_lines.push(Line::from(vec![
-            Span::styled(
-                "    a      ",
-                Style::default()
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled("Stage file", Style::default().fg(MurasakiColors::TEXT_DIM)),
-        ]));
-        help_lines.push(Line::from(vec![
-            Span::styled(
-                "    s      ",
-                Style::default()
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                "Unstage file",
-                Style::default().fg(MurasakiColors::TEXT_DIM),
-            ),
-        ]));
-        help_lines.push(Line::from(vec![
-            Span::styled(
-                "    r      ",
-                Style::default()
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                "Restore file (discard changes)",
-                Style::default().fg(MurasakiColors::TEXT_DIM),
-            ),
-        ]));
-        help_lines.push(Line::from(vec![
-            Span::styled(
-                "    c      ",
-                Style::default()
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                "Commit staged changes",
-                Style::default().fg(MurasakiColors::TEXT_DIM),
-            ),
-        ]));
-    } else {
-        // Conflict mode help
-        help_lines.push(Line::from(vec![
-            Span::styled(
-                "    n/p    ",
-                Style::default()
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                "Next/previous conflict",
-                Style::default().fg(MurasakiColors::TEXT_DIM),
-            ),
-        ]));
-        help_lines.push(Line::from(""));
-        help_lines.push(Line::from(vec![Span::styled(
-            "  Conflict Resolution",
-            Style::default().fg(MurasakiColors::PURPLE_BRIGHT),
-        )]));
-        help_lines.push(Line::from(vec![
-            Span::styled(
-                "    c      ",
-                Style::default()
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                "Choose current (HEAD)",
-                Style::default().fg(MurasakiColors::TEXT_DIM),
-            ),
-        ]));
-        help_lines.push(Line::from(vec![
-            Span::styled(
-                "    i      ",
-                Style::default()
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                "Choose incoming",
-                Style::default().fg(MurasakiColors::TEXT_DIM),
-            ),
-        ]));
-        help_lines.push(Line::from(vec![
-            Span::styled(
-                "    b      ",
-                Style::default()
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled("Choose both", Style::default().fg(MurasakiColors::TEXT_DIM)),
-        ]));
-        help_lines.push(Line::from(vec![
-            Span::styled(
-                "    u      ",
-                Style::default()
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                "Undo resolution",
-                Style::default().fg(MurasakiColors::TEXT_DIM),
-            ),
-        ]));
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_line_changes() {
+        let lines = [
+            "--- a/f.rs",
+            "+++ b/f.rs",
+            " let a = 0;",
+            "-let x = 1;",
+            "+let x = 2;",
+            "+let y = 3;",
+            " let b = 0;",
+            "-gone();",
+        ];
+        let changes = diff_line_changes(&lines);
+
+        assert_eq!(changes.len(), lines.len());
+        // Headers and context are left alone
+        assert!(changes[..3].iter().all(Vec::is_empty));
+        // Paired lines: only the number changed, shifted past the +/-
+        assert_eq!(changes[3], vec![9..10]);
+        assert_eq!(changes[4], vec![9..10]);
+        // An added line without a partner changed as a whole
+        assert_eq!(changes[5], vec![1..11]);
+        assert!(changes[6].is_empty());
+        assert!(changes[7].is_empty());
     }
-
-    // Common actions
-    help_lines.push(Line::from(""));
-    help_lines.push(Line::from(vec![Span::styled(
-        "  Actions",
-        Style::default().fg(MurasakiColors::PURPLE_BRIGHT),
-    )]));
-    help_lines.push(Line::from(vec![
-        Span::styled(
-            "    q      ",
-            Style::default()
-                .fg(Color::White)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::styled("Quit", Style::default().fg(MurasakiColors::TEXT_DIM)),
-    ]));
-
-    let help_block = Block::default()
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(MurasakiColors::CYAN_BRIGHT))
-        .style(Style::default().bg(MurasakiColors::FOOTER_BG));
-
-    let help_content = Paragraph::new(help_lines)
-        .block(help_block)
-        .alignment(Alignment::Left);
-
-    frame.render_widget(help_content, modal_area);
 }
@@ -0,0 +1,313 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use syntect::parsing::SyntaxReference;
+
+use crate::domain::{ConflictedFile, HunkChanges, LineChanges};
+use crate::tui::highlight_cache::HighlightCache;
+use crate::tui::keymap::Keymap;
+use crate::tui::syntax::{emphasize_changes, LineHighlighter, SyntaxHighlighter};
+use crate::tui::theme::theme;
+
+/// Lines of context shown around the current conflict
+const CONTEXT_LINES: usize = 3;
+
+/// Render the ours | base | theirs columns with the result of the current conflict below
+pub fn render_three_way(
+    frame: &mut Frame,
+    file: &ConflictedFile,
+    highlights: &HighlightCache,
+    keymap: &Keymap,
+    conflict_index: usize,
+    scroll_offset: u16,
+    area: Rect,
+) {
+    if conflict_index >= file.conflicts.len() {
+        let content = Paragraph::new("No conflicts in this file")
+            .style(Style::default().fg(theme().text_dim));
+        frame.render_widget(content, area);
+        return;
+    }
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(60), // Ours | base | theirs
+            Constraint::Percentage(40), // Result
+        ])
+        .split(area);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Ratio(1, 3),
+            Constraint::Ratio(1, 3),
+            Constraint::Ratio(1, 3),
+        ])
+        .split(rows[0]);
+
+    let highlighter = SyntaxHighlighter::new();
+    let syntax = highlighter.detect_syntax(&file.path);
+
+    let (sides, hunk_row) = highlights.three_way.get_or_build(file, conflict_index, || {
+        build_columns(file, conflict_index, &highlighter, syntax)
+    });
+    let top = (hunk_row.saturating_sub(CONTEXT_LINES) as u16).saturating_add(scroll_offset);
+
+    // Titles follow the marker labels of the current conflict (branch names, commit subjects)
+    let conflict = &file.conflicts[conflict_index];
+    let titles = [
+        (conflict.current_title(), theme().conflict_current),
+        (conflict.base_title(), theme().conflict_base),
+        (conflict.incoming_title(), theme().conflict_incoming),
+    ];
+    for ((lines, (title, color)), column) in sides.into_iter().zip(titles).zip(columns.iter()) {
+        let block = Block::default()
+            .borders(Borders::TOP | Borders::RIGHT)
+            .border_style(Style::default().fg(theme().text_dim))
+            .title(Span::styled(
+                format!(" {} ", title),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            ));
+        let paragraph = Paragraph::new(lines)
+            .block(block)
+            .style(Style::default().bg(theme().code_bg))
+            .scroll((top, 0));
+        frame.render_widget(paragraph, *column);
+    }
+
+    let result = highlights
+        .three_way_result
+        .get_or_build(file, conflict_index, || {
+            result_lines(file, conflict_index, &highlighter, syntax)
+        });
+    render_result(frame, file, keymap, conflict_index, result, rows[1]);
+}
+
+/// Highlight a line, optionally forcing a background
+fn highlight(code: &mut LineHighlighter, line: &str, background: Option<Color>) -> Line<'static> {
+    let spans: Vec<Span> = code
+        .highlight_line(line)
+        .into_iter()
+        .map(|(style, text)| match background {
+            Some(bg) => Span::styled(text, style.bg(bg)),
+            None => Span::styled(text, style),
+        })
+        .collect();
+    Line::from(spans)
+}
+
+/// Build the three columns, padding each conflict so the sides stay aligned
+///
+/// Returns the columns and the row where the current conflict starts.
+fn build_columns(
+    file: &ConflictedFile,
+    conflict_index: usize,
+    highlighter: &SyntaxHighlighter,
+    syntax: &SyntaxReference,
+) -> ([Vec<Line<'static>>; 3], usize) {
+    let lines: Vec<&str> = file.original_content.lines().collect();
+    let mut columns: [Vec<Line<'static>>; 3] = [Vec::new(), Vec::new(), Vec::new()];
+    // Each column is its own version of the file, so each keeps its own parse state
+    let mut code = [
+        highlighter.highlight_lines(syntax),
+        highlighter.highlight_lines(syntax),
+        highlighter.highlight_lines(syntax),
+    ];
+    let no_changes = HunkChanges::default();
+    let mut hunk_row = 0;
+    let mut line_idx = 0;
+
+    while line_idx < lines.len() {
+        let conflict_at_line = file
+            .conflicts
+            .iter()
+            .enumerate()
+            .find(|(_, c)| c.start_line == line_idx);
+
+        match conflict_at_line {
+            Some((idx, conflict)) => {
+                if idx == conflict_index {
+                    hunk_row = columns[0].len();
+                }
+
+                let changes = file.hunk_changes(idx).unwrap_or(&no_changes);
+                let [current_code, base_code, incoming_code] = &mut code;
+                let base_lines = match &conflict.base {
+                    Some(base) => side_lines(
+                        base_code,
+                        base,
+                        &changes.base,
+                        theme().conflict_base_bg,
+                        theme().conflict_base_changed_bg,
+                    ),
+                    None => vec![Line::from(Span::styled(
+                        "(no base: use merge.conflictStyle=diff3)",
+                        Style::default().fg(theme().text_dim),
+                    ))],
+                };
+                let sides = [
+                    side_lines(
+                        current_code,
+                        &conflict.current,
+                        &changes.current,
+                        theme().conflict_current_bg,
+                        theme().conflict_current_changed_bg,
+                    ),
+                    base_lines,
+                    side_lines(
+                        incoming_code,
+                        &conflict.incoming,
+                        &changes.incoming,
+                        theme().conflict_incoming_bg,
+                        theme().conflict_incoming_changed_bg,
+                    ),
+                ];
+
+                let height = sides.iter().map(Vec::len).max().unwrap_or(0);
+                for (column, side) in columns.iter_mut().zip(sides) {
+                    let padding = height - side.len();
+                    column.extend(side);
+                    column.extend(std::iter::repeat_n(Line::from(""), padding));
+                }
+
+                line_idx = conflict.end_line + 1;
+            }
+            None => {
+                for (column, code) in columns.iter_mut().zip(code.iter_mut()) {
+                    column.push(highlight(code, lines[line_idx], None));
+                }
+                line_idx += 1;
+            }
+        }
+    }
+
+    (columns, hunk_row)
+}
+
+/// Highlight one side of a conflict, emphasizing its changed spans
+fn side_lines(
+    code: &mut LineHighlighter,
+    text: &str,
+    changes: &[LineChanges],
+    bg: Color,
+    changed_bg: Color,
+) -> Vec<Line<'static>> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let segments = code
+                .highlight_line(line)
+                .into_iter()
+                .map(|(style, text)| (style.bg(bg), text))
+                .collect();
+            let spans: Vec<Span> = emphasize_changes(
+                segments,
+                changes.get(i).map_or(&[], Vec::as_slice),
+                changed_bg,
+            )
+            .into_iter()
+            .map(|(style, text)| Span::styled(text, style))
+            .collect();
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// The current conflict's result with some context around it, highlighted
+fn result_lines(
+    file: &ConflictedFile,
+    conflict_index: usize,
+    highlighter: &SyntaxHighlighter,
+    syntax: &SyntaxReference,
+) -> Vec<Line<'static>> {
+    let lines: Vec<&str> = file.original_content.lines().collect();
+    let conflict = &file.conflicts[conflict_index];
+    let resolution = file
+        .resolutions
+        .get(conflict_index)
+        .and_then(|r| r.as_ref());
+
+    let start = conflict.start_line.min(lines.len());
+    let end = (conflict.end_line + 1).min(lines.len());
+    let context_start = start.saturating_sub(CONTEXT_LINES);
+
+    // Parse what comes before so a comment or string open there still counts
+    let mut code = highlighter.highlight_lines(syntax);
+    for line in &lines[..context_start] {
+        code.highlight_line(line);
+    }
+
+    let mut result: Vec<Line<'static>> = lines[context_start..start]
+        .iter()
+        .map(|l| highlight(&mut code, l, None))
+        .collect();
+
+    match resolution {
+        Some(resolution) => {
+            for line in conflict.resolve(resolution.clone()).lines() {
+                result.push(highlight(&mut code, line, Some(theme().resolved_bg)));
+            }
+        }
+        None => result.push(Line::from(Span::styled(
+            "(unresolved: choose with c / i / b / e)",
+            Style::default()
+                .fg(theme().conflict_marker)
+                .add_modifier(Modifier::BOLD),
+        ))),
+    }
+
+    let after_end = (end + CONTEXT_LINES).min(lines.len());
+    result.extend(
+        lines[end..after_end]
+            .iter()
+            .map(|l| highlight(&mut code, l, None)),
+    );
+    result
+}
+
+/// Render the result lines of the current conflict under a title naming its resolution
+fn render_result(
+    frame: &mut Frame,
+    file: &ConflictedFile,
+    keymap: &Keymap,
+    conflict_index: usize,
+    result: Vec<Line<'static>>,
+    area: Rect,
+) {
+    let resolution = file
+        .resolutions
+        .get(conflict_index)
+        .and_then(|r| r.as_ref());
+    let title = match (resolution, file.auto_resolution(conflict_index)) {
+        (Some(resolution), Some(reason)) => format!(
+            " Result  •  {}  •  auto: {}{} ",
+            resolution.as_str(),
+            reason.as_str(),
+            match keymap.accept_auto_hint() {
+                Some(hint) => format!(" ({})", hint),
+                None => String::new(),
+            }
+        ),
+        (Some(resolution), None) => format!(" Result  •  {} ", resolution.as_str()),
+        (None, _) => " Result ".to_string(),
+    };
+    let block = Block::default()
+        .borders(Borders::TOP)
+        .border_style(Style::default().fg(theme().text_dim))
+        .title(Span::styled(
+            title,
+            Style::default()
+                .fg(theme().status_resolved)
+                .add_modifier(Modifier::BOLD),
+        ));
+
+    let paragraph = Paragraph::new(result)
+        .block(block)
+        .style(Style::default().bg(theme().code_bg));
+    frame.render_widget(paragraph, area);
+}
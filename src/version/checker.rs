@@ -15,12 +15,9 @@ pub struct UpdateInfo {
 
 /// Check for updates in a non-blocking way
 /// Returns Some(UpdateInfo) if a newer version is available, None otherwise
+///
+/// Callers skip it when the user turned update checks off (`Config::update_check`)
 pub fn check_for_updates() -> Option<UpdateInfo> {
-    // Check if update check is disabled
-    if std::env::var("SAKI_NO_UPDATE_CHECK").is_ok() {
-        return None;
-    }
-
     let current_version = env!("CARGO_PKG_VERSION").to_string();
 
     // Spawn a thread to check for updates with a timeout